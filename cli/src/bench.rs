@@ -0,0 +1,120 @@
+use color_eyre::eyre::{Context, Result};
+use gadget_benchmarking::{
+    BenchmarkReport, BenchmarkSummary, BENCHMARK_TEST_PREFIX, OUTPUT_DIR_ENV,
+};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Benchmarks failed with {0}")]
+    BenchmarksFailed(std::process::ExitStatus),
+    #[error(
+        "No benchmarks found, annotate a function in the blueprint's library with `#[benchmark]`"
+    )]
+    NoBenchmarks,
+    #[error("Failed to read the benchmark summary at `{}`: {1}", .0.display())]
+    InvalidSummary(PathBuf, serde_json::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct Opts {
+    /// The name of the package to benchmark (if the workspace has multiple packages)
+    pub pkg_name: Option<String>,
+    /// The path to the manifest file
+    pub manifest_path: PathBuf,
+    /// Only run the benchmarks whose name starts with this filter
+    pub filter: Option<String>,
+    /// Where to write the report, `<target dir>/blueprint-benchmarks.json` by default
+    pub output: Option<PathBuf>,
+}
+
+/// Run all benchmarks of a blueprint, writing a [`BenchmarkReport`] as JSON
+///
+/// Every `#[benchmark]` generates an ignored test, so the benchmarks are discovered and run by
+/// `cargo test`, one at a time so they don't skew each other's measurements.
+///
+/// # Errors
+///
+/// * The benchmarks failed to build or run
+/// * No benchmarks were found
+/// * The report could not be written
+pub fn run_benchmarks(
+    Opts {
+        pkg_name,
+        manifest_path,
+        filter,
+        output,
+    }: Opts,
+) -> Result<(PathBuf, BenchmarkReport)> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(&manifest_path)
+        .no_deps()
+        .exec()
+        .context("Getting Metadata about the workspace")?;
+
+    let target_dir = metadata.target_directory.into_std_path_buf();
+    let summaries_dir = target_dir.join("blueprint-benchmarks");
+    if summaries_dir.exists() {
+        std::fs::remove_dir_all(&summaries_dir)
+            .context("Removing the summaries of previous benchmarks")?;
+    }
+
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let mut command = Command::new(cargo);
+    command
+        .args(["test", "--release", "--lib", "--manifest-path"])
+        .arg(&manifest_path);
+    if let Some(pkg_name) = &pkg_name {
+        command.args(["--package", pkg_name]);
+    }
+    // The generated tests are named after their benchmarks, behind a common prefix
+    command
+        .args(["--", "--ignored", "--test-threads=1", "--nocapture"])
+        .arg(format!(
+            "{BENCHMARK_TEST_PREFIX}{}",
+            filter.as_deref().unwrap_or_default()
+        ))
+        .env(OUTPUT_DIR_ENV, &summaries_dir);
+
+    tracing::info!("Running benchmarks...");
+    let status = command.status().context("Running `cargo test`")?;
+    if !status.success() {
+        return Err(Error::BenchmarksFailed(status).into());
+    }
+
+    let report = collect_summaries(&summaries_dir)?;
+    let output = output.unwrap_or_else(|| target_dir.join("blueprint-benchmarks.json"));
+    report
+        .save(&output)
+        .with_context(|| format!("Writing the benchmark report to `{}`", output.display()))?;
+
+    Ok((output, report))
+}
+
+/// Read the summaries written by the benchmarks in `dir`, ordered by job ID and name
+fn collect_summaries(dir: &Path) -> Result<BenchmarkReport> {
+    if !dir.exists() {
+        return Err(Error::NoBenchmarks.into());
+    }
+
+    let mut benchmarks = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let json = std::fs::read_to_string(&path)?;
+        let summary: BenchmarkSummary =
+            serde_json::from_str(&json).map_err(|e| Error::InvalidSummary(path.clone(), e))?;
+        benchmarks.push(summary);
+    }
+
+    if benchmarks.is_empty() {
+        return Err(Error::NoBenchmarks.into());
+    }
+
+    benchmarks.sort_by(|a, b| a.job_id.cmp(&b.job_id).then_with(|| a.name.cmp(&b.name)));
+    Ok(BenchmarkReport { benchmarks })
+}
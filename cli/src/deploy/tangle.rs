@@ -3,7 +3,7 @@ use alloy_provider::{Provider, WsConnect};
 use alloy_rpc_types_eth::TransactionRequest;
 use alloy_signer_local::PrivateKeySigner;
 use color_eyre::eyre::{self, Context, ContextCompat, Result};
-use gadget_blueprint_proc_macro_core::{BlueprintManager, ServiceBlueprint};
+use gadget_blueprint_proc_macro_core::{BlueprintManager, FieldType, ServiceBlueprint};
 use gadget_crypto::tangle_pair_signer::TanglePairSigner;
 use gadget_std::fmt::Debug;
 use gadget_std::path::PathBuf;
@@ -54,6 +54,8 @@ pub enum Error {
     NoPackageFound,
     #[error("The workspace has multiple packages, please specify the package to deploy")]
     ManyPackages,
+    #[error("Job {job_id} uses the enum `{name}` with data, which the Tangle runtime's `FieldType` cannot represent. Use an enum with only unit variants, or a struct, instead")]
+    UnsupportedEnumField { job_id: u64, name: String },
 
     #[error("{0}")]
     Io(#[from] std::io::Error),
@@ -309,6 +311,18 @@ fn build_contracts_if_needed(
 fn bake_blueprint(
     blueprint: ServiceBlueprint,
 ) -> Result<TangleApi::runtime_types::tangle_primitives::services::ServiceBlueprint> {
+    // The on-chain `FieldType` has no enum variant, so the blueprint would fail to decode below. Enums
+    // with only unit variants are described as strings, so only enums with data end up here
+    for job in &blueprint.jobs {
+        if let Some(name) = job.params.iter().chain(&job.result).find_map(find_enum) {
+            return Err(Error::UnsupportedEnumField {
+                job_id: job.job_id,
+                name: name.to_string(),
+            }
+            .into());
+        }
+    }
+
     let mut blueprint_json = serde_json::to_value(&blueprint)?;
     convert_to_bytes_or_null(&mut blueprint_json["metadata"]["name"]);
     convert_to_bytes_or_null(&mut blueprint_json["metadata"]["description"]);
@@ -372,6 +386,17 @@ fn bake_blueprint(
     Ok(blueprint)
 }
 
+/// Returns the name of the first enum in `field_type`
+fn find_enum(field_type: &FieldType) -> Option<&str> {
+    match field_type {
+        FieldType::Enum(name, _) => Some(name.as_str()),
+        FieldType::Optional(ty) | FieldType::Array(_, ty) | FieldType::List(ty) => find_enum(ty),
+        FieldType::Struct(_, fields) => fields.iter().find_map(|(_, ty)| find_enum(ty)),
+        FieldType::Tuple(tys) => tys.iter().find_map(find_enum),
+        _ => None,
+    }
+}
+
 /// Recursively converts a JSON string (or array of JSON strings) to bytes.
 ///
 /// Empty strings are converted to nulls.
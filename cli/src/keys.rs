@@ -1,6 +1,6 @@
 use color_eyre::eyre::Result;
 use gadget_crypto::sp_core::{SpBls377, SpBls381, SpEcdsa, SpEd25519, SpSr25519};
-use gadget_crypto::{bn254::ArkBlsBn254, KeyTypeId};
+use gadget_crypto::{bn254::ArkBlsBn254, p256::P256Ecdsa, KeyTypeId};
 use gadget_crypto_core::KeyEncoding;
 use gadget_keystore::{backends::Backend, Keystore, KeystoreConfig};
 use std::path::Path;
@@ -17,6 +17,7 @@ pub fn generate_key(
     key_type: KeyTypeId,
     output: Option<&impl AsRef<Path>>,
     seed: Option<&[u8]>,
+    password: Option<&str>,
     show_secret: bool,
 ) -> Result<(String, Option<String>)> {
     // Create keystore configuration
@@ -24,6 +25,9 @@ pub fn generate_key(
     if let Some(path) = output {
         config = config.fs_root(path);
     }
+    if let Some(password) = password {
+        config = config.password(password);
+    }
 
     let keystore = Keystore::new(config)?;
 
@@ -59,6 +63,14 @@ pub fn generate_key(
             let secret = keystore.get_secret::<ArkBlsBn254>(&public)?;
             (public.to_bytes(), secret.to_bytes())
         }
+        KeyTypeId::P256 => {
+            let public = keystore.generate::<P256Ecdsa>(seed)?;
+            let secret = keystore.get_secret::<P256Ecdsa>(&public)?;
+            (public.to_bytes(), secret.to_bytes())
+        }
+        // Threshold key shares come from distributed key generation, not a single keypair
+        #[allow(unreachable_patterns)]
+        key_type => return Err(Error::UnknownKeyType(key_type.name().to_string()).into()),
     };
 
     let (public, secret) = (hex::encode(public_bytes), hex::encode(secret_bytes));
@@ -70,3 +82,14 @@ pub fn generate_key(
 
     Ok((public, secret))
 }
+
+/// Reads a keystore password from `path`, removing a single trailing newline
+pub fn read_password(path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path)?;
+    let password = contents
+        .strip_suffix('\n')
+        .map_or(contents.as_str(), |password| {
+            password.strip_suffix('\r').unwrap_or(password)
+        });
+    Ok(password.to_string())
+}
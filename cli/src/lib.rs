@@ -1,3 +1,4 @@
+pub mod bench;
 pub mod create;
 pub mod deploy;
 pub mod foundry;
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use crate::deploy::tangle::{deploy_to_tangle, Opts};
 use cargo_tangle::create::BlueprintType;
-use cargo_tangle::{create, deploy, keys};
+use cargo_tangle::{bench, create, deploy, keys};
 use clap::{Parser, Subcommand};
 use gadget_crypto::KeyTypeId;
 
@@ -72,6 +72,21 @@ pub enum GadgetCommands {
         #[arg(short, long, value_name = "PACKAGE", env = "CARGO_PACKAGE")]
         package: Option<String>,
     },
+    /// Run the benchmarks of a blueprint, writing their results as JSON
+    ///
+    /// The report can be used to derive the price targets an operator registers with.
+    #[command(visible_alias = "b")]
+    Bench {
+        /// The package to benchmark (if the workspace has multiple packages).
+        #[arg(short, long, value_name = "PACKAGE", env = "CARGO_PACKAGE")]
+        package: Option<String>,
+        /// Only run the benchmarks whose name starts with this filter
+        #[arg(value_name = "FILTER")]
+        filter: Option<String>,
+        /// Where to write the report, `<target dir>/blueprint-benchmarks.json` by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Generate a key
     Keygen {
         /// The type of key to generate
@@ -91,6 +106,20 @@ pub enum GadgetCommands {
         /// If true, the secret key will be printed along with the public key
         #[arg(long)]
         show_secret: bool,
+
+        /// Encrypt the saved key with this password
+        #[arg(long, env, requires = "path")]
+        keystore_password: Option<String>,
+
+        /// A file to read the password that encrypts the saved key from
+        #[arg(
+            long,
+            value_name = "PATH",
+            env,
+            requires = "path",
+            conflicts_with = "keystore_password"
+        )]
+        keystore_password_file: Option<PathBuf>,
     },
 }
 
@@ -142,15 +171,51 @@ async fn main() -> color_eyre::Result<()> {
                 })
                 .await?;
             }
+            GadgetCommands::Bench {
+                package,
+                filter,
+                output,
+            } => {
+                let manifest_path = cli
+                    .manifest
+                    .manifest_path
+                    .unwrap_or_else(|| PathBuf::from("Cargo.toml"));
+                let (output, report) = bench::run_benchmarks(bench::Opts {
+                    pkg_name: package,
+                    manifest_path,
+                    filter,
+                    output,
+                })?;
+
+                for summary in &report.benchmarks {
+                    eprintln!("{summary}");
+                }
+                eprintln!(
+                    "Wrote {} benchmark(s) to {}",
+                    report.benchmarks.len(),
+                    output.display()
+                );
+            }
             GadgetCommands::Keygen {
                 key_type,
                 path,
                 seed,
                 show_secret,
+                keystore_password,
+                keystore_password_file,
             } => {
                 let seed = seed.map(hex::decode).transpose()?;
-                let (public, secret) =
-                    keys::generate_key(key_type, path.as_ref(), seed.as_deref(), show_secret)?;
+                let password = match keystore_password_file {
+                    Some(file) => Some(keys::read_password(&file)?),
+                    None => keystore_password,
+                };
+                let (public, secret) = keys::generate_key(
+                    key_type,
+                    path.as_ref(),
+                    seed.as_deref(),
+                    password.as_deref(),
+                    show_secret,
+                )?;
 
                 eprintln!("Generated {} key:", key_type.name());
                 eprintln!("Public key: {}", public);
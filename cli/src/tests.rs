@@ -2,6 +2,7 @@ use crate::keys::generate_key;
 use crate::signer::{load_evm_signer_from_env, load_signer_from_env, EVM_SIGNER_ENV, SIGNER_ENV};
 use color_eyre::eyre::Result;
 use gadget_crypto::bn254::ArkBlsBn254;
+use gadget_crypto::p256::P256Ecdsa;
 use gadget_crypto::sp_core::{SpBls381, SpEcdsa, SpEd25519, SpSr25519};
 use gadget_crypto_core::KeyTypeId;
 use gadget_keystore::backends::Backend;
@@ -22,11 +23,12 @@ fn test_cli_fs_key_generation() -> Result<()> {
         KeyTypeId::Ecdsa,
         KeyTypeId::Bls381,
         KeyTypeId::Bn254,
+        KeyTypeId::P256,
     ]
     .iter()
     {
         println!("Testing key generation for: {:?}", key_type);
-        let (public, secret) = generate_key(*key_type, Some(&output_path), None, true)?;
+        let (public, secret) = generate_key(*key_type, Some(&output_path), None, None, true)?;
         assert!(!public.is_empty());
         assert!(secret.is_some());
         assert!(!secret.unwrap().is_empty());
@@ -48,12 +50,35 @@ fn test_cli_fs_key_generation() -> Result<()> {
             KeyTypeId::Bn254 => {
                 keystore.first_local::<ArkBlsBn254>()?;
             }
+            KeyTypeId::P256 => {
+                keystore.first_local::<P256Ecdsa>()?;
+            }
             _ => unreachable!(),
         }
     }
     Ok(())
 }
 
+#[test]
+fn test_cli_encrypted_key_generation() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let output_path = temp_dir.path();
+
+    generate_key(
+        KeyTypeId::Ecdsa,
+        Some(&output_path),
+        None,
+        Some("hunter2"),
+        false,
+    )?;
+
+    let keystore = Keystore::new(KeystoreConfig::new().fs_root(output_path).password("hunter2"))?;
+    keystore.first_local::<SpEcdsa>()?;
+
+    assert!(Keystore::new(KeystoreConfig::new().fs_root(output_path).password("wrong")).is_err());
+    Ok(())
+}
+
 #[test]
 fn test_cli_mem_key_generation() -> Result<()> {
     for key_type in [
@@ -62,11 +87,12 @@ fn test_cli_mem_key_generation() -> Result<()> {
         KeyTypeId::Ecdsa,
         KeyTypeId::Bls381,
         KeyTypeId::Bn254,
+        KeyTypeId::P256,
     ]
     .iter()
     {
         println!("Testing key generation for: {:?}", key_type);
-        let (public, secret) = generate_key(*key_type, None::<&PathBuf>, None, true)?;
+        let (public, secret) = generate_key(*key_type, None::<&PathBuf>, None, None, true)?;
         assert!(!public.is_empty());
         assert!(secret.is_some());
         assert!(!secret.unwrap().is_empty());
@@ -1,5 +1,24 @@
 pub use tokio;
 
+#[cfg(feature = "std")]
+mod resources;
+mod summary;
+
+pub use summary::{BenchmarkReport, BenchmarkSummary, DurationStats};
+
+use gadget_std::string::String;
+use gadget_std::time::Duration;
+use gadget_std::vec::Vec;
+
+/// The number of times [`Bencher::iter`] runs a benchmark, unless configured otherwise.
+pub const DEFAULT_ITERATIONS: usize = 10;
+
+/// The environment variable holding the directory [`record`] writes benchmark summaries to.
+pub const OUTPUT_DIR_ENV: &str = "BLUEPRINT_BENCHMARK_OUTPUT";
+
+/// The prefix of the names of the tests generated by the `#[benchmark]` macro.
+pub const BENCHMARK_TEST_PREFIX: &str = "__blueprint_benchmark_";
+
 /// The runtime trait that all runtimes must implement.
 pub trait Runtime {
     /// Runs the given future to completion on the runtime.
@@ -34,23 +53,13 @@ pub struct Bencher<R> {
     started_at: gadget_std::time::Instant,
     /// The max number of cores for this benchmark.
     cores: usize,
-}
-
-/// The results of a benchmark.
-///
-/// This implements [`Display`] to provide a human-readable summary of the benchmark.
-#[derive(Debug, Clone)]
-pub struct BenchmarkSummary {
-    /// The name of the benchmark.
-    pub name: String,
-    /// The job identifier.
-    pub job_id: u8,
-    /// The duration of the benchmark.
-    pub elapsed: gadget_std::time::Duration,
-    /// The number of cores the benchmark was run with.
-    pub cores: usize,
-    /// The amount of memory used by the benchmark (in bytes).
-    pub ram_usage: u64,
+    /// The number of times [`Bencher::iter`] runs the benchmark.
+    iterations: usize,
+    /// The duration of each iteration run so far.
+    samples: Vec<Duration>,
+    /// Samples the resources used while the benchmark runs.
+    #[cfg(feature = "std")]
+    monitor: resources::ResourceMonitor,
 }
 
 impl<R: Runtime> Bencher<R> {
@@ -70,9 +79,28 @@ impl<R: Runtime> Bencher<R> {
             runtime,
             started_at: gadget_std::time::Instant::now(),
             cores: threads,
+            iterations: DEFAULT_ITERATIONS,
+            samples: Vec::new(),
+            #[cfg(feature = "std")]
+            monitor: resources::ResourceMonitor::start(),
         }
     }
 
+    /// Set the number of times [`Bencher::iter`] runs the benchmark, [`DEFAULT_ITERATIONS`] by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gadget_benchmarking::{Bencher, TokioRuntime};
+    ///
+    /// let bencher = Bencher::new(4, TokioRuntime).with_iterations(100);
+    /// ```
+    #[must_use]
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
     /// Runs the given future on the [`Runtime`].
     ///
     /// # Examples
@@ -94,11 +122,33 @@ impl<R: Runtime> Bencher<R> {
         self.runtime.block_on(future)
     }
 
-    /// Ends the benchmark and returns a summary.
+    /// Runs the future returned by `f` on the [`Runtime`] once per iteration, timing each run.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use gadget_benchmarking::{Bencher, TokioRuntime};
     ///
-    /// # Panics
+    /// let mut bencher = Bencher::new(4, TokioRuntime).with_iterations(10);
+    /// bencher.iter(|| async {
+    ///     // Do some work...
+    /// });
+    /// ```
+    pub fn iter<F, Fut>(&mut self, mut f: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: gadget_std::future::Future,
+    {
+        for _ in 0..self.iterations {
+            let started_at = gadget_std::time::Instant::now();
+            let _ = self.runtime.block_on(f());
+            self.samples.push(started_at.elapsed());
+        }
+    }
+
+    /// Ends the benchmark and returns a summary.
     ///
-    /// This will panic in the event it cannot determine the process ID.
+    /// Without any [`Bencher::iter`] runs, the whole benchmark counts as a single iteration.
     ///
     /// # Examples
     ///
@@ -115,44 +165,60 @@ impl<R: Runtime> Bencher<R> {
     /// println!("{}", summary);
     /// ```
     #[cfg(feature = "std")] // TODO: Benchmark execution time for WASM?
-    pub fn stop<N: ToString>(&self, name: &N, job_id: u8) -> BenchmarkSummary {
-        let pid = sysinfo::get_current_pid().expect("Failed to get current process ID");
-        let s = sysinfo::System::new_all();
-        let process = s
-            .process(pid)
-            .expect("Failed to get current process from the system");
-        let ram_usage = process.memory();
+    pub fn stop(self, name: impl Into<String>, job_id: u8) -> BenchmarkSummary {
+        let elapsed = self.started_at.elapsed();
+        let usage = self.monitor.stop();
+
+        let samples = if self.samples.is_empty() {
+            vec![elapsed]
+        } else {
+            self.samples
+        };
+
         BenchmarkSummary {
-            name: name.to_string(),
+            name: name.into(),
             job_id,
-            elapsed: self.started_at.elapsed(),
+            elapsed,
             cores: self.cores,
-            ram_usage,
+            iterations: samples.len(),
+            durations: DurationStats::from_samples(&samples),
+            cpu_time: usage.cpu_time,
+            ram_usage: usage.ram_usage,
+            peak_memory: usage.peak_memory,
+            disk_read: usage.disk_read,
+            disk_written: usage.disk_written,
+            network_received: usage.network_received,
+            network_transmitted: usage.network_transmitted,
         }
     }
 }
 
-impl gadget_std::fmt::Display for BenchmarkSummary {
-    #[allow(clippy::cast_precision_loss)]
-    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
-        const KB: f32 = 1024.00;
-        const MB: f32 = 1024.00 * KB;
-        const GB: f32 = 1024.00 * MB;
-        let ram_usage = self.ram_usage as f32;
-        let (ram_usage, unit) = if ram_usage < KB {
-            (ram_usage, "B")
-        } else if ram_usage < MB {
-            (ram_usage / KB, "KB")
-        } else if ram_usage < GB {
-            (ram_usage / MB, "MB")
-        } else {
-            (ram_usage / GB, "GB")
-        };
+/// Print `summary`, and write it as JSON to the directory in [`OUTPUT_DIR_ENV`] if it is set.
+///
+/// This is called by the tests generated by the `#[benchmark]` macro, which is how
+/// `cargo tangle blueprint bench` collects their results. The file is named after the summary's job
+/// ID, the `module_path` of the benchmark and its name, so benchmarks with the same name in different
+/// modules don't overwrite each other.
+///
+/// # Errors
+///
+/// * The summary could not be written
+#[cfg(feature = "std")]
+pub fn record(summary: &BenchmarkSummary, module_path: &str) -> std::io::Result<()> {
+    println!("{summary}");
 
-        write!(
-            f,
-            "Benchmark: {}\nJob ID: {}\nElapsed: {:?}\nvCPU: {}\nRAM Usage: {ram_usage:.2} {unit}\n",
-            self.name, self.job_id, self.elapsed, self.cores,
-        )
-    }
+    let Some(dir) = std::env::var_os(OUTPUT_DIR_ENV) else {
+        return Ok(());
+    };
+
+    let dir = std::path::PathBuf::from(dir);
+    std::fs::create_dir_all(&dir)?;
+    let json = serde_json::to_string_pretty(summary)?;
+    let file_name = format!(
+        "{}-{}-{}.json",
+        summary.job_id,
+        module_path.replace("::", "-"),
+        summary.name
+    );
+    std::fs::write(dir.join(file_name), json)
 }
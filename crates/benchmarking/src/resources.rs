@@ -0,0 +1,142 @@
+use gadget_std::sync::atomic::{AtomicBool, Ordering};
+use gadget_std::sync::Arc;
+use gadget_std::thread::{self, JoinHandle};
+use gadget_std::time::Duration;
+use sysinfo::{Networks, Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// How often the resources of the process are sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The resources used by the process while a [`ResourceMonitor`] was running
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ResourceUsage {
+    pub cpu_time: Duration,
+    pub ram_usage: u64,
+    pub peak_memory: u64,
+    pub disk_read: u64,
+    pub disk_written: u64,
+    pub network_received: u64,
+    pub network_transmitted: u64,
+}
+
+/// Samples the resources used by the current process on a background thread
+///
+/// CPU time is the user and system time the OS accounts to the process, on Unix. Elsewhere it is
+/// estimated from the CPU usage of each sample. Network IO is measured for the whole system, as it
+/// isn't available per process on every platform.
+#[derive(Debug)]
+pub(crate) struct ResourceMonitor {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<ResourceUsage>,
+}
+
+impl ResourceMonitor {
+    /// Start sampling
+    ///
+    /// # Panics
+    ///
+    /// This will panic in the event it cannot determine the process ID.
+    pub(crate) fn start() -> Self {
+        let pid = sysinfo::get_current_pid().expect("Failed to get current process ID");
+        let stop = Arc::new(AtomicBool::new(false));
+        let cpu_time_at_start = process_cpu_time();
+
+        let handle = thread::spawn({
+            let stop = stop.clone();
+            move || sample_until(pid, &stop, cpu_time_at_start)
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Stop sampling, returning the resources used since [`ResourceMonitor::start`]
+    pub(crate) fn stop(self) -> ResourceUsage {
+        self.stop.store(true, Ordering::Release);
+        self.handle.join().unwrap_or_default()
+    }
+}
+
+fn sample_until(pid: Pid, stop: &AtomicBool, cpu_time_at_start: Option<Duration>) -> ResourceUsage {
+    let mut system = System::new();
+    let refresh_kind = ProcessRefreshKind::new()
+        .with_cpu()
+        .with_memory()
+        .with_disk_usage();
+    let refresh = |system: &mut System| {
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), refresh_kind);
+    };
+
+    let mut networks = Networks::new_with_refreshed_list();
+    let (received_at_start, transmitted_at_start) = network_totals(&networks);
+
+    refresh(&mut system);
+    let (read_at_start, written_at_start) = system
+        .process(pid)
+        .map(|process| {
+            let disk = process.disk_usage();
+            (disk.total_read_bytes, disk.total_written_bytes)
+        })
+        .unwrap_or_default();
+
+    let mut usage = ResourceUsage::default();
+    let mut estimated_cpu_time = Duration::ZERO;
+    loop {
+        let stopping = stop.load(Ordering::Acquire);
+        if !stopping {
+            thread::sleep(SAMPLE_INTERVAL);
+        }
+
+        refresh(&mut system);
+        if let Some(process) = system.process(pid) {
+            let disk = process.disk_usage();
+            usage.ram_usage = process.memory();
+            usage.peak_memory = usage.peak_memory.max(usage.ram_usage);
+            estimated_cpu_time += SAMPLE_INTERVAL.mul_f32(process.cpu_usage() / 100.0);
+            usage.disk_read = disk.total_read_bytes.saturating_sub(read_at_start);
+            usage.disk_written = disk.total_written_bytes.saturating_sub(written_at_start);
+        }
+
+        if stopping {
+            break;
+        }
+    }
+
+    usage.cpu_time = match (cpu_time_at_start, process_cpu_time()) {
+        (Some(start), Some(end)) => end.saturating_sub(start),
+        _ => estimated_cpu_time,
+    };
+
+    networks.refresh();
+    let (received, transmitted) = network_totals(&networks);
+    usage.network_received = received.saturating_sub(received_at_start);
+    usage.network_transmitted = transmitted.saturating_sub(transmitted_at_start);
+
+    usage
+}
+
+/// The user and system CPU time used by all threads of the process so far
+#[cfg(unix)]
+fn process_cpu_time() -> Option<Duration> {
+    use nix::sys::resource::{getrusage, UsageWho};
+    use nix::sys::time::TimeValLike;
+
+    let usage = getrusage(UsageWho::RUSAGE_SELF).ok()?;
+    let micros = usage.user_time().num_microseconds() + usage.system_time().num_microseconds();
+    u64::try_from(micros).ok().map(Duration::from_micros)
+}
+
+#[cfg(not(unix))]
+fn process_cpu_time() -> Option<Duration> {
+    None
+}
+
+fn network_totals(networks: &Networks) -> (u64, u64) {
+    networks
+        .iter()
+        .fold((0, 0), |(received, transmitted), (_, data)| {
+            (
+                received + data.total_received(),
+                transmitted + data.total_transmitted(),
+            )
+        })
+}
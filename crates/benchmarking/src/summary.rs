@@ -0,0 +1,239 @@
+use gadget_std::string::String;
+use gadget_std::time::Duration;
+use gadget_std::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// The results of a benchmark.
+///
+/// This implements [`Display`] to provide a human-readable summary of the benchmark.
+///
+/// [`Display`]: gadget_std::fmt::Display
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkSummary {
+    /// The name of the benchmark.
+    pub name: String,
+    /// The job identifier.
+    pub job_id: u8,
+    /// The duration of the benchmark, over all iterations.
+    pub elapsed: Duration,
+    /// The number of cores the benchmark was run with.
+    pub cores: usize,
+    /// The number of times the benchmark was run.
+    pub iterations: usize,
+    /// The duration of a single iteration.
+    pub durations: DurationStats,
+    /// The CPU time used by the process over all iterations.
+    pub cpu_time: Duration,
+    /// The amount of memory used by the process when the benchmark ended (in bytes).
+    pub ram_usage: u64,
+    /// The highest amount of memory used by the process during the benchmark (in bytes).
+    pub peak_memory: u64,
+    /// The number of bytes read from disk by the process.
+    pub disk_read: u64,
+    /// The number of bytes written to disk by the process.
+    pub disk_written: u64,
+    /// The number of bytes received over all network interfaces.
+    ///
+    /// This is measured for the whole system, not only for the benchmark.
+    pub network_received: u64,
+    /// The number of bytes transmitted over all network interfaces.
+    ///
+    /// This is measured for the whole system, not only for the benchmark.
+    pub network_transmitted: u64,
+}
+
+/// Statistics over the durations of the iterations of a benchmark
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DurationStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+}
+
+impl DurationStats {
+    /// Compute the statistics of `samples`
+    ///
+    /// Percentiles use the nearest-rank method. No samples results in all zeroes.
+    #[must_use]
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let total: Duration = sorted.iter().sum();
+        let count = u32::try_from(sorted.len()).unwrap_or(u32::MAX);
+        let percentile = |p: usize| sorted[(p * sorted.len()).div_ceil(100).max(1) - 1];
+
+        Self {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: total / count,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+        }
+    }
+}
+
+/// The summaries of all benchmarks of a blueprint
+///
+/// This is the JSON written by `cargo tangle blueprint bench`, and can be used to derive the
+/// price targets of an operator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub benchmarks: Vec<BenchmarkSummary>,
+}
+
+impl BenchmarkReport {
+    /// The highest number of cores used by any benchmark
+    #[must_use]
+    pub fn max_cores(&self) -> usize {
+        self.benchmarks.iter().map(|b| b.cores).max().unwrap_or(0)
+    }
+
+    /// The highest peak memory of any benchmark (in bytes)
+    #[must_use]
+    pub fn max_peak_memory(&self) -> u64 {
+        self.benchmarks
+            .iter()
+            .map(|b| b.peak_memory)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The most bytes written to disk by any benchmark
+    #[must_use]
+    pub fn max_disk_written(&self) -> u64 {
+        self.benchmarks
+            .iter()
+            .map(|b| b.disk_written)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Encode the report as JSON
+    ///
+    /// # Errors
+    ///
+    /// See [`serde_json::to_string_pretty`]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Decode a report from JSON
+    ///
+    /// # Errors
+    ///
+    /// See [`serde_json::from_str`]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Read a report from the JSON file at `path`
+    ///
+    /// # Errors
+    ///
+    /// * The file could not be read
+    /// * The file does not contain a valid report
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_json(&json).map_err(std::io::Error::from)
+    }
+
+    /// Write the report as JSON to `path`
+    ///
+    /// # Errors
+    ///
+    /// * The file could not be written
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json()?)
+    }
+}
+
+impl gadget_std::fmt::Display for BenchmarkSummary {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        writeln!(f, "Benchmark: {}", self.name)?;
+        writeln!(f, "Job ID: {}", self.job_id)?;
+        writeln!(f, "Iterations: {}", self.iterations)?;
+        writeln!(f, "Elapsed: {:?}", self.elapsed)?;
+        writeln!(
+            f,
+            "Iteration: min {:?}, mean {:?}, p50 {:?}, p90 {:?}, p99 {:?}, max {:?}",
+            self.durations.min,
+            self.durations.mean,
+            self.durations.p50,
+            self.durations.p90,
+            self.durations.p99,
+            self.durations.max,
+        )?;
+        writeln!(f, "vCPU: {}", self.cores)?;
+        writeln!(f, "CPU Time: {:?}", self.cpu_time)?;
+        writeln!(f, "RAM Usage: {}", Bytes(self.ram_usage))?;
+        writeln!(f, "Peak RAM Usage: {}", Bytes(self.peak_memory))?;
+        writeln!(
+            f,
+            "Disk: {} read, {} written",
+            Bytes(self.disk_read),
+            Bytes(self.disk_written)
+        )?;
+        writeln!(
+            f,
+            "Network: {} received, {} transmitted",
+            Bytes(self.network_received),
+            Bytes(self.network_transmitted)
+        )
+    }
+}
+
+struct Bytes(u64);
+
+impl gadget_std::fmt::Display for Bytes {
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        const KB: f32 = 1024.00;
+        const MB: f32 = 1024.00 * KB;
+        const GB: f32 = 1024.00 * MB;
+        let bytes = self.0 as f32;
+        let (bytes, unit) = if bytes < KB {
+            (bytes, "B")
+        } else if bytes < MB {
+            (bytes / KB, "KB")
+        } else if bytes < GB {
+            (bytes / MB, "MB")
+        } else {
+            (bytes / GB, "GB")
+        };
+
+        write!(f, "{bytes:.2} {unit}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_stats() {
+        let samples: Vec<_> = (1..=100).rev().map(Duration::from_millis).collect();
+        let stats = DurationStats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.mean, Duration::from_micros(50_500));
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p90, Duration::from_millis(90));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+
+        let single = DurationStats::from_samples(&[Duration::from_secs(1)]);
+        assert_eq!(single.p50, Duration::from_secs(1));
+        assert_eq!(single.p99, Duration::from_secs(1));
+        assert_eq!(DurationStats::from_samples(&[]), DurationStats::default());
+    }
+}
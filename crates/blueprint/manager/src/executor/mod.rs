@@ -12,7 +12,7 @@ use gadget_config::GadgetConfiguration;
 use gadget_crypto::sp_core::{SpEcdsa, SpSr25519};
 use gadget_crypto::tangle_pair_signer::TanglePairSigner;
 use gadget_keystore::backends::Backend;
-use gadget_keystore::{Keystore, KeystoreConfig};
+use gadget_keystore::Keystore;
 use gadget_logging::info;
 use std::collections::HashMap;
 use std::future::Future;
@@ -177,7 +177,7 @@ pub async fn run_blueprint_manager<F: SendFuture<'static, ()>>(
 
     // TODO: Actual error handling
     let (tangle_key, ecdsa_key) = {
-        let keystore = Keystore::new(KeystoreConfig::new().fs_root(&gadget_config.keystore_uri))?;
+        let keystore = Keystore::new(gadget_config.keystore_config()?)?;
         let sr_key_pub = keystore.first_local::<SpSr25519>()?;
         let sr_pair = keystore.get_secret::<SpSr25519>(&sr_key_pub)?;
         let sr_key = TanglePairSigner::new(sr_pair.0);
@@ -54,11 +54,6 @@ pub fn process_arguments_and_env(
         ),
     ]);
 
-    // TODO: Add support for keystore password
-    // if let Some(keystore_password) = &gadget_config.keystore_password {
-    //     arguments.push(format!("--keystore-password={}", keystore_password));
-    // }
-
     // Uses occurrences of clap short -v
     if manager_opts.verbose > 0 {
         arguments.push(format!("-{}", "v".repeat(manager_opts.verbose as usize)));
@@ -89,8 +84,23 @@ pub fn process_arguments_and_env(
         data_dir.to_string_lossy().into_owned(),
     ));
 
+    // The keystore password is passed in the environment, since the arguments are visible to other
+    // processes. It replaces any other source of the password this process was given.
+    if let Some(keystore_password) = &gadget_config.keystore_password {
+        env_vars.push((
+            "KEYSTORE_PASSWORD".to_string(),
+            keystore_password.expose().to_string(),
+        ));
+    }
+
     // Ensure our child process inherits the current processes' environment vars
-    env_vars.extend(std::env::vars());
+    env_vars.extend(std::env::vars().filter(|(key, _)| {
+        gadget_config.keystore_password.is_none()
+            || !matches!(
+                key.as_str(),
+                "KEYSTORE_PASSWORD" | "KEYSTORE_PASSWORD_FILE" | "KEYSTORE_PASSWORD_FD"
+            )
+    }));
 
     if blueprint.registration_mode {
         env_vars.push(("REGISTRATION_MODE_ON".to_string(), "true".to_string()));
@@ -5,9 +5,9 @@ use gadget_std::process::{Command, Stdio};
 
 use cargo_metadata::{Metadata, Package};
 use gadget_blueprint_proc_macro_core::{
-    BlueprintManager, FieldType, Gadget, GadgetSource, GadgetSourceFetcher, JobDefinition,
-    MasterBlueprintServiceManagerRevision, NativeGadget, ServiceBlueprint, ServiceMetadata,
-    TestFetcher,
+    BlueprintManager, FieldType, FieldTypeDefinition, Gadget, GadgetSource, GadgetSourceFetcher,
+    JobDefinition, MasterBlueprintServiceManagerRevision, NativeGadget, ServiceBlueprint,
+    ServiceMetadata, TestFetcher,
 };
 
 use rustdoc_types::{Crate, Id, Item, ItemEnum, Module};
@@ -33,9 +33,23 @@ impl Config {
         });
         let krate = generate_rustdoc();
         // Extract the job definitions from the rustdoc output
-        let jobs = extract_jobs(&krate);
+        let mut jobs = extract_jobs(&krate);
         eprintln!("Extracted {} job definitions", jobs.len());
-        let hooks = extract_hooks(&krate);
+        let mut hooks = extract_hooks(&krate);
+
+        // Fill in the user-defined types that job definitions and hooks only name
+        let field_types = extract_field_types(&krate);
+        for job in &mut jobs {
+            for field_type in job.params.iter_mut().chain(&mut job.result) {
+                resolve_field_type(field_type, &field_types, &mut Vec::new());
+            }
+        }
+        for hook in &mut hooks {
+            let (Hook::RegistrationParams(params) | Hook::RequestParams(params)) = hook;
+            for field_type in params {
+                resolve_field_type(field_type, &field_types, &mut Vec::new());
+            }
+        }
         let metadata = extract_metadata();
         let crate_name = std::env::var("CARGO_PKG_NAME").expect("Failed to get package name");
         let package = find_package(&metadata, &crate_name);
@@ -157,6 +171,103 @@ fn extract_jobs_from_module<'a>(
     jobs
 }
 
+/// Extract the field types of the user-defined types deriving `BlueprintField` from the rustdoc output.
+fn extract_field_types(krate: &Crate) -> HashMap<String, FieldType> {
+    let root_module = krate
+        .index
+        .get(&krate.root)
+        .expect("Failed to get root module");
+    let ItemEnum::Module(blueprint_crate) = &root_module.inner else {
+        panic!("Failed to get blueprint crate module");
+    };
+    let mut field_types = HashMap::new();
+    extract_field_types_from_module(&krate.index, blueprint_crate, &mut field_types);
+    field_types
+}
+
+/// Extracts field type definitions from a module.
+fn extract_field_types_from_module(
+    index: &HashMap<Id, Item>,
+    module: &Module,
+    field_types: &mut HashMap<String, FieldType>,
+) {
+    let automatically_derived: String = String::from("#[automatically_derived]");
+    const FIELD_TYPE: &str = "_FIELD_TYPE";
+
+    for item_id in &module.items {
+        let item = index.get(item_id).expect("Failed to get item");
+        match &item.inner {
+            ItemEnum::Module(m) => extract_field_types_from_module(index, m, field_types),
+            ItemEnum::Constant { const_: c, .. }
+                if item.attrs.contains(&automatically_derived)
+                    && item
+                        .name
+                        .as_ref()
+                        .map(|v| v.ends_with(FIELD_TYPE))
+                        .unwrap_or(false) =>
+            {
+                let definition: FieldTypeDefinition =
+                    serde_json::from_str(&unescape_json_string(&c.expr))
+                        .expect("Failed to deserialize field type definition");
+                let previous = field_types.insert(definition.name.clone(), definition.field_type);
+                assert!(
+                    previous.is_none(),
+                    "Multiple types named `{}` derive `BlueprintField`, rename one of them",
+                    definition.name
+                );
+            }
+            _ => continue,
+        }
+    }
+}
+
+/// Replaces the user-defined types named in `field_type`, which appear as structs with no fields,
+/// with their definitions.
+///
+/// `resolving` holds the types currently being resolved, to reject recursive types.
+fn resolve_field_type(
+    field_type: &mut FieldType,
+    definitions: &HashMap<String, FieldType>,
+    resolving: &mut Vec<String>,
+) {
+    match field_type {
+        FieldType::Struct(name, fields) if fields.is_empty() => {
+            let Some(definition) = definitions.get(name.as_str()) else {
+                eprintln!("No `BlueprintField` definition found for `{name}`, leaving it empty");
+                return;
+            };
+            assert!(
+                !resolving.contains(name),
+                "`{name}` is recursive, which can't be described by a `FieldType`"
+            );
+
+            let name = name.clone();
+            let mut definition = definition.clone();
+            // A struct with no fields is its own definition
+            if definition != *field_type {
+                resolving.push(name);
+                resolve_field_type(&mut definition, definitions, resolving);
+                let _ = resolving.pop();
+            }
+            *field_type = definition;
+        }
+        FieldType::Struct(_, fields) | FieldType::Enum(_, fields) => {
+            for (_, field) in fields {
+                resolve_field_type(field, definitions, resolving);
+            }
+        }
+        FieldType::Optional(inner) | FieldType::Array(_, inner) | FieldType::List(inner) => {
+            resolve_field_type(inner, definitions, resolving);
+        }
+        FieldType::Tuple(elems) => {
+            for elem in elems {
+                resolve_field_type(elem, definitions, resolving);
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Extracts hooks from a module.
 fn extract_hooks_from_module(_root: &Id, index: &HashMap<Id, Item>, module: &Module) -> Vec<Hook> {
     let mut hooks = vec![];
@@ -1,6 +1,7 @@
 use crate::error::{Error, Result, UnsupportedType};
 use crate::Field;
 use alloc::collections::BTreeMap;
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use serde::de::IntoDeserializer;
@@ -163,11 +164,26 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     where
         V: de::Visitor<'de>,
     {
-        // Only accept unit variants
         match self.0 {
+            // Unit variants
             Field::String(bound_string) => {
                 visitor.visit_enum(String::from_utf8(bound_string.0 .0)?.into_deserializer())
             }
+            // Variants with data, see `ser::variant_field`
+            Field::Struct(name, fields) if fields.0.len() == 1 => {
+                let name = String::from_utf8(name.0 .0)?;
+                let Some((variant, value)) = fields.0.into_iter().next() else {
+                    unreachable!("length checked above");
+                };
+                let variant = String::from_utf8(variant.0 .0)?;
+                if name.split_once("::").map(|(_, v)| v) != Some(variant.as_str()) {
+                    return Err(Error::Other(format!(
+                        "expected an enum variant named `Enum::{variant}`, found `{name}`"
+                    )));
+                }
+
+                visitor.visit_enum(EnumDeserializer { variant, value })
+            }
             _ => Err(self.invalid_type(&visitor)),
         }
     }
@@ -220,6 +236,61 @@ impl Deserializer {
     }
 }
 
+struct EnumDeserializer {
+    variant: String,
+    value: Field<AccountId32>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantDeserializer(self.value)))
+    }
+}
+
+struct VariantDeserializer(Field<AccountId32>);
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.0 {
+            Field::None => Ok(()),
+            field => Err(Deserializer(field).invalid_type(&"unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(Deserializer(self.0))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple_struct(Deserializer(self.0), "", len, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Field::Struct(_, fields) => visit_struct(*fields, visitor),
+            field => Err(Deserializer(field).invalid_type(&"struct variant")),
+        }
+    }
+}
+
 struct StructDeserializer {
     iter: <BTreeMap<String, Field<AccountId32>> as IntoIterator>::IntoIter,
     field: Option<Field<AccountId32>>,
@@ -15,31 +15,9 @@ pub enum UnsupportedType {
     f64,
     Map,
     /// Any enum that is not a simple unit enum
-    ///
-    /// ## Valid
-    ///
-    /// ```rust
-    /// enum Foo {
-    ///     Bar,
-    ///     Baz,
-    /// }
-    /// ```
-    ///
-    /// ## Invalid
-    ///
-    /// ```rust
-    /// enum Foo {
-    ///     Bar(String),
-    /// }
-    /// ```
-    ///
-    /// Or
-    ///
-    /// ```rust
-    /// enum Foo {
-    ///     Baz { a: String },
-    /// }
-    /// ```
+    #[deprecated(
+        note = "enums with data are supported, and are encoded as described in the crate docs"
+    )]
     NonUnitEnum,
 }
 
@@ -41,11 +41,44 @@
 //! let person_deserialized: Person = gadget_blueprint_serde::from_field(field).unwrap();
 //! assert_eq!(person, person_deserialized);
 //! ```
+//!
+//! # Enums
+//!
+//! Unit variants are encoded as a [`Field::String`] of the variant name. Since [`Field`] has no enum
+//! representation, variants with data are encoded as a [`Field::Struct`] named `Enum::Variant`, with a
+//! single field named after the variant. That field holds the variant's data, encoded like a newtype,
+//! tuple struct or struct respectively. Struct names never contain `::`, so a variant can't be mistaken
+//! for a struct with a single field.
+//!
+//! ```rust
+//! use gadget_blueprint_serde::{new_bounded_string, BoundedVec, Field};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(PartialEq, Debug, Serialize, Deserialize)]
+//! enum Curve {
+//!     Ed25519,
+//!     Custom(u8),
+//! }
+//!
+//! let field = gadget_blueprint_serde::to_field(Curve::Custom(7)).unwrap();
+//! let expected = Field::Struct(
+//!     new_bounded_string("Curve::Custom"),
+//!     Box::new(BoundedVec(vec![(
+//!         new_bounded_string("Custom"),
+//!         Field::Uint8(7),
+//!     )])),
+//! );
+//! assert_eq!(expected, field);
+//!
+//! let curve: Curve = gadget_blueprint_serde::from_field(field).unwrap();
+//! assert_eq!(curve, Curve::Custom(7));
+//! ```
 
 #![cfg_attr(feature = "std", no_std)]
 
 mod de;
 pub mod error;
+mod outputs;
 mod ser;
 #[cfg(test)]
 mod tests;
@@ -57,6 +90,7 @@ use serde::de::DeserializeOwned;
 use tangle_subxt::subxt_core::utils::AccountId32;
 pub use tangle_subxt::tangle_testnet_runtime::api::runtime_types::tangle_primitives::services::field::Field;
 pub use tangle_subxt::tangle_testnet_runtime::api::runtime_types::bounded_collections::bounded_vec::BoundedVec;
+pub use outputs::{to_fields, Outputs};
 pub use ser::new_bounded_string;
 pub use serde_bytes::ByteBuf;
 use error::Result;
@@ -0,0 +1,75 @@
+use crate::error::Result;
+use crate::Field;
+use alloc::vec;
+use alloc::vec::Vec;
+use serde::ser::SerializeTupleStruct;
+use serde::Serialize;
+use tangle_subxt::subxt_core::utils::AccountId32;
+
+/// The name [`Outputs`] are serialized with, so that [`to_fields`] can recognize them
+const OUTPUTS_NAME: &str = "__blueprint_outputs";
+
+/// The results of a job that returns several values, each submitted as its own field
+///
+/// Wraps a tuple of up to 12 elements. Any other value is submitted as a single field.
+///
+/// # Examples
+///
+/// ```rust
+/// use gadget_blueprint_serde::{new_bounded_string, Field, Outputs};
+///
+/// let fields = gadget_blueprint_serde::to_fields(Outputs((1u8, String::from("one")))).unwrap();
+/// assert_eq!(
+///     fields,
+///     vec![Field::Uint8(1), Field::String(new_bounded_string("one"))]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Outputs<T>(pub T);
+
+macro_rules! impl_serialize_for_outputs {
+    ($len:literal => $($idx:tt $name:ident),+) => {
+        impl<$($name: Serialize),+> Serialize for Outputs<($($name,)+)> {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let mut state = serializer.serialize_tuple_struct(OUTPUTS_NAME, $len)?;
+                $(state.serialize_field(&(self.0).$idx)?;)+
+                state.end()
+            }
+        }
+    };
+}
+
+impl_serialize_for_outputs!(1 => 0 A);
+impl_serialize_for_outputs!(2 => 0 A, 1 B);
+impl_serialize_for_outputs!(3 => 0 A, 1 B, 2 C);
+impl_serialize_for_outputs!(4 => 0 A, 1 B, 2 C, 3 D);
+impl_serialize_for_outputs!(5 => 0 A, 1 B, 2 C, 3 D, 4 E);
+impl_serialize_for_outputs!(6 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+impl_serialize_for_outputs!(7 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G);
+impl_serialize_for_outputs!(8 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H);
+impl_serialize_for_outputs!(9 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I);
+impl_serialize_for_outputs!(10 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J);
+impl_serialize_for_outputs!(11 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K);
+impl_serialize_for_outputs!(12 => 0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L);
+
+/// Derive the result [`Field`]s of a job from its result
+///
+/// [`Outputs`] become one field per element, and any other value a single field, as with [`to_field`](crate::to_field).
+///
+/// # Errors
+///
+/// * Attempting to serialize an [`UnsupportedType`](crate::error::UnsupportedType)
+pub fn to_fields<S>(value: S) -> Result<Vec<Field<AccountId32>>>
+where
+    S: Serialize,
+{
+    match crate::to_field(value)? {
+        Field::Struct(name, fields) if name.0 .0 == OUTPUTS_NAME.as_bytes() => {
+            Ok(fields.0.into_iter().map(|(_, field)| field).collect())
+        }
+        field => Ok(vec![field]),
+    }
+}
@@ -3,6 +3,7 @@ use crate::Field;
 use alloc::boxed::Box;
 use alloc::format;
 use alloc::string::String;
+use alloc::vec;
 use alloc::vec::Vec;
 use serde::ser;
 use serde::Serialize;
@@ -21,10 +22,10 @@ impl<'a> serde::Serializer for &'a mut Serializer {
     type SerializeSeq = SerializeSeq<'a>;
     type SerializeTuple = Self::SerializeSeq;
     type SerializeTupleStruct = SerializeTupleStruct<'a>;
-    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = SerializeTupleVariant<'a>;
     type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
     type SerializeStruct = SerializeStruct<'a>;
-    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = SerializeStructVariant<'a>;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
         Ok(Field::Bool(v))
@@ -121,15 +122,16 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _value: &T,
+        variant: &'static str,
+        value: &T,
     ) -> Result<Self::Ok>
     where
         T: ?Sized + Serialize,
     {
-        Err(Self::Error::UnsupportedType(UnsupportedType::NonUnitEnum))
+        let value = value.serialize(self)?;
+        Ok(variant_field(name, variant, value))
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
@@ -161,12 +163,16 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        Err(Self::Error::UnsupportedType(UnsupportedType::NonUnitEnum))
+        let ser = SerializeTupleVariant {
+            name,
+            inner: self.serialize_tuple_struct(variant, len)?,
+        };
+        Ok(ser)
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
@@ -184,12 +190,16 @@ impl<'a> serde::Serializer for &'a mut Serializer {
 
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        Err(Self::Error::UnsupportedType(UnsupportedType::NonUnitEnum))
+        let ser = SerializeStructVariant {
+            name,
+            inner: self.serialize_struct(variant, len)?,
+        };
+        Ok(ser)
     }
 
     fn is_human_readable(&self) -> bool {
@@ -344,6 +354,64 @@ impl ser::SerializeStruct for SerializeStruct<'_> {
     }
 }
 
+/// Variants with data are encoded as a [`Field::Struct`] named `Enum::Variant`, with a single field
+/// named after the variant.
+///
+/// The variant's data is encoded like a newtype, tuple, or named struct respectively. Struct names
+/// can't contain `::`, so the name tells a variant apart from a struct with a single field.
+fn variant_field(name: &str, variant: &str, value: Field<AccountId32>) -> Field<AccountId32> {
+    Field::Struct(
+        new_bounded_string(format!("{name}::{variant}")),
+        Box::new(BoundedVec(vec![(new_bounded_string(variant), value)])),
+    )
+}
+
+pub struct SerializeTupleVariant<'a> {
+    name: &'a str,
+    inner: SerializeTupleStruct<'a>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant<'_> {
+    type Ok = Field<AccountId32>;
+    type Error = crate::error::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTupleStruct::serialize_field(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        let variant = self.inner.name;
+        let value = ser::SerializeTupleStruct::end(self.inner)?;
+        Ok(variant_field(self.name, variant, value))
+    }
+}
+
+pub struct SerializeStructVariant<'a> {
+    name: &'a str,
+    inner: SerializeStruct<'a>,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant<'_> {
+    type Ok = Field<AccountId32>;
+    type Error = crate::error::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        let variant = self.inner.name;
+        let value = ser::SerializeStruct::end(self.inner)?;
+        Ok(variant_field(self.name, variant, value))
+    }
+}
+
 pub fn new_bounded_string<S>(s: S) -> BoundedString
 where
     S: Into<String>,
@@ -3,6 +3,7 @@ use crate::ser::new_bounded_string;
 use crate::to_field;
 use crate::Field;
 use alloc::boxed::Box;
+use alloc::format;
 use alloc::string::String;
 use alloc::vec;
 use serde::{Deserialize, Serialize};
@@ -342,59 +343,166 @@ mod enums {
     }
 
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
-    enum InvalidAvailability {
-        Available { days: u8 },
-        NotAvailable(String),
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Line(u8, String),
+        Rect { width: u16, height: u16 },
     }
 
-    impl Default for InvalidAvailability {
-        fn default() -> Self {
-            Self::Available { days: 5 }
+    impl Shape {
+        fn as_field(&self) -> Field<AccountId32> {
+            let variant = |variant: &str, value: Field<AccountId32>| {
+                Field::Struct(
+                    new_bounded_string(format!("Shape::{variant}")),
+                    Box::new(BoundedVec(vec![(new_bounded_string(variant), value)])),
+                )
+            };
+
+            match self {
+                Shape::Empty => Field::String(new_bounded_string("Empty")),
+                Shape::Circle(radius) => variant("Circle", Field::Uint32(*radius)),
+                Shape::Line(len, label) => variant(
+                    "Line",
+                    Field::Struct(
+                        new_bounded_string("Line"),
+                        Box::new(BoundedVec(vec![
+                            (new_bounded_string("field_0"), Field::Uint8(*len)),
+                            (
+                                new_bounded_string("field_1"),
+                                Field::String(new_bounded_string(label.as_str())),
+                            ),
+                        ])),
+                    ),
+                ),
+                Shape::Rect { width, height } => variant(
+                    "Rect",
+                    Field::Struct(
+                        new_bounded_string("Rect"),
+                        Box::new(BoundedVec(vec![
+                            (new_bounded_string("width"), Field::Uint16(*width)),
+                            (new_bounded_string("height"), Field::Uint16(*height)),
+                        ])),
+                    ),
+                ),
+            }
+        }
+
+        fn all() -> [Shape; 4] {
+            [
+                Shape::Empty,
+                Shape::Circle(5),
+                Shape::Line(3, String::from("abc")),
+                Shape::Rect {
+                    width: 4,
+                    height: 2,
+                },
+            ]
         }
     }
 
     #[test]
-    fn test_ser_invalid_enum() {
-        let invalid_availability = InvalidAvailability::default();
-
+    fn test_ser_data_enum() {
         assert_ser_tokens(
-            &invalid_availability,
+            &Shape::Rect {
+                width: 4,
+                height: 2,
+            },
             &[
                 Token::StructVariant {
-                    name: "InvalidAvailability",
-                    variant: "Available",
-                    len: 1,
+                    name: "Shape",
+                    variant: "Rect",
+                    len: 2,
                 },
-                Token::Str("days"),
-                Token::U8(5),
+                Token::Str("width"),
+                Token::U16(4),
+                Token::Str("height"),
+                Token::U16(2),
                 Token::StructVariantEnd,
             ],
         );
 
-        let err = to_field(&invalid_availability).unwrap_err();
-        assert!(matches!(err, crate::error::Error::UnsupportedType(_)));
+        for shape in Shape::all() {
+            let field = to_field(&shape).unwrap();
+            assert_eq!(field, shape.as_field());
+        }
     }
 
     #[test]
-    fn test_de_invalid_enum() {
-        let invalid_availability = InvalidAvailability::default();
-
-        assert_ser_tokens(
-            &invalid_availability,
+    fn test_de_data_enum() {
+        assert_de_tokens(
+            &Shape::Line(3, String::from("abc")),
             &[
-                Token::StructVariant {
-                    name: "InvalidAvailability",
-                    variant: "Available",
-                    len: 1,
+                Token::TupleVariant {
+                    name: "Shape",
+                    variant: "Line",
+                    len: 2,
                 },
-                Token::Str("days"),
-                Token::U8(5),
-                Token::StructVariantEnd,
+                Token::U8(3),
+                Token::Str("abc"),
+                Token::TupleVariantEnd,
             ],
         );
 
-        let _ = from_field::<InvalidAvailability>(Field::String(new_bounded_string("Available")))
+        for shape in Shape::all() {
+            let shape_de: Shape = from_field(shape.as_field()).unwrap();
+            assert_eq!(shape_de, shape);
+        }
+    }
+
+    #[test]
+    fn test_enum_nested() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Drawing {
+            shapes: alloc::vec::Vec<Shape>,
+            highlight: Option<Shape>,
+        }
+
+        let drawing = Drawing {
+            shapes: Shape::all().into(),
+            highlight: Some(Shape::Circle(1)),
+        };
+
+        let field = to_field(&drawing).unwrap();
+        let drawing_de: Drawing = from_field(field).unwrap();
+        assert_eq!(drawing_de, drawing);
+    }
+
+    #[test]
+    fn test_de_enum_mismatched_variant() {
+        // A struct variant encoded as a unit variant
+        let _ = from_field::<Shape>(Field::String(new_bounded_string("Rect")))
             .expect_err("should fail");
+
+        // Data for a unit variant
+        let field = Field::Struct(
+            new_bounded_string("Shape::Empty"),
+            Box::new(BoundedVec(vec![(
+                new_bounded_string("Empty"),
+                Field::Uint8(1),
+            )])),
+        );
+        let _ = from_field::<Shape>(field).expect_err("should fail");
+
+        // A struct with a single field is not a variant
+        let field = Field::Struct(
+            new_bounded_string("Shape"),
+            Box::new(BoundedVec(vec![(
+                new_bounded_string("Circle"),
+                Field::Uint32(1),
+            )])),
+        );
+        let _ = from_field::<Shape>(field).expect_err("should fail");
+
+        // More than one variant
+        let field = Field::Struct(
+            new_bounded_string("Shape::Circle"),
+            Box::new(BoundedVec(vec![
+                (new_bounded_string("Circle"), Field::Uint32(1)),
+                (new_bounded_string("Circle"), Field::Uint32(2)),
+            ])),
+        );
+        let _ = from_field::<Shape>(field).expect_err("should fail");
     }
 }
 
@@ -766,3 +874,52 @@ mod accountid32 {
         assert_eq!(account_id, account_id_de);
     }
 }
+
+mod outputs {
+    use super::*;
+    use crate::{to_fields, Outputs};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    #[test]
+    fn test_outputs_split() {
+        let fields = to_fields(Outputs((
+            Point { x: 1, y: 2 },
+            String::from("point"),
+            [7u16; 2],
+        )))
+        .unwrap();
+
+        assert_eq!(
+            fields,
+            vec![
+                to_field(Point { x: 1, y: 2 }).unwrap(),
+                Field::String(new_bounded_string("point")),
+                Field::Array(BoundedVec(vec![Field::Uint16(7), Field::Uint16(7)])),
+            ]
+        );
+
+        let point: Point = from_field(fields.into_iter().next().unwrap()).unwrap();
+        assert_eq!(point, Point { x: 1, y: 2 });
+    }
+
+    #[test]
+    fn test_single_output() {
+        // Tuples and arrays that aren't wrapped in `Outputs` are a single field
+        let fields = to_fields((1u8, 2u8)).unwrap();
+        assert_eq!(
+            fields,
+            vec![Field::Array(BoundedVec(vec![
+                Field::Uint8(1),
+                Field::Uint8(2)
+            ]))]
+        );
+
+        let fields = to_fields(Point { x: 1, y: 2 }).unwrap();
+        assert_eq!(fields, vec![to_field(Point { x: 1, y: 2 }).unwrap()]);
+    }
+}
@@ -12,7 +12,6 @@ use round_based::PartyIndex;
 pub struct P2PClient {
     name: String,
     config: GadgetConfiguration,
-    target_port: u16,
     gossip_msg_keypair: GossipMsgKeyPair,
 }
 
@@ -20,13 +19,11 @@ impl P2PClient {
     pub fn new(
         name: String,
         config: GadgetConfiguration,
-        target_port: u16,
         gossip_msg_keypair: GossipMsgKeyPair,
     ) -> Self {
         Self {
             name,
             config,
-            target_port,
             gossip_msg_keypair,
         }
     }
@@ -52,19 +49,30 @@ impl P2PClient {
     }
 
     /// Returns a new `NetworkConfig` for the current environment.
+    ///
+    /// The port is resolved with [`GadgetConfiguration::libp2p_port`].
     pub fn libp2p_network_config<T: Into<String>>(
         &self,
         network_name: T,
         ed25519_seed: Vec<u8>,
     ) -> Result<NetworkConfig> {
         let network_identity = self.libp2p_identity(ed25519_seed)?;
-        let network_config = NetworkConfig::new_service_network(
+        let port = self
+            .config
+            .libp2p_port()
+            .map_err(|err| Error::Configuration(err.to_string()))?;
+        let mut network_config = NetworkConfig::new_service_network(
             network_identity,
             self.gossip_msg_keypair.clone(),
             self.config.bootnodes.clone(),
-            self.target_port,
+            port,
             network_name,
-        );
+        )
+        .with_listen_addrs(self.config.network_bind_addrs.clone())
+        .with_external_addrs(self.config.network_external_addrs.clone());
+        if let Some(data_dir) = &self.config.data_dir {
+            network_config = network_config.with_data_dir(data_dir.clone());
+        }
 
         Ok(network_config)
     }
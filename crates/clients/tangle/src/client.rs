@@ -13,7 +13,7 @@ use tangle_subxt::tangle_testnet_runtime::api::runtime_types::pallet_multi_asset
 use gadget_client_core::{GadgetServicesClient, OperatorSet};
 use gadget_config::GadgetConfiguration;
 use gadget_crypto_sp_core::{SpEcdsa, SpSr25519};
-use gadget_keystore::{Keystore, KeystoreConfig};
+use gadget_keystore::Keystore;
 use gadget_keystore::backends::Backend;
 use crate::services::TangleServicesClient;
 
@@ -49,8 +49,9 @@ const KEY_ID: &str = "tangle-default";
 impl TangleClient {
     /// Create a new Tangle runtime client from an existing [`GadgetConfiguration`].
     pub async fn new(config: GadgetConfiguration) -> std::result::Result<Self, Error> {
-        let keystore_config =
-            KeystoreConfig::new().fs_root(config.keystore_uri.replace("file://", ""));
+        let keystore_config = config
+            .keystore_config()
+            .map_err(|e| Error::Other(e.to_string()))?;
 
         let keystore = Arc::new(Keystore::new(keystore_config)?);
 
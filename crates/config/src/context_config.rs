@@ -21,6 +21,14 @@ pub struct ContextConfig {
 pub enum GadgetCLICoreSettings {
     #[command(name = "run")]
     Run {
+        /// A TOML or YAML file to read settings from, see the `config_file` module
+        #[arg(long, value_name = "PATH", env = "GADGET_CONFIG")]
+        #[serde(skip)]
+        config: Option<PathBuf>,
+        /// Print the effective configuration, with secrets redacted, and exit
+        #[arg(long)]
+        #[serde(skip)]
+        print_config: bool,
         #[arg(long, short = 't', env)]
         test_mode: bool,
         #[arg(long, env)]
@@ -33,6 +41,21 @@ pub enum GadgetCLICoreSettings {
         #[arg(long, value_parser = <Multiaddr as gadget_std::str::FromStr>::from_str, action = clap::ArgAction::Append, env)]
         #[serde(default)]
         bootnodes: Option<Vec<Multiaddr>>,
+        /// The addresses to listen on for p2p connections, on all interfaces by default
+        #[cfg(feature = "networking")]
+        #[arg(long, value_parser = <Multiaddr as gadget_std::str::FromStr>::from_str, action = clap::ArgAction::Append, env)]
+        #[serde(default)]
+        network_bind_addrs: Option<Vec<Multiaddr>>,
+        /// The publicly reachable addresses to announce to peers, such as the address of a NAT
+        #[cfg(feature = "networking")]
+        #[arg(long, value_parser = <Multiaddr as gadget_std::str::FromStr>::from_str, action = clap::ArgAction::Append, env)]
+        #[serde(default)]
+        network_external_addrs: Option<Vec<Multiaddr>>,
+        /// The port to listen on for p2p connections, when no `network_bind_addrs` are given
+        #[cfg(feature = "networking")]
+        #[arg(long, env)]
+        #[serde(default)]
+        network_port: Option<u16>,
         #[arg(long, short = 'd', env)]
         keystore_uri: String,
         #[arg(long, value_enum, env)]
@@ -42,8 +65,17 @@ pub enum GadgetCLICoreSettings {
         /// Whether to use pretty logging
         #[arg(long, env)]
         pretty: bool,
+        /// The password of an encrypted keystore
         #[arg(long, env)]
         keystore_password: Option<String>,
+        /// A file to read the keystore password from
+        #[arg(long, value_name = "PATH", env, conflicts_with_all = ["keystore_password", "keystore_password_fd"])]
+        #[serde(default)]
+        keystore_password_file: Option<PathBuf>,
+        /// An open file descriptor to read the keystore password from, on Unix
+        #[arg(long, value_name = "FD", env, conflicts_with = "keystore_password")]
+        #[serde(default)]
+        keystore_password_fd: Option<u32>,
         /// The protocol to use
         #[arg(long, value_enum, env)]
         #[serde(default = "default_protocol")]
@@ -207,16 +239,26 @@ pub enum GadgetCLICoreSettings {
 impl Default for GadgetCLICoreSettings {
     fn default() -> Self {
         Self::Run {
+            config: None,
+            print_config: false,
             test_mode: false,
             http_rpc_url: default_http_rpc_url(),
             ws_rpc_url: default_ws_rpc_url(),
             #[cfg(feature = "networking")]
             bootnodes: None,
+            #[cfg(feature = "networking")]
+            network_bind_addrs: None,
+            #[cfg(feature = "networking")]
+            network_external_addrs: None,
+            #[cfg(feature = "networking")]
+            network_port: None,
             keystore_uri: String::new(),
             chain: SupportedChains::default(),
             verbose: 0,
             pretty: false,
             keystore_password: None,
+            keystore_password_file: None,
+            keystore_password_fd: None,
             protocol: Protocol::default(),
             #[cfg(feature = "tangle")]
             blueprint_id: Some(1),
@@ -338,15 +380,25 @@ impl ContextConfig {
 
         ContextConfig {
             gadget_core_settings: GadgetCLICoreSettings::Run {
+                config: None,
+                print_config: false,
                 test_mode: false,
                 http_rpc_url,
                 #[cfg(feature = "networking")]
                 bootnodes: None,
+                #[cfg(feature = "networking")]
+                network_bind_addrs: None,
+                #[cfg(feature = "networking")]
+                network_external_addrs: None,
+                #[cfg(feature = "networking")]
+                network_port: None,
                 keystore_uri,
                 chain,
                 verbose: 3,
                 pretty: true,
                 keystore_password,
+                keystore_password_file: None,
+                keystore_password_fd: None,
                 protocol,
                 ws_rpc_url,
                 #[cfg(feature = "tangle")]
@@ -7,6 +7,8 @@ use gadget_std::path::PathBuf;
 #[cfg(not(feature = "std"))]
 pub type PathBuf = String;
 
+#[cfg(feature = "std")]
+pub mod config_file;
 pub mod context_config;
 pub mod protocol;
 pub mod supported_chains;
@@ -67,6 +69,43 @@ pub enum Error {
     BadRpcConnection(String),
     #[error("Configuration error: {0}")]
     ConfigurationError(String),
+    /// Invalid command line arguments
+    #[error(transparent)]
+    Cli(clap::Error),
+    /// Every problem found while validating the configuration
+    #[error("Invalid configuration:\n  {}", .0.join("\n  "))]
+    InvalidConfiguration(gadget_std::vec::Vec<String>),
+    /// Failed to read the keystore password from a file or file descriptor
+    #[error("Failed to read the keystore password: {0}")]
+    KeystorePassword(String),
+    /// Keystore error
+    #[cfg(feature = "keystore")]
+    #[error(transparent)]
+    Keystore(gadget_keystore::Error),
+}
+
+/// The password of an encrypted keystore, which is redacted when printed
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeystorePassword(String);
+
+impl KeystorePassword {
+    /// Create a new `KeystorePassword`
+    #[must_use]
+    pub fn new(password: impl Into<String>) -> Self {
+        Self(password.into())
+    }
+
+    /// The password itself
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for KeystorePassword {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        f.write_str("KeystorePassword(<redacted>)")
+    }
 }
 
 #[cfg(feature = "networking")]
@@ -83,7 +122,11 @@ pub struct GadgetConfiguration {
     /// WS RPC endpoint for host restaking network (Tangle / Ethereum (Eigenlayer or Symbiotic)).
     pub ws_rpc_endpoint: String,
     /// The keystore URI for the gadget
+    ///
+    /// See `KeystoreConfig::from_uri` for the supported URIs.
     pub keystore_uri: String,
+    /// The password to unlock an encrypted keystore
+    pub keystore_password: Option<KeystorePassword>,
     /// Data directory exclusively for this gadget
     ///
     /// This will be `None` if the blueprint manager was not provided a base directory.
@@ -91,6 +134,19 @@ pub struct GadgetConfiguration {
     /// The list of bootnodes to connect to
     #[cfg(feature = "networking")]
     pub bootnodes: Vec<Multiaddr>,
+    /// The addresses to listen on for p2p connections
+    ///
+    /// If empty, the network listens on all interfaces on the network port.
+    #[cfg(feature = "networking")]
+    pub network_bind_addrs: Vec<Multiaddr>,
+    /// The publicly reachable addresses announced to peers
+    #[cfg(feature = "networking")]
+    pub network_external_addrs: Vec<Multiaddr>,
+    /// The port to listen on for p2p connections, when there are no bind addresses
+    ///
+    /// If `None`, the port of the RPC endpoint is used.
+    #[cfg(feature = "networking")]
+    pub network_port: Option<u16>,
     /// The type of protocol the gadget is executing on.
     pub protocol: Protocol,
     /// Protocol-specific settings
@@ -100,6 +156,26 @@ pub struct GadgetConfiguration {
 }
 
 impl GadgetConfiguration {
+    /// Returns the `KeystoreConfig` for the keystore URI, unlocked with the keystore password.
+    ///
+    /// # Errors
+    ///
+    /// The keystore URI is not supported.
+    #[cfg(feature = "keystore")]
+    pub fn keystore_config(&self) -> Result<gadget_keystore::KeystoreConfig, Error> {
+        let mut config = gadget_keystore::KeystoreConfig::from_uri(&self.keystore_uri).map_err(
+            |err| match err {
+                gadget_keystore::Error::InvalidUri(reason) => Error::UnsupportedKeystoreUri(reason),
+                err => Error::Keystore(err),
+            },
+        )?;
+        if let Some(password) = &self.keystore_password {
+            config = config.password(password.expose());
+        }
+
+        Ok(config)
+    }
+
     /// Returns a new `NetworkConfig` for the current environment.
     #[cfg(feature = "networking")]
     pub fn libp2p_network_config(
@@ -110,9 +186,8 @@ impl GadgetConfiguration {
         use gadget_keystore::crypto::sp_core::SpEd25519 as LibP2PKeyType;
         use gadget_networking::key_types::Curve as GossipMsgKeyPair;
 
-        let keystore_config = gadget_keystore::KeystoreConfig::new().fs_root(&self.keystore_uri);
-        let keystore = gadget_keystore::Keystore::new(keystore_config)
-            .map_err(|err| Error::ConfigurationError(err.to_string()))?;
+        let keystore =
+            gadget_keystore::Keystore::new(self.keystore_config()?).map_err(Error::Keystore)?;
         let ed25519_pub_key = keystore
             .first_local::<LibP2PKeyType>()
             .map_err(|err| Error::ConfigurationError(err.to_string()))?;
@@ -122,7 +197,7 @@ impl GadgetConfiguration {
         let network_identity = libp2p::identity::Keypair::ed25519_from_bytes(ed25519_pair.seed())
             .map_err(|err| Error::ConfigurationError(err.to_string()))?;
 
-        let port = self.get_port()?;
+        let port = self.libp2p_port()?;
         let ecdsa_pub_key = keystore
             .first_local::<GossipMsgKeyPair>()
             .map_err(|err| Error::ConfigurationError(err.to_string()))?;
@@ -130,17 +205,39 @@ impl GadgetConfiguration {
             .get_secret::<GossipMsgKeyPair>(&ecdsa_pub_key)
             .map_err(|err| Error::ConfigurationError(err.to_string()))?;
 
-        let network_config = gadget_networking::setup::NetworkConfig::new_service_network(
+        let mut network_config = gadget_networking::setup::NetworkConfig::new_service_network(
             network_identity,
             ecdsa_pair,
             self.bootnodes.clone(),
             port,
             network_name,
-        );
+        )
+        .with_listen_addrs(self.network_bind_addrs.clone())
+        .with_external_addrs(self.network_external_addrs.clone());
+        if let Some(data_dir) = &self.data_dir {
+            network_config = network_config.with_data_dir(data_dir.clone());
+        }
 
         Ok(network_config)
     }
 
+    /// Returns the port the P2P network listens on when no bind addresses are given
+    ///
+    /// This is `network_port` if set, `0` if there are bind addresses (the port is unused), and
+    /// otherwise the port of the RPC endpoints.
+    ///
+    /// # Errors
+    ///
+    /// If the port has to be taken from the RPC endpoints, and they have none
+    #[cfg(feature = "networking")]
+    pub fn libp2p_port(&self) -> Result<u16, Error> {
+        match self.network_port {
+            Some(port) => Ok(port),
+            None if !self.network_bind_addrs.is_empty() => Ok(0),
+            None => self.get_port(),
+        }
+    }
+
     /// Attempt to look at the ws_rpc_endpoint and extract the port. If not found,
     /// looks at the http_rpc_endpoint and tries to extract the port.
     ///
@@ -200,7 +297,16 @@ fn load_inner(config: ContextConfig) -> Result<GadgetConfiguration, Error> {
                 ws_rpc_url,
                 #[cfg(feature = "networking")]
                 bootnodes,
+                #[cfg(feature = "networking")]
+                network_bind_addrs,
+                #[cfg(feature = "networking")]
+                network_external_addrs,
+                #[cfg(feature = "networking")]
+                network_port,
                 keystore_uri,
+                keystore_password,
+                keystore_password_file,
+                keystore_password_fd,
                 protocol,
                 #[cfg(feature = "tangle")]
                 blueprint_id,
@@ -306,22 +412,80 @@ fn load_inner(config: ContextConfig) -> Result<GadgetConfiguration, Error> {
         return Err(Error::UnsupportedProtocol(protocol.to_string()));
     };
 
+    let keystore_password = read_keystore_password(
+        keystore_password,
+        keystore_password_file,
+        keystore_password_fd,
+    )?;
+
     Ok(GadgetConfiguration {
         test_mode,
         http_rpc_endpoint: http_rpc_url.to_string(),
         ws_rpc_endpoint: ws_rpc_url.to_string(),
         keystore_uri,
+        keystore_password,
         #[cfg(feature = "std")]
         data_dir: gadget_std::env::var("DATA_DIR").ok().map(PathBuf::from),
         #[cfg(not(feature = "std"))]
         data_dir: None,
         #[cfg(feature = "networking")]
         bootnodes: bootnodes.unwrap_or_default(),
+        #[cfg(feature = "networking")]
+        network_bind_addrs: network_bind_addrs.unwrap_or_default(),
+        #[cfg(feature = "networking")]
+        network_external_addrs: network_external_addrs.unwrap_or_default(),
+        #[cfg(feature = "networking")]
+        network_port,
         protocol,
         protocol_settings,
     })
 }
 
+/// Reads the keystore password, which is given directly, in a file, or through a file descriptor.
+///
+/// A single trailing newline is removed from passwords that are read.
+fn read_keystore_password(
+    password: Option<String>,
+    file: Option<PathBuf>,
+    fd: Option<u32>,
+) -> Result<Option<KeystorePassword>, Error> {
+    if let Some(password) = password {
+        return Ok(Some(KeystorePassword::new(password)));
+    }
+
+    let contents = match (file, fd) {
+        (Some(file), _) => read_password_from(&file)?,
+        (None, Some(fd)) if cfg!(unix) => read_password_from(&format!("/dev/fd/{fd}"))?,
+        (None, Some(_)) => {
+            return Err(Error::KeystorePassword(String::from(
+                "password file descriptors are only supported on Unix",
+            )))
+        }
+        (None, None) => return Ok(None),
+    };
+
+    let password = contents
+        .strip_suffix('\n')
+        .map_or(contents.as_str(), |password| {
+            password.strip_suffix('\r').unwrap_or(password)
+        });
+    Ok(Some(KeystorePassword::new(password)))
+}
+
+#[cfg(feature = "std")]
+fn read_password_from<P: AsRef<gadget_std::path::Path>>(path: P) -> Result<String, Error> {
+    let path = path.as_ref();
+    gadget_std::fs::read_to_string(path)
+        .map_err(|err| Error::KeystorePassword(format!("{}: {err}", path.display())))
+}
+
+#[cfg(not(feature = "std"))]
+fn read_password_from<P>(_path: P) -> Result<String, Error> {
+    Err(Error::KeystorePassword(String::from(
+        "reading the password requires the `std` feature",
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -454,6 +618,55 @@ mod tests {
         assert!(validate_keystore_uri(&config.keystore_uri).is_err());
     }
 
+    #[test]
+    fn test_read_keystore_password() {
+        let path = gadget_std::env::temp_dir().join(format!(
+            "gadget-keystore-password-{}",
+            gadget_std::process::id()
+        ));
+        gadget_std::fs::write(&path, "hunter2\n").unwrap();
+
+        let password = read_keystore_password(None, Some(path.clone()), None).unwrap();
+        assert_eq!(password, Some(KeystorePassword::new("hunter2")));
+
+        let password = read_keystore_password(Some("direct".to_string()), None, None).unwrap();
+        assert_eq!(password, Some(KeystorePassword::new("direct")));
+
+        gadget_std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            read_keystore_password(None, Some(path), None),
+            Err(Error::KeystorePassword(_))
+        ));
+        assert_eq!(read_keystore_password(None, None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_keystore_password_is_redacted() {
+        let config = GadgetConfiguration {
+            keystore_password: Some(KeystorePassword::new("hunter2")),
+            ..Default::default()
+        };
+        assert!(!format!("{config:?}").contains("hunter2"));
+    }
+
+    #[test]
+    fn test_keystore_config() {
+        let config = GadgetConfiguration {
+            keystore_uri: "memory://".to_string(),
+            ..Default::default()
+        };
+        assert!(config.keystore_config().is_ok());
+
+        let config = GadgetConfiguration {
+            keystore_uri: "ftp://keystore".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.keystore_config(),
+            Err(Error::UnsupportedKeystoreUri(_))
+        ));
+    }
+
     // Helper functions for tests
     fn validate_rpc_endpoint(endpoint: &str) -> Result<(), Error> {
         if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
@@ -1,5 +1,5 @@
 use gadget_config::GadgetConfiguration;
-use gadget_keystore::{Keystore, KeystoreConfig};
+use gadget_keystore::Keystore;
 
 /// `KeystoreContext` trait provides access to the generic keystore from the context.
 pub trait KeystoreContext {
@@ -8,8 +8,15 @@ pub trait KeystoreContext {
 }
 
 impl KeystoreContext for GadgetConfiguration {
+    /// Opens the keystore at the keystore URI, unlocked with the keystore password.
+    ///
+    /// # Panics
+    ///
+    /// The keystore URI is not supported, or the keystore password does not unlock the keystore.
     fn keystore(&self) -> Keystore {
-        let config = KeystoreConfig::new().fs_root(self.keystore_uri.clone());
-        Keystore::new(config).expect("Failed to create keystore")
+        let config = self
+            .keystore_config()
+            .unwrap_or_else(|e| panic!("Failed to create keystore: {e}"));
+        Keystore::new(config).unwrap_or_else(|e| panic!("Failed to create keystore: {e}"))
     }
 }
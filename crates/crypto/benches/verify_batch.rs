@@ -0,0 +1,63 @@
+//! Compares verifying signatures one at a time against `KeyType::verify_batch`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gadget_crypto::bls::bls381::W3fBls381;
+use gadget_crypto::bn254::ArkBlsBn254;
+use gadget_crypto::ed25519::Ed25519Zebra;
+use gadget_crypto::sr25519::SchnorrkelSr25519;
+use gadget_crypto::KeyType;
+
+const BATCH_SIZES: [usize; 3] = [16, 64, 256];
+
+type Batch<T> = Vec<(<T as KeyType>::Public, Vec<u8>, <T as KeyType>::Signature)>;
+
+fn signed_batch<T: KeyType>(size: usize) -> Batch<T> {
+    (0..size)
+        .map(|i| {
+            let seed = [u8::try_from(i % 255).unwrap() + 1; 32];
+            let Ok(mut secret) = T::generate_with_seed(Some(&seed)) else {
+                panic!("failed to generate key");
+            };
+            let public = T::public_from_secret(&secret);
+            let msg = format!("operator message {i}").into_bytes();
+            let Ok(signature) = T::sign_with_secret(&mut secret, &msg) else {
+                panic!("failed to sign");
+            };
+            (public, msg, signature)
+        })
+        .collect()
+}
+
+fn bench_key_type<T: KeyType>(c: &mut Criterion, name: &str) {
+    let mut group = c.benchmark_group(format!("verify/{name}"));
+    for size in BATCH_SIZES {
+        let batch = signed_batch::<T>(size);
+        let items = batch
+            .iter()
+            .map(|(public, msg, signature)| (public, msg.as_slice(), signature))
+            .collect::<Vec<_>>();
+
+        group.throughput(Throughput::Elements(u64::try_from(size).unwrap()));
+        group.bench_with_input(BenchmarkId::new("individual", size), &items, |b, items| {
+            b.iter(|| {
+                assert!(items
+                    .iter()
+                    .all(|(public, msg, signature)| T::verify(public, msg, signature)));
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("batch", size), &items, |b, items| {
+            b.iter(|| assert!(T::verify_batch(items)));
+        });
+    }
+    group.finish();
+}
+
+fn verify_batch(c: &mut Criterion) {
+    bench_key_type::<Ed25519Zebra>(c, "ed25519");
+    bench_key_type::<SchnorrkelSr25519>(c, "sr25519");
+    bench_key_type::<ArkBlsBn254>(c, "bn254");
+    bench_key_type::<W3fBls381>(c, "bls381");
+}
+
+criterion_group!(benches, verify_batch);
+criterion_main!(benches);
@@ -7,6 +7,8 @@ pub enum BlsError {
     InvalidSeed(String),
     #[error("Invalid hex string: {0}")]
     HexError(hex::FromHexError),
+    #[error("Cannot aggregate an empty set")]
+    EmptyAggregation,
 }
 
 impl From<hex::FromHexError> for BlsError {
@@ -23,6 +23,10 @@ pub fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> T {
 
 pub const CONTEXT: &[u8] = b"tangle";
 
+/// Signing context for proofs of possession, kept apart from [`CONTEXT`] so that a regular
+/// signature over a public key can never double as a proof.
+pub const POP_CONTEXT: &[u8] = b"tangle-pop";
+
 macro_rules! impl_w3f_serde {
     ($name:ident, $inner:ty) => {
         #[derive(Clone)]
@@ -98,9 +102,10 @@ macro_rules! define_bls_key {
             pub mod [<$ty:lower>] {
                 use crate::error::{BlsError, Result};
                 use crate::from_bytes;
-                use gadget_crypto_core::{KeyType, KeyTypeId, KeyEncoding};
+                use gadget_crypto_core::{AggregatableSignature, KeyType, KeyTypeId, KeyEncoding, ProofOfPossession};
                 use gadget_std::{UniformRand, string::{String, ToString}};
-                use w3f_bls::{Message, PublicKey, SecretKey, SerializableToBytes, Signature, [<Tiny $ty:upper>]};
+                use w3f_bls::multi_pop_aggregator::MultiMessageSignatureAggregatorAssumingPoP;
+                use w3f_bls::{EngineBLS, Message, PublicKey, SecretKey, SerializableToBytes, Signature, Signed, [<Tiny $ty:upper>]};
 
                 #[doc = $ty:upper]
                 /// key type
@@ -161,6 +166,71 @@ macro_rules! define_bls_key {
                         let message = Message::new(super::CONTEXT, msg);
                         signature.0.verify(&message, &public.0)
                     }
+
+                    // The random coefficients must be unpredictable, which `get_rng` only guarantees with
+                    // `std`. Without it, signatures are verified one by one.
+                    #[cfg(feature = "std")]
+                    fn verify_batch(items: &[(&Self::Public, &[u8], &Self::Signature)]) -> bool {
+                        let Some(((first_public, first_msg, first_signature), rest)) = items.split_first() else {
+                            return true;
+                        };
+
+                        // Scale each signature and key by a random coefficient so that invalid
+                        // signatures cannot cancel each other out in the multi-pairing
+                        let mut rng = Self::get_rng();
+                        let mut aggregator = MultiMessageSignatureAggregatorAssumingPoP::<[<Tiny $ty:upper>]>::new();
+                        let r = <[<Tiny $ty:upper>] as EngineBLS>::Scalar::rand(&mut rng);
+                        let mut aggregate = first_signature.0.0 * r;
+                        aggregator.add_message_n_publickey(&Message::new(super::CONTEXT, first_msg), &PublicKey(first_public.0.0 * r));
+                        for (public, msg, signature) in rest {
+                            let r = <[<Tiny $ty:upper>] as EngineBLS>::Scalar::rand(&mut rng);
+                            aggregate += signature.0.0 * r;
+                            aggregator.add_message_n_publickey(&Message::new(super::CONTEXT, msg), &PublicKey(public.0.0 * r));
+                        }
+                        aggregator.add_signature(&Signature(aggregate));
+                        aggregator.verify()
+                    }
+                }
+
+                impl ProofOfPossession for [<W3f $ty>] {
+                    fn prove_possession(secret: &mut Self::Secret) -> Result<Self::Signature> {
+                        let mut rng = Self::get_rng();
+                        let public = Self::public_from_secret(secret);
+                        let message = Message::new(super::POP_CONTEXT, &public.to_bytes());
+                        Ok([<W3f $ty Signature>](secret.0.sign(&message, &mut rng)))
+                    }
+
+                    fn verify_possession(public: &Self::Public, proof: &Self::Signature) -> bool {
+                        let message = Message::new(super::POP_CONTEXT, &public.to_bytes());
+                        proof.0.verify(&message, &public.0)
+                    }
+                }
+
+                impl AggregatableSignature for [<W3f $ty>] {
+                    fn aggregate_signatures(signatures: &[Self::Signature]) -> Result<Self::Signature> {
+                        let (first, rest) = signatures.split_first().ok_or(BlsError::EmptyAggregation)?;
+                        let aggregate = rest.iter().fold(first.0.0, |acc, signature| acc + signature.0.0);
+                        Ok([<W3f $ty Signature>](Signature(aggregate)))
+                    }
+
+                    fn aggregate_public_keys(public_keys: &[Self::Public]) -> Result<Self::Public> {
+                        let (first, rest) = public_keys.split_first().ok_or(BlsError::EmptyAggregation)?;
+                        let aggregate = rest.iter().fold(first.0.0, |acc, public| acc + public.0.0);
+                        Ok([<W3f $ty Public>](PublicKey(aggregate)))
+                    }
+
+                    fn verify_multi_message(items: &[(&Self::Public, &[u8])], signature: &Self::Signature) -> bool {
+                        if items.is_empty() {
+                            return false;
+                        }
+
+                        let mut aggregator = MultiMessageSignatureAggregatorAssumingPoP::<[<Tiny $ty:upper>]>::new();
+                        aggregator.add_signature(&signature.0);
+                        for (public, msg) in items {
+                            aggregator.add_message_n_publickey(&Message::new(super::CONTEXT, msg), &public.0);
+                        }
+                        aggregator.verify()
+                    }
                 }
             }
             )+
@@ -21,6 +21,30 @@ mod bls381_crypto_tests {
     gadget_crypto_core::impl_crypto_tests!(W3fBls381, W3fBls381Secret, W3fBls381Signature);
 }
 
+mod bls377_aggregation_tests {
+    use super::bls377::W3fBls377;
+    use gadget_crypto_core::KeyType;
+    gadget_crypto_core::impl_aggregation_tests!(W3fBls377);
+}
+
+mod bls377_pop_tests {
+    use super::bls377::W3fBls377;
+    use gadget_crypto_core::KeyType;
+    gadget_crypto_core::impl_proof_of_possession_tests!(W3fBls377);
+}
+
+mod bls381_pop_tests {
+    use super::bls381::W3fBls381;
+    use gadget_crypto_core::KeyType;
+    gadget_crypto_core::impl_proof_of_possession_tests!(W3fBls381);
+}
+
+mod bls381_aggregation_tests {
+    use super::bls381::W3fBls381;
+    use gadget_crypto_core::KeyType;
+    gadget_crypto_core::impl_aggregation_tests!(W3fBls381);
+}
+
 mod bls377_tests {
     use super::*;
     use ::w3f_bls::SerializableToBytes;
@@ -0,0 +1,174 @@
+//! Selectable schemes for hashing messages to G1.
+
+use crate::hash_to_curve;
+use ark_bn254::{Fq, G1Affine, G1Projective};
+use ark_ec::CurveGroup;
+use ark_ff::{BigInteger, Field, One, PrimeField, Zero};
+use gadget_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// How a message is mapped to a point in G1 before it is signed.
+///
+/// A signature only verifies under the scheme, and domain separation tag, it was created with.
+/// Blueprints and protocols that use distinct tags can't have their signatures replayed across
+/// each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HashToCurve<'a> {
+    /// SHA-256 try-and-increment with no domain separation, matching EigenLayer's contracts.
+    #[default]
+    Legacy,
+    /// RFC 9380 hashing, using `expand_message_xmd` with SHA-256 and the Shallue-van de Woestijne
+    /// map (`BN254G1_XMD:SHA-256_SVDW_RO_`), under the domain separation tag `dst`.
+    Rfc9380 { dst: &'a [u8] },
+}
+
+impl HashToCurve<'_> {
+    /// Hash `msg` to a point in G1.
+    #[must_use]
+    pub fn hash(&self, msg: &[u8]) -> G1Affine {
+        match self {
+            HashToCurve::Legacy => hash_to_curve(msg),
+            HashToCurve::Rfc9380 { dst } => {
+                let [u0, u1] = hash_to_field(msg, dst);
+                (map_to_curve_svdw(u0) + map_to_curve_svdw(u1)).into_affine()
+            }
+        }
+    }
+}
+
+/// SHA-256 output size, `b_in_bytes` in RFC 9380
+const B_IN_BYTES: usize = 32;
+/// SHA-256 block size, `s_in_bytes` in RFC 9380
+const S_IN_BYTES: usize = 64;
+/// Bytes hashed per field element: `ceil((ceil(log2(p)) + k) / 8)` with `k = 128`
+const L: usize = 48;
+
+/// `expand_message_xmd` from RFC 9380 section 5.3.1, using SHA-256.
+///
+/// Tags longer than 255 bytes are first hashed, as described in section 5.3.3.
+///
+/// # Panics
+///
+/// Panics if `len_in_bytes` is greater than 8160, the most SHA-256 can expand to.
+#[must_use]
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    assert!(ell <= 255, "expand_message_xmd output too long");
+
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        oversize_dst.as_slice()
+    } else {
+        dst
+    };
+    // Both lengths are checked to fit above
+    #[allow(clippy::cast_possible_truncation)]
+    let dst_len = [dst.len() as u8];
+    #[allow(clippy::cast_possible_truncation)]
+    let len_in_bytes_bytes = (len_in_bytes as u16).to_be_bytes();
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update(len_in_bytes_bytes)
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    uniform_bytes.extend_from_slice(&b_i);
+
+    for i in 2..=ell {
+        let mut xored = [0u8; B_IN_BYTES];
+        for (x, (a, b)) in xored.iter_mut().zip(b_0.iter().zip(b_i.iter())) {
+            *x = a ^ b;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let i = [i as u8];
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update(i)
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+/// `hash_to_field` from RFC 9380 section 5.2, producing two base field elements.
+fn hash_to_field(msg: &[u8], dst: &[u8]) -> [Fq; 2] {
+    let uniform_bytes = expand_message_xmd(msg, dst, 2 * L);
+    [
+        Fq::from_be_bytes_mod_order(&uniform_bytes[..L]),
+        Fq::from_be_bytes_mod_order(&uniform_bytes[L..]),
+    ]
+}
+
+fn sgn0(x: Fq) -> bool {
+    x.into_bigint().is_odd()
+}
+
+/// `g(x) = x^3 + 3`, the right-hand side of the BN254 G1 curve equation
+fn curve_rhs(x: Fq) -> Fq {
+    x.square() * x + Fq::from(3u64)
+}
+
+/// The Shallue-van de Woestijne map from RFC 9380 section 6.6.1, with `Z = 1`.
+///
+/// G1 has cofactor 1, so the mapped point needs no clearing.
+fn map_to_curve_svdw(u: Fq) -> G1Projective {
+    let z = Fq::one();
+    let three = Fq::from(3u64);
+
+    // c1 = g(Z), c2 = -Z / 2, c3 = sqrt(-g(Z) * 3Z^2) with sgn0(c3) = 0, c4 = -4g(Z) / 3Z^2
+    let c1 = curve_rhs(z);
+    let c2 = -z / Fq::from(2u64);
+    let mut c3 = (-c1 * three * z.square())
+        .sqrt()
+        .expect("-g(Z) * 3Z^2 is square for BN254 with Z = 1");
+    if sgn0(c3) {
+        c3 = -c3;
+    }
+    let c4 = -Fq::from(4u64) * c1 / (three * z.square());
+
+    let tv1 = u.square() * c1;
+    let tv2 = Fq::one() + tv1;
+    let tv1 = Fq::one() - tv1;
+    let tv3 = (tv1 * tv2).inverse().unwrap_or(Fq::zero());
+    let tv4 = u * tv1 * tv3 * c3;
+
+    let x1 = c2 - tv4;
+    let x2 = c2 + tv4;
+    let x3 = (tv2.square() * tv3).square() * c4 + z;
+
+    let x = if curve_rhs(x1).sqrt().is_some() {
+        x1
+    } else if curve_rhs(x2).sqrt().is_some() {
+        x2
+    } else {
+        x3
+    };
+
+    let mut y = curve_rhs(x)
+        .sqrt()
+        .expect("one of the candidates is always on the curve");
+    if sgn0(u) != sgn0(y) {
+        y = -y;
+    }
+
+    G1Projective::new(x, y, Fq::one())
+}
@@ -1,18 +1,20 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod error;
+pub mod hashing;
 use error::{Bn254Error, Result};
+pub use hashing::HashToCurve;
 
 #[cfg(test)]
 mod tests;
 
-use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective, G2Affine};
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_ff::UniformRand;
-use ark_ff::{BigInteger256, Field, One, PrimeField};
+use ark_ff::{BigInteger, BigInteger256, Field, One, PrimeField, Zero};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use gadget_crypto_core::KeyEncoding;
-use gadget_crypto_core::{KeyType, KeyTypeId};
+use gadget_crypto_core::{AggregatableSignature, KeyType, KeyTypeId, ProofOfPossession};
 use gadget_std::vec::Vec;
 use gadget_std::{
     str::FromStr,
@@ -74,7 +76,12 @@ fn hash_to_curve(digest: &[u8]) -> G1Affine {
 }
 
 pub fn sign(sk: Fr, message: &[u8]) -> Result<G1Affine> {
-    let q = hash_to_curve(message);
+    sign_with_scheme(sk, message, HashToCurve::Legacy)
+}
+
+/// Sign `message`, hashing it to G1 with `scheme`.
+pub fn sign_with_scheme(sk: Fr, message: &[u8], scheme: HashToCurve<'_>) -> Result<G1Affine> {
+    let q = scheme.hash(message);
 
     let sk_int: BigInteger256 = sk.into();
     let r = q.mul_bigint(sk_int);
@@ -88,17 +95,31 @@ pub fn sign(sk: Fr, message: &[u8]) -> Result<G1Affine> {
 }
 
 pub fn verify(public_key: G2Affine, message: &[u8], signature: G1Affine) -> bool {
+    verify_with_scheme(public_key, message, signature, HashToCurve::Legacy)
+}
+
+/// Verify a signature over `message` that was hashed to G1 with `scheme`.
+pub fn verify_with_scheme(
+    public_key: G2Affine,
+    message: &[u8],
+    signature: G1Affine,
+    scheme: HashToCurve<'_>,
+) -> bool {
     if !signature.is_in_correct_subgroup_assuming_on_curve() || !signature.is_on_curve() {
         return false;
     }
 
-    let q = hash_to_curve(message);
+    let q = scheme.hash(message);
     let c1 = Bn254::pairing(q, public_key);
     let c2 = Bn254::pairing(signature, G2Affine::generator());
     c1 == c2
 }
 
 /// BLS-BN254 key type
+///
+/// The [`KeyType`] methods hash messages with [`HashToCurve::Legacy`], for compatibility with
+/// EigenLayer. Use [`ArkBlsBn254::sign_with_scheme`] and [`ArkBlsBn254::verify_with_scheme`] to
+/// sign under a domain separation tag instead.
 pub struct ArkBlsBn254;
 
 macro_rules! impl_ark_serde {
@@ -209,4 +230,207 @@ impl KeyType for ArkBlsBn254 {
     fn verify(public: &Self::Public, msg: &[u8], signature: &Self::Signature) -> bool {
         verify(public.0, msg, signature.0)
     }
+
+    // The random coefficients must be unpredictable, which `get_rng` only guarantees with `std`.
+    // Without it, signatures are verified one by one.
+    #[cfg(feature = "std")]
+    fn verify_batch(items: &[(&Self::Public, &[u8], &Self::Signature)]) -> bool {
+        if items.is_empty() {
+            return true;
+        }
+
+        // Combine the checks with random coefficients so that invalid signatures cannot cancel
+        // each other out: e(sum r_i * sig_i, g2) == prod e(r_i * H(m_i), pk_i)
+        let mut rng = Self::get_rng();
+        let mut aggregate = G1Projective::zero();
+        let mut g1 = Vec::with_capacity(items.len() + 1);
+        let mut g2 = Vec::with_capacity(items.len() + 1);
+        for (public, msg, signature) in items {
+            if !signature.0.is_on_curve() || !signature.0.is_in_correct_subgroup_assuming_on_curve()
+            {
+                return false;
+            }
+
+            let r = Fr::rand(&mut rng);
+            aggregate += signature.0 * r;
+            g1.push((hash_to_curve(msg) * r).into_affine());
+            g2.push(public.0);
+        }
+        g1.push(-aggregate.into_affine());
+        g2.push(G2Affine::generator());
+
+        Bn254::multi_pairing(g1, g2).0.is_one()
+    }
+}
+
+impl ArkBlsBn254 {
+    /// Sign `msg`, hashing it to G1 with `scheme`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if signing fails.
+    pub fn sign_with_scheme(
+        secret: &mut ArkBlsBn254Secret,
+        msg: &[u8],
+        scheme: HashToCurve<'_>,
+    ) -> Result<ArkBlsBn254Signature> {
+        let signature = sign_with_scheme(secret.0, msg, scheme)
+            .map_err(|e| Bn254Error::SignatureFailed(e.to_string()))?;
+        Ok(ArkBlsBn254Signature(signature))
+    }
+
+    /// Verify a signature over `msg` that was created with `scheme`.
+    #[must_use]
+    pub fn verify_with_scheme(
+        public: &ArkBlsBn254Public,
+        msg: &[u8],
+        signature: &ArkBlsBn254Signature,
+        scheme: HashToCurve<'_>,
+    ) -> bool {
+        verify_with_scheme(public.0, msg, signature.0, scheme)
+    }
+}
+
+impl AggregatableSignature for ArkBlsBn254 {
+    fn aggregate_signatures(signatures: &[Self::Signature]) -> Result<Self::Signature> {
+        if signatures.is_empty() {
+            return Err(Bn254Error::EmptyAggregation);
+        }
+        let aggregate = signatures
+            .iter()
+            .fold(G1Projective::zero(), |acc, signature| acc + signature.0);
+        Ok(ArkBlsBn254Signature(aggregate.into_affine()))
+    }
+
+    fn aggregate_public_keys(public_keys: &[Self::Public]) -> Result<Self::Public> {
+        if public_keys.is_empty() {
+            return Err(Bn254Error::EmptyAggregation);
+        }
+        let aggregate = public_keys
+            .iter()
+            .fold(G2Projective::zero(), |acc, public| acc + public.0);
+        Ok(ArkBlsBn254Public(aggregate.into_affine()))
+    }
+
+    fn verify_multi_message(items: &[(&Self::Public, &[u8])], signature: &Self::Signature) -> bool {
+        if items.is_empty()
+            || !signature.0.is_on_curve()
+            || !signature.0.is_in_correct_subgroup_assuming_on_curve()
+        {
+            return false;
+        }
+
+        // e(signature, g2) == prod e(H(m_i), pk_i), checked as a single multi-pairing
+        let g1 = items
+            .iter()
+            .map(|(_, msg)| hash_to_curve(msg))
+            .chain(gadget_std::iter::once(-signature.0))
+            .collect::<Vec<_>>();
+        let g2 = items
+            .iter()
+            .map(|(public, _)| public.0)
+            .chain(gadget_std::iter::once(G2Affine::generator()))
+            .collect::<Vec<_>>();
+        Bn254::multi_pairing(g1, g2).0.is_one()
+    }
+}
+
+/// Domain separation tag prepended to the public key when proving possession.
+pub const POP_DST: &[u8] = b"GADGET_BLS_BN254_POP";
+
+fn pop_message(public: &ArkBlsBn254Public) -> Vec<u8> {
+    let mut msg = POP_DST.to_vec();
+    msg.extend_from_slice(&public.to_bytes());
+    msg
+}
+
+impl ProofOfPossession for ArkBlsBn254 {
+    fn prove_possession(secret: &mut Self::Secret) -> Result<Self::Signature> {
+        let public = Self::public_from_secret(secret);
+        Self::sign_with_secret(secret, &pop_message(&public))
+    }
+
+    fn verify_possession(public: &Self::Public, proof: &Self::Signature) -> bool {
+        Self::verify(public, &pop_message(public), proof)
+    }
+}
+
+/// Get the G1 public key for `secret`, as registered alongside the G2 key by EigenLayer.
+#[must_use]
+pub fn g1_public_from_secret(secret: &ArkBlsBn254Secret) -> G1Affine {
+    G1Affine::generator()
+        .mul_bigint(secret.0.into_bigint())
+        .into_affine()
+}
+
+/// The parameters for registering a BN254 key with EigenLayer's `BLSApkRegistry`, mirroring its
+/// `PubkeyRegistrationParams`.
+///
+/// The signature is the operator's proof of possession of the key, made over the G1 point
+/// returned by the registry coordinator's `pubkeyRegistrationMessageHash(operator)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PubkeyRegistrationParams {
+    pub pubkey_registration_signature: G1Affine,
+    pub pubkey_g1: G1Affine,
+    pub pubkey_g2: G2Affine,
+}
+
+impl PubkeyRegistrationParams {
+    /// Sign `message_hash` with `secret` to create the registration parameters.
+    #[must_use]
+    pub fn new(secret: &ArkBlsBn254Secret, message_hash: G1Affine) -> Self {
+        let signature = message_hash
+            .mul_bigint(secret.0.into_bigint())
+            .into_affine();
+        Self {
+            pubkey_registration_signature: signature,
+            pubkey_g1: g1_public_from_secret(secret),
+            pubkey_g2: ArkBlsBn254::public_from_secret(secret).0,
+        }
+    }
+
+    /// Verify the parameters the same way `BLSApkRegistry.registerBLSPublicKey` does.
+    ///
+    /// This also checks that the G1 and G2 public keys share the same secret key.
+    #[must_use]
+    pub fn verify(&self, message_hash: G1Affine) -> bool {
+        let signature = self.pubkey_registration_signature;
+        if !signature.is_on_curve() || !signature.is_in_correct_subgroup_assuming_on_curve() {
+            return false;
+        }
+
+        let gamma = self.gamma(message_hash);
+        let lhs = (self.pubkey_g1 * gamma + signature).into_affine();
+        let rhs = (G1Affine::generator() * gamma + message_hash).into_affine();
+
+        // e(sig + gamma * pk_g1, -g2) * e(H + gamma * g1, pk_g2) == 1
+        Bn254::multi_pairing([lhs, rhs], [-G2Affine::generator(), self.pubkey_g2])
+            .0
+            .is_one()
+    }
+
+    /// The random linear combination coefficient, computed as
+    /// `keccak256(abi.encodePacked(sig, pubkeyG1, pubkeyG2, messageHash)) % r`.
+    fn gamma(&self, message_hash: G1Affine) -> Fr {
+        use sha3::{Digest as _, Keccak256};
+
+        fn push_fq(hasher: &mut Keccak256, fq: Fq) {
+            hasher.update(fq.into_bigint().to_bytes_be());
+        }
+
+        let mut hasher = Keccak256::new();
+        for point in [self.pubkey_registration_signature, self.pubkey_g1] {
+            push_fq(&mut hasher, point.x);
+            push_fq(&mut hasher, point.y);
+        }
+        // Solidity orders the G2 coordinates with the imaginary part first
+        for coordinate in [self.pubkey_g2.x, self.pubkey_g2.y] {
+            push_fq(&mut hasher, coordinate.c1);
+            push_fq(&mut hasher, coordinate.c0);
+        }
+        push_fq(&mut hasher, message_hash.x);
+        push_fq(&mut hasher, message_hash.y);
+
+        Fr::from_be_bytes_mod_order(&hasher.finalize())
+    }
 }
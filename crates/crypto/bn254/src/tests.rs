@@ -15,6 +15,11 @@ mod ark_bn254_crypto_tests {
     gadget_crypto_core::impl_crypto_tests!(ArkBlsBn254, ArkBlsBn254Secret, ArkBlsBn254Signature);
 }
 
+mod ark_bn254_aggregation_tests {
+    use super::*;
+    gadget_crypto_core::impl_aggregation_tests!(ArkBlsBn254);
+}
+
 #[test]
 fn test_key_generation() {
     // Test seed-based generation is deterministic
@@ -265,3 +270,161 @@ fn test_key_generation_edge_cases() {
         assert!(ArkBlsBn254::verify(&public, msg, &signature));
     }
 }
+
+mod ark_bn254_pop_tests {
+    use super::*;
+    gadget_crypto_core::impl_proof_of_possession_tests!(ArkBlsBn254);
+}
+
+#[test]
+fn test_pubkey_registration_params() {
+    let secret = ArkBlsBn254::generate_with_seed(Some(b"registration")).unwrap();
+    let message_hash = hash_to_curve(b"pubkeyRegistrationMessageHash");
+    let params = PubkeyRegistrationParams::new(&secret, message_hash);
+    assert!(params.verify(message_hash));
+
+    // Wrong message
+    assert!(!params.verify(hash_to_curve(b"another operator")));
+
+    // G1 and G2 keys must belong to the same secret
+    let other = ArkBlsBn254::generate_with_seed(Some(b"other")).unwrap();
+    let mismatched = PubkeyRegistrationParams {
+        pubkey_g1: g1_public_from_secret(&other),
+        ..params.clone()
+    };
+    assert!(!mismatched.verify(message_hash));
+}
+
+/// RFC 9380, Appendix K.1: `expand_message_xmd` with SHA-256
+#[test]
+fn test_expand_message_xmd_vectors() {
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    let vectors: [(&[u8], &str); 2] = [
+        (
+            b"",
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+        ),
+        (
+            b"abc",
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+        ),
+    ];
+
+    for (msg, expected) in vectors {
+        let uniform_bytes = hashing::expand_message_xmd(msg, dst, 0x20);
+        assert_eq!(hex::encode(uniform_bytes), expected);
+    }
+
+    assert_eq!(hashing::expand_message_xmd(b"abc", dst, 0x80).len(), 0x80);
+}
+
+#[test]
+fn test_rfc9380_hash_to_curve() {
+    let dst = b"GADGET-TEST-V01-CS01-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+    let scheme = HashToCurve::Rfc9380 { dst };
+
+    for msg in [&b""[..], b"abc", &[0xFF; 1000]] {
+        let point = scheme.hash(msg);
+        assert!(point.is_on_curve());
+        assert!(point.is_in_correct_subgroup_assuming_on_curve());
+        assert_eq!(point, scheme.hash(msg));
+        assert_ne!(point, HashToCurve::Legacy.hash(msg));
+    }
+
+    let other = HashToCurve::Rfc9380 { dst: b"OTHER-DST" };
+    assert_ne!(scheme.hash(b"abc"), other.hash(b"abc"));
+
+    // Tags over 255 bytes are hashed down rather than rejected
+    let long_dst = [b'D'; 300];
+    let point = HashToCurve::Rfc9380 { dst: &long_dst }.hash(b"abc");
+    assert!(point.is_on_curve());
+}
+
+/// `BN254G1_XMD:SHA-256_SVDW_RO_` vectors for the RFC 9380 test messages, as used by gnark-crypto
+#[test]
+fn test_rfc9380_hash_to_curve_vectors() {
+    fn fq(hex: &str) -> Fq {
+        let hex = hex.trim_start_matches("0x");
+        Fq::from(BigUint::parse_bytes(hex.as_bytes(), 16).unwrap())
+    }
+
+    let scheme = HashToCurve::Rfc9380 {
+        dst: b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_",
+    };
+    let q128 = [&b"q128_"[..], &[b'q'; 128]].concat();
+    let a512 = [&b"a512_"[..], &[b'a'; 512]].concat();
+    let vectors: [(&[u8], &str, &str); 5] = [
+        (
+            b"",
+            "0xa976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+            "0x2925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5",
+        ),
+        (
+            b"abc",
+            "0x23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+            "0x4142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d",
+        ),
+        (
+            b"abcdef0123456789",
+            "0x187dbf1c3c89aceceef254d6548d7163fdfa43084145f92c4c91c85c21442d4a",
+            "0xabd99d5b0000910b56058f9cc3b0ab0a22d47cf27615f588924fac1e5c63b4d",
+        ),
+        (
+            &q128,
+            "0xfe2b0743575324fc452d590d217390ad48e5a16cf051bee5c40a2eba233f5c",
+            "0x794211e0cc72d3cbbdf8e4e5cd6e7d7e78d101ff94862caae8acbe63e9fdc78",
+        ),
+        (
+            &a512,
+            "0x1b05dc540bd79fd0fea4fbb07de08e94fc2e7bd171fe025c479dc212a2173ce",
+            "0x1bf028afc00c0f843d113758968f580640541728cfc6d32ced9779aa613cd9b0",
+        ),
+    ];
+
+    for (msg, x, y) in vectors {
+        let point = scheme.hash(msg);
+        assert_eq!(
+            (point.x, point.y),
+            (fq(x), fq(y)),
+            "msg: {:?}",
+            String::from_utf8_lossy(msg)
+        );
+    }
+}
+
+#[test]
+fn test_signatures_are_bound_to_their_scheme() {
+    let mut secret = ArkBlsBn254::generate_with_seed(None).unwrap();
+    let public = ArkBlsBn254::public_from_secret(&secret);
+    let msg = b"operator attestation";
+
+    let blueprint_a = HashToCurve::Rfc9380 {
+        dst: b"BLUEPRINT-A-V01",
+    };
+    let blueprint_b = HashToCurve::Rfc9380 {
+        dst: b"BLUEPRINT-B-V01",
+    };
+
+    let signature = ArkBlsBn254::sign_with_scheme(&mut secret, msg, blueprint_a).unwrap();
+    assert!(ArkBlsBn254::verify_with_scheme(
+        &public,
+        msg,
+        &signature,
+        blueprint_a
+    ));
+    assert!(!ArkBlsBn254::verify_with_scheme(
+        &public,
+        msg,
+        &signature,
+        blueprint_b
+    ));
+    assert!(!ArkBlsBn254::verify(&public, msg, &signature));
+
+    // The legacy scheme is what the `KeyType` methods use
+    let legacy = ArkBlsBn254::sign_with_scheme(&mut secret, msg, HashToCurve::Legacy).unwrap();
+    assert_eq!(
+        legacy,
+        ArkBlsBn254::sign_with_secret(&mut secret, msg).unwrap()
+    );
+    assert!(ArkBlsBn254::verify(&public, msg, &legacy));
+}
@@ -18,6 +18,14 @@ pub enum KeyTypeId {
     Bls377,
     #[cfg(any(feature = "zebra", feature = "tangle"))]
     Ed25519,
+    #[cfg(feature = "k256")]
+    K256Schnorr,
+    #[cfg(feature = "p256")]
+    P256,
+    #[cfg(feature = "frost")]
+    FrostEd25519,
+    #[cfg(feature = "frost")]
+    FrostSecp256k1,
 }
 
 impl KeyTypeId {
@@ -34,6 +42,14 @@ impl KeyTypeId {
         Self::Bls377,
         #[cfg(any(feature = "zebra", feature = "tangle"))]
         Self::Ed25519,
+        #[cfg(feature = "k256")]
+        Self::K256Schnorr,
+        #[cfg(feature = "p256")]
+        Self::P256,
+        #[cfg(feature = "frost")]
+        Self::FrostEd25519,
+        #[cfg(feature = "frost")]
+        Self::FrostSecp256k1,
     ];
 
     pub fn name(&self) -> &'static str {
@@ -50,13 +66,23 @@ impl KeyTypeId {
             Self::Bls377 => "bls377",
             #[cfg(any(feature = "zebra", feature = "tangle"))]
             Self::Ed25519 => "ed25519",
+            #[cfg(feature = "k256")]
+            Self::K256Schnorr => "k256-schnorr",
+            #[cfg(feature = "p256")]
+            Self::P256 => "p256",
+            #[cfg(feature = "frost")]
+            Self::FrostEd25519 => "frost-ed25519",
+            #[cfg(feature = "frost")]
+            Self::FrostSecp256k1 => "frost-secp256k1",
             #[cfg(all(
                 not(feature = "bn254"),
                 not(feature = "k256"),
                 not(feature = "sr25519-schnorrkel"),
                 not(feature = "bls"),
                 not(feature = "zebra"),
-                not(feature = "tangle")
+                not(feature = "tangle"),
+                not(feature = "p256"),
+                not(feature = "frost")
             ))]
             _ => unreachable!("All possible variants are feature-gated"),
         }
@@ -83,6 +109,8 @@ pub trait KeyType: Sized + 'static {
         gadget_std::rand::thread_rng()
     }
 
+    /// Without `std` there is no entropy source, so this is deterministic. Anything that relies on
+    /// unpredictable randomness, such as batch verification, must not use it.
     #[cfg(not(feature = "std"))]
     fn get_rng() -> impl gadget_std::CryptoRng + gadget_std::Rng {
         gadget_std::test_rng()
@@ -105,6 +133,85 @@ pub trait KeyType: Sized + 'static {
         msg: &[u8; 32],
     ) -> Result<Self::Signature, Self::Error>;
     fn verify(public: &Self::Public, msg: &[u8], signature: &Self::Signature) -> bool;
+
+    /// Verify a batch of signatures, returning `true` only if every signature is valid.
+    ///
+    /// Key types with native batch verification override this. Use
+    /// [`KeyType::find_invalid_signatures`] to locate the failures in a rejected batch.
+    fn verify_batch(items: &[(&Self::Public, &[u8], &Self::Signature)]) -> bool {
+        items
+            .iter()
+            .all(|(public, msg, signature)| Self::verify(public, msg, signature))
+    }
+
+    /// Returns the indices of the invalid signatures in `items`.
+    ///
+    /// The batch is checked with [`KeyType::verify_batch`] first, falling back to verifying each
+    /// signature individually only if it is rejected.
+    fn find_invalid_signatures(items: &[(&Self::Public, &[u8], &Self::Signature)]) -> Vec<usize> {
+        if Self::verify_batch(items) {
+            return Vec::new();
+        }
+
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, (public, msg, signature))| !Self::verify(public, msg, signature))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Trait for key types whose signatures can be aggregated, such as BLS.
+///
+/// Aggregation is only safe against rogue-key attacks if every public key involved has a
+/// verified proof of possession.
+pub trait AggregatableSignature: KeyType {
+    /// Combine the signatures into a single aggregate signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `signatures` is empty.
+    fn aggregate_signatures(signatures: &[Self::Signature])
+        -> Result<Self::Signature, Self::Error>;
+
+    /// Combine the public keys into a single aggregate public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `public_keys` is empty.
+    fn aggregate_public_keys(public_keys: &[Self::Public]) -> Result<Self::Public, Self::Error>;
+
+    /// Verify an aggregate signature over the same message signed by every public key.
+    fn verify_aggregate(
+        public_keys: &[Self::Public],
+        msg: &[u8],
+        signature: &Self::Signature,
+    ) -> bool {
+        Self::aggregate_public_keys(public_keys)
+            .is_ok_and(|public| Self::verify(&public, msg, signature))
+    }
+
+    /// Verify an aggregate signature where each public key signed its own message.
+    ///
+    /// Returns `false` if `items` is empty.
+    fn verify_multi_message(items: &[(&Self::Public, &[u8])], signature: &Self::Signature) -> bool;
+}
+
+/// Trait for key types that can prove possession of their secret key.
+///
+/// A proof of possession is a signature over the public key itself. Verifying it before
+/// accepting a key rules out rogue-key attacks against [`AggregatableSignature`].
+pub trait ProofOfPossession: KeyType {
+    /// Create a proof that the holder of `secret` knows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if signing fails.
+    fn prove_possession(secret: &mut Self::Secret) -> Result<Self::Signature, Self::Error>;
+
+    /// Verify a proof of possession for `public`.
+    fn verify_possession(public: &Self::Public, proof: &Self::Signature) -> bool;
 }
 
 #[cfg(feature = "clap")]
@@ -116,6 +223,8 @@ impl clap::ValueEnum for KeyTypeId {
             Self::Ecdsa,
             Self::Bls381,
             Self::Bn254,
+            #[cfg(feature = "p256")]
+            Self::P256,
         ]
     }
 
@@ -135,6 +244,8 @@ impl clap::ValueEnum for KeyTypeId {
             Self::Bn254 => {
                 clap::builder::PossibleValue::new("blsbn254").help("Boneh-Lynn-Shacham on BN254")
             }
+            #[cfg(feature = "p256")]
+            Self::P256 => clap::builder::PossibleValue::new("p256").help("ECDSA on NIST P-256"),
             _ => return None,
         })
     }
@@ -214,6 +325,40 @@ macro_rules! impl_crypto_tests {
             );
         }
 
+        #[test]
+        fn test_verify_batch() {
+            let messages: [&[u8]; 4] = [b"one", b"two", b"three", b"four"];
+            // Distinct seeds, so every signature is checked against a different key
+            let mut keys = (1..=messages.len())
+                .map(|i| {
+                    let seed = [u8::try_from(i).unwrap(); 32];
+                    let secret = <$crypto_type>::generate_with_seed(Some(&seed)).unwrap();
+                    let public = <$crypto_type>::public_from_secret(&secret);
+                    (secret, public)
+                })
+                .collect::<gadget_std::vec::Vec<_>>();
+            assert!(keys.windows(2).all(|pair| pair[0].1 != pair[1].1));
+            let signatures = keys
+                .iter_mut()
+                .zip(messages)
+                .map(|((secret, _), msg)| <$crypto_type>::sign_with_secret(secret, msg).unwrap())
+                .collect::<gadget_std::vec::Vec<_>>();
+
+            let mut items = keys
+                .iter()
+                .zip(messages)
+                .zip(&signatures)
+                .map(|(((_, public), msg), signature)| (public, msg, signature))
+                .collect::<gadget_std::vec::Vec<_>>();
+            assert!(<$crypto_type>::verify_batch(&items));
+            assert!(<$crypto_type>::verify_batch(&[]));
+            assert!(<$crypto_type>::find_invalid_signatures(&items).is_empty());
+
+            items[2].1 = b"tampered";
+            assert!(!<$crypto_type>::verify_batch(&items));
+            assert_eq!(<$crypto_type>::find_invalid_signatures(&items), [2]);
+        }
+
         #[test]
         fn test_key_comparison() {
             let secret1 = <$crypto_type>::generate_with_seed(None).unwrap();
@@ -230,3 +375,125 @@ macro_rules! impl_crypto_tests {
         }
     };
 }
+
+#[macro_export]
+macro_rules! impl_aggregation_tests {
+    ($crypto_type:ty) => {
+        use $crate::AggregatableSignature;
+
+        fn aggregation_keys() -> gadget_std::vec::Vec<(
+            <$crypto_type as $crate::KeyType>::Secret,
+            <$crypto_type as $crate::KeyType>::Public,
+        )> {
+            (1u8..=3)
+                .map(|i| {
+                    let secret = <$crypto_type>::generate_with_seed(Some(&[i; 32])).unwrap();
+                    let public = <$crypto_type>::public_from_secret(&secret);
+                    (secret, public)
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_aggregate_same_message() {
+            let message = b"aggregate me";
+            let mut keys = aggregation_keys();
+            let signatures = keys
+                .iter_mut()
+                .map(|(secret, _)| <$crypto_type>::sign_with_secret(secret, message).unwrap())
+                .collect::<gadget_std::vec::Vec<_>>();
+            let public_keys = keys
+                .iter()
+                .map(|(_, public)| public.clone())
+                .collect::<gadget_std::vec::Vec<_>>();
+
+            let aggregate = <$crypto_type>::aggregate_signatures(&signatures).unwrap();
+            assert!(<$crypto_type>::verify_aggregate(
+                &public_keys,
+                message,
+                &aggregate
+            ));
+            assert!(!<$crypto_type>::verify_aggregate(
+                &public_keys,
+                b"another message",
+                &aggregate
+            ));
+            assert!(!<$crypto_type>::verify_aggregate(
+                &public_keys[1..],
+                message,
+                &aggregate
+            ));
+
+            let aggregate_public = <$crypto_type>::aggregate_public_keys(&public_keys).unwrap();
+            assert!(<$crypto_type>::verify(
+                &aggregate_public,
+                message,
+                &aggregate
+            ));
+        }
+
+        #[test]
+        fn test_aggregate_multi_message() {
+            let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+            let mut keys = aggregation_keys();
+            let signatures = keys
+                .iter_mut()
+                .zip(messages)
+                .map(|((secret, _), msg)| <$crypto_type>::sign_with_secret(secret, msg).unwrap())
+                .collect::<gadget_std::vec::Vec<_>>();
+            let aggregate = <$crypto_type>::aggregate_signatures(&signatures).unwrap();
+
+            let items = keys
+                .iter()
+                .zip(messages)
+                .map(|((_, public), msg)| (public, msg))
+                .collect::<gadget_std::vec::Vec<_>>();
+            assert!(<$crypto_type>::verify_multi_message(&items, &aggregate));
+
+            let mut swapped = items.clone();
+            swapped[0].1 = messages[1];
+            assert!(!<$crypto_type>::verify_multi_message(&swapped, &aggregate));
+            assert!(!<$crypto_type>::verify_multi_message(&[], &aggregate));
+        }
+
+        #[test]
+        fn test_aggregate_empty() {
+            assert!(<$crypto_type>::aggregate_signatures(&[]).is_err());
+            assert!(<$crypto_type>::aggregate_public_keys(&[]).is_err());
+            assert!(!<$crypto_type>::verify_aggregate(
+                &[],
+                b"message",
+                &<$crypto_type>::sign_with_secret(&mut aggregation_keys()[0].0, b"message")
+                    .unwrap()
+            ));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_proof_of_possession_tests {
+    ($crypto_type:ty) => {
+        use $crate::ProofOfPossession;
+
+        #[test]
+        fn test_proof_of_possession() {
+            let mut secret = <$crypto_type>::generate_with_seed(Some(&[1u8; 32])).unwrap();
+            let public = <$crypto_type>::public_from_secret(&secret);
+            let other = <$crypto_type>::public_from_secret(
+                &<$crypto_type>::generate_with_seed(Some(&[2u8; 32])).unwrap(),
+            );
+
+            let proof = <$crypto_type>::prove_possession(&mut secret).unwrap();
+            assert!(<$crypto_type>::verify_possession(&public, &proof));
+            assert!(!<$crypto_type>::verify_possession(&other, &proof));
+
+            // A plain signature over the public key must not double as a proof
+            let signature = <$crypto_type>::sign_with_secret(
+                &mut secret,
+                &$crate::KeyEncoding::to_bytes(&public),
+            )
+            .unwrap();
+            assert!(!<$crypto_type>::verify_possession(&public, &signature));
+        }
+    };
+}
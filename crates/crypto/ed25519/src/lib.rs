@@ -176,4 +176,17 @@ impl KeyType for Ed25519Zebra {
     fn verify(public: &Self::Public, msg: &[u8], signature: &Self::Signature) -> bool {
         public.0.verify(&signature.0, msg).is_ok()
     }
+
+    #[cfg(feature = "std")]
+    fn verify_batch(items: &[(&Self::Public, &[u8], &Self::Signature)]) -> bool {
+        let mut verifier = ed25519_zebra::batch::Verifier::new();
+        for (public, msg, signature) in items {
+            verifier.queue((
+                ed25519_zebra::VerificationKeyBytes::from(public.0),
+                signature.0,
+                msg,
+            ));
+        }
+        verifier.verify(Self::get_rng()).is_ok()
+    }
 }
@@ -0,0 +1,27 @@
+use frost_core::Ciphersuite;
+use gadget_std::string::{String, ToString};
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error)]
+pub enum FrostError {
+    #[error("FROST error: {0}")]
+    Frost(String),
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Invalid party: {0}")]
+    InvalidParty(String),
+    #[error("Party {0} received different round 1 commitments")]
+    InconsistentBroadcast(u16),
+    #[error("Missing round 2 package for party {0}")]
+    MissingPackage(u16),
+    #[error("Invalid key share: {0}")]
+    InvalidKeyShare(String),
+}
+
+impl<C: Ciphersuite> From<frost_core::Error<C>> for FrostError {
+    fn from(error: frost_core::Error<C>) -> Self {
+        FrostError::Frost(error.to_string())
+    }
+}
+
+pub type Result<T> = gadget_std::result::Result<T, FrostError>;
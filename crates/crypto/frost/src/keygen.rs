@@ -0,0 +1,176 @@
+//! Distributed key generation, following the FROST paper's Pedersen DKG.
+//!
+//! | Round | Message          | Sent as   |
+//! |-------|------------------|-----------|
+//! | 1     | [`KeygenRound1`] | Broadcast |
+//! | Echo  | [`KeygenEcho`]   | Broadcast |
+//! | 2     | [`KeygenRound2`] | P2P       |
+//!
+//! FROST's DKG assumes round 1 is delivered over a reliable broadcast channel, so every party sees
+//! the same commitments. Gossip delivery alone does not guarantee this, so after round 1 every
+//! party echoes a hash of all the commitments it received, and aborts before sending any secret
+//! shares if another party saw different ones.
+
+use crate::error::{FrostError, Result};
+use crate::{identifier, FrostCiphersuite, KeyShare};
+use frost_core::keys::dkg;
+use frost_core::Ciphersuite;
+use futures::SinkExt;
+use gadget_crypto_hashing::sha2_256;
+use gadget_std::collections::BTreeMap;
+use gadget_std::format;
+use gadget_std::rand::{CryptoRng, RngCore};
+use gadget_std::string::ToString;
+use gadget_std::vec::Vec;
+use round_based::rounds_router::simple_store::RoundInput;
+use round_based::rounds_router::RoundsRouter;
+use round_based::ProtocolMessage;
+use round_based::{Delivery, MessageDestination, Mpc, MpcParty, Outgoing, PartyIndex};
+use serde::{Deserialize, Serialize};
+
+/// Messages of the key generation protocol
+#[derive(Clone, ProtocolMessage, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum KeygenMsg<C: Ciphersuite> {
+    Round1(KeygenRound1<C>),
+    Echo(KeygenEcho),
+    Round2(KeygenRound2<C>),
+}
+
+/// A party's commitment to its secret polynomial, broadcast to all parties
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KeygenRound1<C: Ciphersuite> {
+    pub package: dkg::round1::Package<C>,
+}
+
+/// A hash of every party's round 1 commitment, in party order, as received by the sender
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeygenEcho {
+    pub commitments_hash: [u8; 32],
+}
+
+/// A party's secret share for a single recipient
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KeygenRound2<C: Ciphersuite> {
+    pub package: dkg::round2::Package<C>,
+}
+
+/// Run distributed key generation as party `i` of `n`, producing a key that any `t` parties can sign with.
+///
+/// # Errors
+///
+/// Returns an error if the parameters are invalid, the network fails, another party sends an
+/// invalid package, or the parties received different round 1 commitments.
+pub async fn run_keygen<C, M, R>(
+    party: M,
+    i: PartyIndex,
+    n: u16,
+    t: u16,
+    rng: &mut R,
+) -> Result<KeyShare<C>>
+where
+    C: FrostCiphersuite,
+    M: Mpc<ProtocolMessage = KeygenMsg<C>>,
+    R: RngCore + CryptoRng,
+{
+    if i >= n {
+        return Err(FrostError::InvalidParty(format!(
+            "party index {i} is out of range for {n} parties"
+        )));
+    }
+
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incoming, mut outgoing) = delivery.split();
+
+    let mut rounds = RoundsRouter::<KeygenMsg<C>>::builder();
+    let round1 = rounds.add_round(RoundInput::<KeygenRound1<C>>::broadcast(i, n));
+    let echo = rounds.add_round(RoundInput::<KeygenEcho>::broadcast(i, n));
+    let round2 = rounds.add_round(RoundInput::<KeygenRound2<C>>::p2p(i, n));
+    let mut rounds = rounds.listen(incoming);
+
+    // Round 1: commit to a secret polynomial
+    let (round1_secret, package) = dkg::part1(identifier::<C>(i)?, n, t, &mut *rng)?;
+    outgoing
+        .send(Outgoing {
+            recipient: MessageDestination::AllParties,
+            msg: KeygenMsg::Round1(KeygenRound1 {
+                package: package.clone(),
+            }),
+        })
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?;
+
+    let mut commitments = rounds
+        .complete(round1)
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?
+        .into_iter_indexed()
+        .map(|(j, _, msg)| (j, msg.package))
+        .collect::<BTreeMap<_, _>>();
+
+    // Echo: check that every party received the same commitments before revealing any shares
+    let _ = commitments.insert(i, package);
+    let commitments_hash = commitments_hash(&commitments)?;
+    outgoing
+        .send(Outgoing {
+            recipient: MessageDestination::AllParties,
+            msg: KeygenMsg::Echo(KeygenEcho { commitments_hash }),
+        })
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?;
+
+    let echoes = rounds
+        .complete(echo)
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?;
+    if let Some((j, _, _)) = echoes
+        .into_iter_indexed()
+        .find(|(_, _, echo)| echo.commitments_hash != commitments_hash)
+    {
+        return Err(FrostError::InconsistentBroadcast(j));
+    }
+
+    let _ = commitments.remove(&i);
+    let round1_packages = commitments
+        .into_iter()
+        .map(|(j, package)| Ok((identifier::<C>(j)?, package)))
+        .collect::<Result<BTreeMap<_, _>>>()?;
+
+    // Round 2: send each party its share of our polynomial
+    let (round2_secret, mut round2_packages) = dkg::part2(round1_secret, &round1_packages)?;
+    for j in (0..n).filter(|j| *j != i) {
+        let package = round2_packages
+            .remove(&identifier::<C>(j)?)
+            .ok_or(FrostError::MissingPackage(j))?;
+        outgoing
+            .send(Outgoing {
+                recipient: MessageDestination::OneParty(j),
+                msg: KeygenMsg::Round2(KeygenRound2 { package }),
+            })
+            .await
+            .map_err(|e| FrostError::Network(e.to_string()))?;
+    }
+
+    let round2_packages = rounds
+        .complete(round2)
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?
+        .into_iter_indexed()
+        .map(|(j, _, msg)| Ok((identifier::<C>(j)?, msg.package)))
+        .collect::<Result<BTreeMap<_, _>>>()?;
+
+    let (key_package, public_key_package) =
+        dkg::part3(&round2_secret, &round1_packages, &round2_packages)?;
+    KeyShare::new(key_package, public_key_package)
+}
+
+/// Hash every party's round 1 package, in party order
+fn commitments_hash<C: Ciphersuite>(
+    commitments: &BTreeMap<PartyIndex, dkg::round1::Package<C>>,
+) -> Result<[u8; 32]> {
+    let packages = commitments.values().collect::<Vec<_>>();
+    let bytes = serde_json::to_vec(&packages).map_err(|e| FrostError::Frost(e.to_string()))?;
+    Ok(sha2_256(&bytes))
+}
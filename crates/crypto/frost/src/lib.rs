@@ -0,0 +1,124 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Threshold Schnorr signatures using [FROST](https://eprint.iacr.org/2020/852).
+//!
+//! This crate provides distributed key generation ([`keygen`]) and two-round threshold signing
+//! ([`signing`]) as `round_based` protocols, for Ed25519 and secp256k1. Both protocols run over
+//! any `round_based` delivery, including `gadget_networking`'s `NetworkDeliveryWrapper`.
+//!
+//! The resulting [`KeyShare`]s can be stored in the keystore under [`FrostCiphersuite::KEY_TYPE_ID`].
+
+pub mod error;
+pub mod keygen;
+pub mod signing;
+
+#[cfg(test)]
+mod tests;
+
+pub use frost_core;
+pub use frost_ed25519::Ed25519Sha512;
+pub use frost_secp256k1::Secp256K1Sha256;
+pub use keygen::{run_keygen, KeygenMsg};
+pub use signing::{run_signing, SigningMsg};
+
+use crate::error::{FrostError, Result};
+use frost_core::keys::{KeyPackage, PublicKeyPackage};
+use frost_core::{Ciphersuite, Identifier, Signature, VerifyingKey};
+use gadget_crypto_core::{KeyEncoding, KeyTypeId};
+use gadget_std::string::ToString;
+use gadget_std::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// A FROST ciphersuite whose key shares can be stored in the keystore
+pub trait FrostCiphersuite: Ciphersuite + Send + Sync + Unpin {
+    /// The key type the shares are stored under
+    const KEY_TYPE_ID: KeyTypeId;
+}
+
+impl FrostCiphersuite for Ed25519Sha512 {
+    const KEY_TYPE_ID: KeyTypeId = KeyTypeId::FrostEd25519;
+}
+
+impl FrostCiphersuite for Secp256K1Sha256 {
+    const KEY_TYPE_ID: KeyTypeId = KeyTypeId::FrostSecp256k1;
+}
+
+/// Get the FROST identifier of the party at index `party` in key generation.
+///
+/// FROST identifiers are non-zero, so party `i` is assigned the identifier `i + 1`.
+///
+/// # Errors
+///
+/// Returns an error if `party` is `u16::MAX`.
+pub fn identifier<C: Ciphersuite>(party: u16) -> Result<Identifier<C>> {
+    let id = party
+        .checked_add(1)
+        .ok_or_else(|| FrostError::InvalidParty(party.to_string()))?;
+    Ok(Identifier::try_from(id)?)
+}
+
+/// A party's share of a FROST key, as produced by [`run_keygen`]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KeyShare<C: Ciphersuite> {
+    key_package: KeyPackage<C>,
+    public_key_package: PublicKeyPackage<C>,
+}
+
+impl<C: Ciphersuite> KeyShare<C> {
+    /// Create a key share from the packages produced by key generation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the packages belong to different group keys.
+    pub fn new(
+        key_package: KeyPackage<C>,
+        public_key_package: PublicKeyPackage<C>,
+    ) -> Result<Self> {
+        if key_package.verifying_key() != public_key_package.verifying_key() {
+            return Err(FrostError::InvalidKeyShare(
+                "key package and public key package have different group keys".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            key_package,
+            public_key_package,
+        })
+    }
+
+    /// This party's secret share and identifier
+    pub fn key_package(&self) -> &KeyPackage<C> {
+        &self.key_package
+    }
+
+    /// The public shares of all parties
+    pub fn public_key_package(&self) -> &PublicKeyPackage<C> {
+        &self.public_key_package
+    }
+
+    /// This party's FROST identifier
+    pub fn identifier(&self) -> &Identifier<C> {
+        self.key_package.identifier()
+    }
+
+    /// The group public key
+    pub fn verifying_key(&self) -> &VerifyingKey<C> {
+        self.public_key_package.verifying_key()
+    }
+
+    /// Verify a group signature over `msg`
+    pub fn verify(&self, msg: &[u8], signature: &Signature<C>) -> bool {
+        self.verifying_key().verify(msg, signature).is_ok()
+    }
+}
+
+impl<C: Ciphersuite> KeyEncoding for KeyShare<C> {
+    fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("key shares always serialize")
+    }
+
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, serde::de::value::Error> {
+        serde_json::from_slice(bytes).map_err(|e| serde::de::Error::custom(e.to_string()))
+    }
+}
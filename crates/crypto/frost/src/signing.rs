@@ -0,0 +1,146 @@
+//! Two-round threshold signing.
+//!
+//! | Round | Message           | Sent as   |
+//! |-------|-------------------|-----------|
+//! | 1     | [`SigningRound1`] | Broadcast |
+//! | 2     | [`SigningRound2`] | Broadcast |
+//!
+//! Every signer receives every signature share, so all of them output the aggregated signature.
+
+use crate::error::{FrostError, Result};
+use crate::{identifier, FrostCiphersuite, KeyShare};
+use frost_core::round1::SigningCommitments;
+use frost_core::round2::SignatureShare;
+use frost_core::{Ciphersuite, Signature, SigningPackage};
+use futures::SinkExt;
+use gadget_std::collections::BTreeMap;
+use gadget_std::format;
+use gadget_std::rand::{CryptoRng, RngCore};
+use gadget_std::string::ToString;
+use gadget_std::vec::Vec;
+use round_based::rounds_router::simple_store::RoundInput;
+use round_based::rounds_router::RoundsRouter;
+use round_based::ProtocolMessage;
+use round_based::{Delivery, MessageDestination, Mpc, MpcParty, Outgoing, PartyIndex};
+use serde::{Deserialize, Serialize};
+
+/// Messages of the signing protocol
+#[derive(Clone, ProtocolMessage, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum SigningMsg<C: Ciphersuite> {
+    Round1(SigningRound1<C>),
+    Round2(SigningRound2<C>),
+}
+
+/// A signer's nonce commitments
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SigningRound1<C: Ciphersuite> {
+    pub commitments: SigningCommitments<C>,
+}
+
+/// A signer's share of the signature
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SigningRound2<C: Ciphersuite> {
+    pub share: SignatureShare<C>,
+}
+
+/// Sign `message` as party `i` of a signing session.
+///
+/// `signers[j]` is the key generation index of the `j`th party in this session, so `signers[i]`
+/// must be the index our `key_share` was generated for. At least the threshold number of signers
+/// must take part.
+///
+/// # Errors
+///
+/// Returns an error if the signer set doesn't match `key_share`, the network fails, or another
+/// signer sends an invalid signature share.
+pub async fn run_signing<C, M, R>(
+    party: M,
+    i: PartyIndex,
+    signers: &[u16],
+    key_share: &KeyShare<C>,
+    message: &[u8],
+    rng: &mut R,
+) -> Result<Signature<C>>
+where
+    C: FrostCiphersuite,
+    M: Mpc<ProtocolMessage = SigningMsg<C>>,
+    R: RngCore + CryptoRng,
+{
+    let n = u16::try_from(signers.len())
+        .map_err(|_| FrostError::InvalidParty(format!("too many signers: {}", signers.len())))?;
+    let ids = signers
+        .iter()
+        .map(|j| identifier::<C>(*j))
+        .collect::<Result<Vec<_>>>()?;
+    match ids.get(usize::from(i)) {
+        Some(id) if id == key_share.identifier() => {}
+        Some(_) => {
+            return Err(FrostError::InvalidParty(format!(
+                "signer {i} is not the holder of this key share"
+            )))
+        }
+        None => {
+            return Err(FrostError::InvalidParty(format!(
+                "party index {i} is out of range for {n} signers"
+            )))
+        }
+    }
+
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incoming, mut outgoing) = delivery.split();
+
+    let mut rounds = RoundsRouter::<SigningMsg<C>>::builder();
+    let round1 = rounds.add_round(RoundInput::<SigningRound1<C>>::broadcast(i, n));
+    let round2 = rounds.add_round(RoundInput::<SigningRound2<C>>::broadcast(i, n));
+    let mut rounds = rounds.listen(incoming);
+
+    // Round 1: commit to the signing nonces
+    let (nonces, commitments) =
+        frost_core::round1::commit(key_share.key_package().signing_share(), rng);
+    outgoing
+        .send(Outgoing {
+            recipient: MessageDestination::AllParties,
+            msg: SigningMsg::Round1(SigningRound1 { commitments }),
+        })
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?;
+
+    let mut all_commitments = rounds
+        .complete(round1)
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?
+        .into_iter_indexed()
+        .map(|(j, _, msg)| (ids[usize::from(j)], msg.commitments))
+        .collect::<BTreeMap<_, _>>();
+    let _ = all_commitments.insert(ids[usize::from(i)], commitments);
+    let signing_package = SigningPackage::new(all_commitments, message);
+
+    // Round 2: sign and share our signature share
+    let share = frost_core::round2::sign(&signing_package, &nonces, key_share.key_package())?;
+    outgoing
+        .send(Outgoing {
+            recipient: MessageDestination::AllParties,
+            msg: SigningMsg::Round2(SigningRound2 { share }),
+        })
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?;
+
+    let mut shares = rounds
+        .complete(round2)
+        .await
+        .map_err(|e| FrostError::Network(e.to_string()))?
+        .into_iter_indexed()
+        .map(|(j, _, msg)| (ids[usize::from(j)], msg.share))
+        .collect::<BTreeMap<_, _>>();
+    let _ = shares.insert(ids[usize::from(i)], share);
+
+    // Aggregation verifies each share, and reports the culprit if one is invalid
+    Ok(frost_core::aggregate(
+        &signing_package,
+        &shares,
+        key_share.public_key_package(),
+    )?)
+}
@@ -0,0 +1,53 @@
+use super::*;
+use frost_core::keys::{generate_with_dealer, IdentifierList};
+use gadget_std::rand::rngs::OsRng;
+
+fn dealer_shares<C: FrostCiphersuite>() -> Vec<KeyShare<C>> {
+    let (shares, public_key_package) =
+        generate_with_dealer::<C, _>(3, 2, IdentifierList::Default, OsRng).unwrap();
+    shares
+        .into_values()
+        .map(|share| {
+            let key_package = KeyPackage::try_from(share).unwrap();
+            KeyShare::new(key_package, public_key_package.clone()).unwrap()
+        })
+        .collect()
+}
+
+fn key_share_round_trip<C: FrostCiphersuite>() {
+    for share in dealer_shares::<C>() {
+        let decoded = KeyShare::<C>::from_bytes(&share.to_bytes()).unwrap();
+        assert!(decoded == share);
+        assert_eq!(decoded.verifying_key(), share.verifying_key());
+    }
+}
+
+#[test]
+fn test_key_share_encoding_ed25519() {
+    key_share_round_trip::<Ed25519Sha512>();
+}
+
+#[test]
+fn test_key_share_encoding_secp256k1() {
+    key_share_round_trip::<Secp256K1Sha256>();
+}
+
+#[test]
+fn test_key_share_rejects_mismatched_packages() {
+    let first = dealer_shares::<Ed25519Sha512>().remove(0);
+    let second = dealer_shares::<Ed25519Sha512>().remove(0);
+    assert!(KeyShare::new(
+        first.key_package().clone(),
+        second.public_key_package().clone()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_identifiers_are_offset_by_one() {
+    assert_eq!(
+        identifier::<Ed25519Sha512>(0).unwrap(),
+        Identifier::try_from(1).unwrap()
+    );
+    assert!(identifier::<Ed25519Sha512>(u16::MAX).is_err());
+}
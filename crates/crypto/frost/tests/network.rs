@@ -0,0 +1,149 @@
+//! Runs key generation and signing over `NetworkDeliveryWrapper` on a simulated network.
+
+use futures::future::{join_all, try_join_all};
+use futures::StreamExt;
+use gadget_crypto_frost::error::FrostError;
+use gadget_crypto_frost::frost_core::keys::dkg;
+use gadget_crypto_frost::{
+    identifier, run_keygen, run_signing, Ed25519Sha512, FrostCiphersuite, KeyShare, KeygenMsg,
+    Secp256K1Sha256,
+};
+use gadget_networking::networking::NetworkMultiplexer;
+use gadget_networking::round_based_compat::NetworkDeliveryWrapper;
+use gadget_networking::simulated::SimulatedNetwork;
+use gadget_std::rand::rngs::OsRng;
+use round_based::{Delivery, MpcParty};
+use std::sync::Arc;
+
+fn deliveries<M>(parties: u16, task_hash: [u8; 32]) -> Vec<NetworkDeliveryWrapper<M>>
+where
+    M: Clone + Send + Unpin + 'static,
+    M: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let (network, parties) = SimulatedNetwork::builder(usize::from(parties)).build();
+    let keys = network.public_keys();
+    parties
+        .into_iter()
+        .zip(0..)
+        .map(|(party, i)| {
+            let mux = Arc::new(NetworkMultiplexer::new(party));
+            NetworkDeliveryWrapper::builder(mux, i, task_hash)
+                .parties(keys.iter().copied().zip(0..).map(|(key, j)| (j, key)))
+                .build()
+        })
+        .collect()
+}
+
+async fn keygen<C: FrostCiphersuite>(n: u16, t: u16) -> Vec<KeyShare<C>> {
+    try_join_all(
+        deliveries(n, [1; 32])
+            .into_iter()
+            .zip(0..)
+            .map(|(delivery, i)| async move {
+                run_keygen::<C, _, _>(MpcParty::connected(delivery), i, n, t, &mut OsRng).await
+            }),
+    )
+    .await
+    .unwrap()
+}
+
+async fn keygen_and_sign<C: FrostCiphersuite>() {
+    let shares = keygen::<C>(3, 2).await;
+    assert!(shares
+        .iter()
+        .all(|share| share.verifying_key() == shares[0].verifying_key()));
+
+    // Any two of the three parties can sign
+    let signers = [0u16, 2];
+    let message = b"threshold message";
+    let signatures = try_join_all(deliveries(2, [2; 32]).into_iter().zip(0..).map(
+        |(delivery, i)| {
+            let share = &shares[usize::from(signers[usize::from(i)])];
+            async move {
+                run_signing::<C, _, _>(
+                    MpcParty::connected(delivery),
+                    i,
+                    &signers,
+                    share,
+                    message,
+                    &mut OsRng,
+                )
+                .await
+            }
+        },
+    ))
+    .await
+    .unwrap();
+
+    for signature in &signatures {
+        assert!(shares[1].verify(message, signature));
+        assert!(!shares[1].verify(b"another message", signature));
+    }
+}
+
+#[tokio::test]
+async fn frost_ed25519_over_network() {
+    keygen_and_sign::<Ed25519Sha512>().await;
+}
+
+#[tokio::test]
+async fn frost_secp256k1_over_network() {
+    keygen_and_sign::<Secp256K1Sha256>().await;
+}
+
+#[tokio::test]
+async fn signing_rejects_mismatched_share() {
+    let shares = keygen::<Ed25519Sha512>(3, 2).await;
+    let delivery = deliveries(2, [3; 32]).remove(0);
+
+    // Party 0 of the session claims to be key generation party 1, but holds party 0's share
+    let result = run_signing::<Ed25519Sha512, _, _>(
+        MpcParty::connected(delivery),
+        0,
+        &[1, 2],
+        &shares[0],
+        b"message",
+        &mut OsRng,
+    )
+    .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn keygen_aborts_on_equivocation() {
+    let (n, t) = (3, 2);
+    // Party 0 shows party 1 a different commitment from the one everyone else sees
+    let (_, forged) = dkg::part1(identifier::<Ed25519Sha512>(0).unwrap(), n, t, OsRng).unwrap();
+
+    let results = join_all(deliveries(n, [4; 32]).into_iter().zip(0..).map(
+        |(delivery, i): (NetworkDeliveryWrapper<KeygenMsg<Ed25519Sha512>>, u16)| {
+            let forged = forged.clone();
+            let (incoming, outgoing) = delivery.split();
+            let incoming = incoming.map(move |incoming| {
+                incoming.map(|mut incoming| {
+                    if let KeygenMsg::Round1(round1) = &mut incoming.msg {
+                        if i == 1 && incoming.sender == 0 {
+                            round1.package = forged.clone();
+                        }
+                    }
+                    incoming
+                })
+            });
+            async move {
+                run_keygen::<Ed25519Sha512, _, _>(
+                    MpcParty::connected((incoming, outgoing)),
+                    i,
+                    n,
+                    t,
+                    &mut OsRng,
+                )
+                .await
+            }
+        },
+    ))
+    .await;
+
+    for result in results {
+        assert!(matches!(result, Err(FrostError::InconsistentBroadcast(_))));
+    }
+}
@@ -1,6 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod error;
+pub mod schnorr;
 
 #[cfg(test)]
 mod tests;
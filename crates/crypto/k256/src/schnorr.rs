@@ -0,0 +1,228 @@
+//! BIP-340 Schnorr signatures over secp256k1, with x-only public keys.
+
+use crate::error::{K256Error, Result};
+use gadget_crypto_core::{KeyEncoding, KeyType, KeyTypeId};
+use gadget_std::string::{String, ToString};
+use gadget_std::vec::Vec;
+use gadget_std::UniformRand;
+use k256::schnorr::{Signature, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+/// Tag used to hash messages before they are signed by [`K256Schnorr::sign_with_secret`].
+pub const MESSAGE_TAG: &[u8] = b"gadget/k256-schnorr/message";
+
+/// The BIP-340 tagged hash, `SHA256(SHA256(tag) || SHA256(tag) || msg)`
+#[must_use]
+pub fn tagged_hash(tag: &[u8], msg: &[u8]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(msg)
+        .finalize()
+        .into()
+}
+
+/// BIP-340 Schnorr key type
+///
+/// [`KeyType::sign_with_secret`] signs the [`tagged_hash`] of the message under [`MESSAGE_TAG`].
+/// [`KeyType::sign_with_secret_pre_hashed`] signs a 32-byte message as-is, as needed for Bitcoin
+/// sighashes.
+pub struct K256Schnorr;
+
+macro_rules! impl_schnorr_bytes {
+    ($wrapper:ident, $inner:ty) => {
+        #[derive(Clone)]
+        pub struct $wrapper(pub $inner);
+
+        impl PartialEq for $wrapper {
+            fn eq(&self, other: &Self) -> bool {
+                self.to_bytes() == other.to_bytes()
+            }
+        }
+
+        impl Eq for $wrapper {}
+
+        impl PartialOrd for $wrapper {
+            fn partial_cmp(&self, other: &Self) -> Option<gadget_std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $wrapper {
+            fn cmp(&self, other: &Self) -> gadget_std::cmp::Ordering {
+                self.to_bytes().cmp(&other.to_bytes())
+            }
+        }
+
+        impl KeyEncoding for $wrapper {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.to_bytes().to_vec()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, serde::de::value::Error> {
+                <$wrapper>::from_bytes_impl(bytes)
+                    .map_err(|e| serde::de::Error::custom(e.to_string()))
+            }
+        }
+
+        impl serde::Serialize for $wrapper {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let bytes = self.to_bytes();
+                Vec::serialize(&bytes, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $wrapper {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let bytes = Vec::<u8>::deserialize(deserializer)?;
+                <$wrapper>::from_bytes_impl(&bytes)
+                    .map_err(|e| serde::de::Error::custom(e.to_string()))
+            }
+        }
+    };
+}
+
+impl_schnorr_bytes!(K256SchnorrSigningKey, SigningKey);
+
+impl K256SchnorrSigningKey {
+    fn from_bytes_impl(bytes: &[u8]) -> Result<Self> {
+        let key =
+            SigningKey::from_bytes(bytes).map_err(|e| K256Error::InvalidSigner(e.to_string()))?;
+        Ok(K256SchnorrSigningKey(key))
+    }
+
+    /// Sign a message as specified by BIP-340, using `aux_rand` as the auxiliary randomness.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if signing fails.
+    pub fn sign_raw(&self, msg: &[u8], aux_rand: &[u8; 32]) -> Result<K256SchnorrSignature> {
+        let signature = self
+            .0
+            .sign_raw(msg, aux_rand)
+            .map_err(|e| K256Error::SignatureFailed(e.to_string()))?;
+        Ok(K256SchnorrSignature(signature))
+    }
+
+    pub fn verifying_key(&self) -> K256SchnorrPublic {
+        K256SchnorrPublic(*self.0.verifying_key())
+    }
+
+    /// Alias for `verifying_key` for consistency
+    pub fn public(&self) -> K256SchnorrPublic {
+        self.verifying_key()
+    }
+}
+
+impl gadget_std::fmt::Debug for K256SchnorrSigningKey {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        f.debug_tuple("K256SchnorrSigningKey")
+            .field(&self.verifying_key())
+            .finish()
+    }
+}
+
+impl_schnorr_bytes!(K256SchnorrPublic, VerifyingKey);
+
+impl K256SchnorrPublic {
+    fn from_bytes_impl(bytes: &[u8]) -> Result<Self> {
+        let key = VerifyingKey::from_bytes(bytes)
+            .map_err(|e| K256Error::InvalidVerifyingKey(e.to_string()))?;
+        Ok(K256SchnorrPublic(key))
+    }
+
+    /// Verify a signature over a message as specified by BIP-340, without any hashing.
+    #[must_use]
+    pub fn verify_raw(&self, msg: &[u8], signature: &K256SchnorrSignature) -> bool {
+        self.0.verify_raw(msg, &signature.0).is_ok()
+    }
+}
+
+impl gadget_std::fmt::Debug for K256SchnorrPublic {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        write!(f, "K256SchnorrPublic({})", hex::encode(self.to_bytes()))
+    }
+}
+
+impl_schnorr_bytes!(K256SchnorrSignature, Signature);
+
+impl K256SchnorrSignature {
+    fn from_bytes_impl(bytes: &[u8]) -> Result<Self> {
+        let signature =
+            Signature::try_from(bytes).map_err(|e| K256Error::InvalidSignature(e.to_string()))?;
+        Ok(K256SchnorrSignature(signature))
+    }
+}
+
+impl gadget_std::fmt::Debug for K256SchnorrSignature {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        write!(f, "K256SchnorrSignature({})", hex::encode(self.to_bytes()))
+    }
+}
+
+impl KeyType for K256Schnorr {
+    type Secret = K256SchnorrSigningKey;
+    type Public = K256SchnorrPublic;
+    type Signature = K256SchnorrSignature;
+    type Error = K256Error;
+
+    fn key_type_id() -> KeyTypeId {
+        KeyTypeId::K256Schnorr
+    }
+
+    fn generate_with_seed(seed: Option<&[u8]>) -> Result<Self::Secret> {
+        let bytes = if let Some(seed) = seed {
+            // Pad seed if less than 32 bytes, error if larger
+            if seed.len() > 32 {
+                return Err(K256Error::InvalidSeed(
+                    "Seed must not exceed 32 bytes".into(),
+                ));
+            }
+            let mut padded_seed = [0u8; 32];
+            padded_seed[..seed.len()].copy_from_slice(seed);
+            padded_seed
+        } else {
+            let mut rng = Self::get_rng();
+            <[u8; 32]>::rand(&mut rng)
+        };
+
+        let signing_key =
+            SigningKey::from_bytes(&bytes).map_err(|e| K256Error::InvalidSeed(e.to_string()))?;
+        Ok(K256SchnorrSigningKey(signing_key))
+    }
+
+    fn generate_with_string(secret: String) -> Result<Self::Secret> {
+        let hex_encoded = hex::decode(secret)?;
+        let signing_key = SigningKey::from_bytes(&hex_encoded)
+            .map_err(|e| K256Error::InvalidSeed(e.to_string()))?;
+        Ok(K256SchnorrSigningKey(signing_key))
+    }
+
+    fn public_from_secret(secret: &Self::Secret) -> Self::Public {
+        secret.verifying_key()
+    }
+
+    fn sign_with_secret(secret: &mut Self::Secret, msg: &[u8]) -> Result<Self::Signature> {
+        Self::sign_with_secret_pre_hashed(secret, &tagged_hash(MESSAGE_TAG, msg))
+    }
+
+    fn sign_with_secret_pre_hashed(
+        secret: &mut Self::Secret,
+        msg: &[u8; 32],
+    ) -> Result<Self::Signature> {
+        let mut rng = Self::get_rng();
+        let aux_rand = <[u8; 32]>::rand(&mut rng);
+        secret.sign_raw(msg, &aux_rand)
+    }
+
+    fn verify(public: &Self::Public, msg: &[u8], signature: &Self::Signature) -> bool {
+        public.verify_raw(&tagged_hash(MESSAGE_TAG, msg), signature)
+    }
+}
@@ -206,3 +206,137 @@ fn test_low_s_normalization() {
     let high_bit = s_bytes[0] & 0x80;
     assert_eq!(high_bit, 0, "S value should be normalized to low S");
 }
+
+mod k256_schnorr_tests {
+    use crate::schnorr::*;
+    gadget_crypto_core::impl_crypto_tests!(
+        K256Schnorr,
+        K256SchnorrSigningKey,
+        K256SchnorrSignature
+    );
+}
+
+/// Test vectors from BIP-340 (`test-vectors.csv`)
+mod bip340_vectors {
+    use crate::schnorr::*;
+    use gadget_crypto_core::{KeyEncoding, KeyType};
+
+    struct SigningVector {
+        secret: &'static str,
+        public: &'static str,
+        aux_rand: &'static str,
+        message: &'static str,
+        signature: &'static str,
+    }
+
+    const SIGNING_VECTORS: [SigningVector; 4] = [
+        SigningVector {
+            secret: "0000000000000000000000000000000000000000000000000000000000000003",
+            public: "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000000",
+            message: "0000000000000000000000000000000000000000000000000000000000000000",
+            signature: "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+        },
+        SigningVector {
+            secret: "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+            public: "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000001",
+            message: "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            signature: "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+        },
+        SigningVector {
+            secret: "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+            public: "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+            aux_rand: "c87aa53824b4d7ae2eb035a2b5bbbccc080e76cdc6d1692c4b0b62d798e6d906",
+            message: "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+            signature: "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+        },
+        SigningVector {
+            secret: "0b432b2677937381aef05bb02a66ecd012773062cf3fa2549e44f58ed2401710",
+            public: "25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517",
+            aux_rand: "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            message: "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            signature: "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3",
+        },
+    ];
+
+    fn decode<const N: usize>(hex_str: &str) -> [u8; N] {
+        hex::decode(hex_str).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_signing_vectors() {
+        for vector in SIGNING_VECTORS {
+            let secret = K256Schnorr::generate_with_string(vector.secret.to_string()).unwrap();
+            let public = K256Schnorr::public_from_secret(&secret);
+            assert_eq!(hex::encode(public.to_bytes()), vector.public);
+
+            let message = decode::<32>(vector.message);
+            let signature = secret.sign_raw(&message, &decode(vector.aux_rand)).unwrap();
+            assert_eq!(hex::encode(signature.to_bytes()), vector.signature);
+            assert!(public.verify_raw(&message, &signature));
+        }
+    }
+
+    #[test]
+    fn test_verification_vectors() {
+        // (public key, message, signature, valid)
+        let vectors = [
+            (
+                "d69c3509bb99e412e68b0fe8544e72837dfa30746d8be2aa65975f29d22dc7b9",
+                "4df3c3f68fcc83b27e9d42c90431a72499f17875c81a599b566c9889b9696703",
+                "00000000000000000000003b78ce563f89a0ed9414f5aa28ad0d96d6795f9c6376afb1548af603b3eb45c9f8207dee1060cb71c04e80f593060b07d28308d7f4",
+                true,
+            ),
+            // has_even_y(R) is false
+            (
+                "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+                "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+                "fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a14602975563cc27944640ac607cd107ae10923d9ef7a73c643e166be5ebeafa34b1ac553e2",
+                false,
+            ),
+        ];
+
+        for (public, message, signature, valid) in vectors {
+            let public = K256SchnorrPublic::from_bytes(&hex::decode(public).unwrap()).unwrap();
+            let signature =
+                K256SchnorrSignature::from_bytes(&hex::decode(signature).unwrap()).unwrap();
+            assert_eq!(
+                public.verify_raw(&hex::decode(message).unwrap(), &signature),
+                valid
+            );
+        }
+    }
+
+    #[test]
+    fn test_public_key_not_on_curve_is_rejected() {
+        let public = "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34";
+        assert!(K256SchnorrPublic::from_bytes(&hex::decode(public).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_x_only_public_key_encoding() {
+        let secret = K256Schnorr::generate_with_seed(None).unwrap();
+        let public = K256Schnorr::public_from_secret(&secret);
+
+        let bytes = public.to_bytes();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(K256SchnorrPublic::from_bytes(&bytes).unwrap(), public);
+    }
+
+    #[test]
+    fn test_messages_are_tagged_hashed() {
+        let mut secret = K256Schnorr::generate_with_seed(None).unwrap();
+        let public = K256Schnorr::public_from_secret(&secret);
+        let msg = b"bridge withdrawal";
+
+        let signature = K256Schnorr::sign_with_secret(&mut secret, msg).unwrap();
+        assert!(K256Schnorr::verify(&public, msg, &signature));
+        assert!(public.verify_raw(&tagged_hash(MESSAGE_TAG, msg), &signature));
+        assert!(!public.verify_raw(msg, &signature));
+
+        let sighash = [0x42; 32];
+        let signature = K256Schnorr::sign_with_secret_pre_hashed(&mut secret, &sighash).unwrap();
+        assert!(public.verify_raw(&sighash, &signature));
+    }
+}
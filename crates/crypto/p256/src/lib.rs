@@ -0,0 +1,225 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod error;
+
+#[cfg(test)]
+mod tests;
+
+use crate::error::{P256Error, Result};
+use gadget_crypto_core::KeyEncoding;
+use gadget_crypto_core::{KeyType, KeyTypeId};
+use gadget_std::string::{String, ToString};
+use gadget_std::vec::Vec;
+use gadget_std::UniformRand;
+use p256::ecdsa::signature::SignerMut;
+use p256::ecdsa::VerifyingKey;
+use serde::{Deserialize, Serialize};
+
+/// NIST P-256 (secp256r1) ECDSA key type, as used by WebAuthn/passkeys and TEE attestation
+pub struct P256Ecdsa;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct P256VerifyingKey(pub VerifyingKey);
+
+impl From<P256VerifyingKey> for VerifyingKey {
+    fn from(key: P256VerifyingKey) -> Self {
+        key.0
+    }
+}
+
+impl KeyEncoding for P256VerifyingKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_sec1_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, serde::de::value::Error> {
+        let vk = VerifyingKey::from_sec1_bytes(bytes)
+            .map_err(|e| serde::de::Error::custom(e.to_string()))?;
+        Ok(P256VerifyingKey(vk))
+    }
+}
+
+impl PartialOrd for P256VerifyingKey {
+    fn partial_cmp(&self, other: &Self) -> Option<gadget_std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for P256VerifyingKey {
+    fn cmp(&self, other: &Self) -> gadget_std::cmp::Ordering {
+        self.0.to_sec1_bytes().cmp(&other.0.to_sec1_bytes())
+    }
+}
+
+macro_rules! impl_serde_bytes {
+    ($wrapper:ident, $inner:path) => {
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        pub struct $wrapper(pub $inner);
+
+        impl PartialOrd for $wrapper {
+            fn partial_cmp(&self, other: &Self) -> Option<gadget_std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $wrapper {
+            fn cmp(&self, other: &Self) -> gadget_std::cmp::Ordering {
+                self.to_bytes().cmp(&other.to_bytes())
+            }
+        }
+
+        impl KeyEncoding for $wrapper {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.to_bytes_impl().to_vec()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, serde::de::value::Error> {
+                <$wrapper>::from_bytes_impl(bytes)
+                    .map_err(|e| serde::de::Error::custom(e.to_string()))
+            }
+        }
+
+        impl serde::Serialize for $wrapper {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                let bytes = self.to_bytes();
+                Vec::serialize(&bytes, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $wrapper {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let bytes = Vec::<u8>::deserialize(deserializer)?;
+                let inner = <$inner>::from_slice(&bytes)
+                    .map_err(|e| serde::de::Error::custom(e.to_string()))?;
+                Ok($wrapper(inner))
+            }
+        }
+    };
+}
+
+impl_serde_bytes!(P256SigningKey, p256::ecdsa::SigningKey);
+
+impl P256SigningKey {
+    fn to_bytes_impl(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    fn from_bytes_impl(bytes: &[u8]) -> Result<Self> {
+        let key = p256::ecdsa::SigningKey::try_from(bytes)
+            .map_err(|e| P256Error::InvalidSigner(e.to_string()))?;
+        Ok(P256SigningKey(key))
+    }
+}
+
+impl_serde_bytes!(P256Signature, p256::ecdsa::Signature);
+
+impl P256Signature {
+    fn to_bytes_impl(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    fn from_bytes_impl(bytes: &[u8]) -> Result<Self> {
+        let sig = p256::ecdsa::Signature::try_from(bytes)
+            .map_err(|e| P256Error::InvalidSignature(e.to_string()))?;
+        Ok(P256Signature(sig))
+    }
+
+    /// Parse an ASN.1 DER encoded signature, as produced by WebAuthn authenticators
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid DER encoded signature.
+    pub fn from_der(bytes: &[u8]) -> Result<Self> {
+        let sig = p256::ecdsa::Signature::from_der(bytes)
+            .map_err(|e| P256Error::InvalidSignature(e.to_string()))?;
+        Ok(P256Signature(sig))
+    }
+
+    /// Encode the signature as ASN.1 DER
+    pub fn to_der(&self) -> Vec<u8> {
+        self.0.to_der().as_bytes().to_vec()
+    }
+}
+
+impl KeyType for P256Ecdsa {
+    type Secret = P256SigningKey;
+    type Public = P256VerifyingKey;
+    type Signature = P256Signature;
+    type Error = P256Error;
+
+    fn key_type_id() -> KeyTypeId {
+        KeyTypeId::P256
+    }
+
+    fn generate_with_seed(seed: Option<&[u8]>) -> Result<Self::Secret> {
+        let signing_key = if let Some(seed) = seed {
+            // Pad seed if less than 32 bytes, error if larger
+            if seed.len() > 32 {
+                return Err(P256Error::InvalidSeed(
+                    "Seed must not exceed 32 bytes".into(),
+                ));
+            }
+            let mut padded_seed = [0u8; 32];
+            padded_seed[..seed.len()].copy_from_slice(seed);
+            p256::ecdsa::SigningKey::from_bytes(&padded_seed.into())
+                .map_err(|e| P256Error::InvalidSeed(e.to_string()))
+        } else {
+            let mut rng = Self::get_rng();
+            let rand_bytes: [u8; 32] = <[u8; 32]>::rand(&mut rng);
+            p256::ecdsa::SigningKey::from_slice(&rand_bytes)
+                .map_err(|e| P256Error::InvalidSeed(e.to_string()))
+        };
+
+        signing_key.map(P256SigningKey)
+    }
+
+    fn generate_with_string(secret: String) -> Result<Self::Secret> {
+        let hex_encoded = hex::decode(secret)?;
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&hex_encoded)
+            .map_err(|e| P256Error::InvalidSeed(e.to_string()))?;
+        Ok(P256SigningKey(signing_key))
+    }
+
+    fn public_from_secret(secret: &Self::Secret) -> Self::Public {
+        P256VerifyingKey(*secret.0.verifying_key())
+    }
+
+    fn sign_with_secret(secret: &mut Self::Secret, msg: &[u8]) -> Result<Self::Signature> {
+        let sig = secret.0.sign(msg);
+        Ok(P256Signature(sig))
+    }
+
+    fn sign_with_secret_pre_hashed(
+        secret: &mut Self::Secret,
+        msg: &[u8; 32],
+    ) -> Result<Self::Signature> {
+        use p256::ecdsa::signature::hazmat::PrehashSigner;
+        let sig = secret
+            .0
+            .sign_prehash(msg)
+            .map_err(|e| P256Error::SignatureFailed(e.to_string()))?;
+        Ok(P256Signature(sig))
+    }
+
+    fn verify(public: &Self::Public, msg: &[u8], signature: &Self::Signature) -> bool {
+        use p256::ecdsa::signature::Verifier;
+        public.0.verify(msg, &signature.0).is_ok()
+    }
+}
+
+impl P256SigningKey {
+    pub fn verifying_key(&self) -> P256VerifyingKey {
+        P256VerifyingKey(*self.0.verifying_key())
+    }
+
+    /// Alias for `verifying_key` for consistency
+    pub fn public(&self) -> P256VerifyingKey {
+        self.verifying_key()
+    }
+}
@@ -0,0 +1,107 @@
+use super::*;
+use gadget_crypto_core::{KeyEncoding, KeyType};
+
+mod p256_crypto_tests {
+    use super::*;
+    gadget_crypto_core::impl_crypto_tests!(P256Ecdsa, P256SigningKey, P256Signature);
+}
+
+/// RFC 6979, Appendix A.2.5: ECDSA, 256 Bits (Prime Field), with SHA-256
+mod rfc6979_vectors {
+    use super::*;
+
+    const SECRET: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const PUBLIC_X: &str = "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6";
+    const PUBLIC_Y: &str = "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+
+    const VECTORS: [(&[u8], &str, &str); 2] = [
+        (
+            b"sample",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+        ),
+        (
+            b"test",
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+            "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+        ),
+    ];
+
+    #[test]
+    fn test_public_key() {
+        let secret = P256Ecdsa::generate_with_string(SECRET.to_string()).unwrap();
+        let public = P256Ecdsa::public_from_secret(&secret);
+
+        let uncompressed = public.0.to_encoded_point(false);
+        assert_eq!(hex::encode(uncompressed.x().unwrap()), PUBLIC_X);
+        assert_eq!(hex::encode(uncompressed.y().unwrap()), PUBLIC_Y);
+    }
+
+    #[test]
+    fn test_deterministic_signatures() {
+        let mut secret = P256Ecdsa::generate_with_string(SECRET.to_string()).unwrap();
+        let public = P256Ecdsa::public_from_secret(&secret);
+
+        for (msg, r, s) in VECTORS {
+            let signature = P256Ecdsa::sign_with_secret(&mut secret, msg).unwrap();
+            assert_eq!(hex::encode(signature.to_bytes()), format!("{r}{s}"));
+            assert!(P256Ecdsa::verify(&public, msg, &signature));
+
+            let decoded =
+                P256Signature::from_bytes(&hex::decode(format!("{r}{s}")).unwrap()).unwrap();
+            assert!(P256Ecdsa::verify(&public, msg, &decoded));
+        }
+    }
+}
+
+#[test]
+fn test_key_generation_deterministic() {
+    let seed = b"deterministic_test_seed";
+    let key1 = P256Ecdsa::generate_with_seed(Some(seed)).unwrap();
+    let key2 = P256Ecdsa::generate_with_seed(Some(seed)).unwrap();
+    assert_eq!(key1, key2, "Same seed should produce identical keys");
+
+    let key3 = P256Ecdsa::generate_with_seed(Some(b"different_seed")).unwrap();
+    assert_ne!(key1, key3, "Different seeds should produce different keys");
+
+    assert!(P256Ecdsa::generate_with_seed(Some(&[1u8; 33])).is_err());
+}
+
+#[test]
+fn test_sec1_public_key_encoding() {
+    let secret = P256Ecdsa::generate_with_seed(None).unwrap();
+    let public = P256Ecdsa::public_from_secret(&secret);
+
+    let decoded = P256VerifyingKey::from_bytes(&public.to_bytes()).unwrap();
+    assert_eq!(public, decoded);
+
+    // Uncompressed points, as found in WebAuthn/COSE keys, are accepted too
+    let uncompressed = public.0.to_encoded_point(false);
+    let decoded = P256VerifyingKey::from_bytes(uncompressed.as_bytes()).unwrap();
+    assert_eq!(public, decoded);
+}
+
+#[test]
+fn test_der_signatures() {
+    let mut secret = P256Ecdsa::generate_with_seed(None).unwrap();
+    let public = P256Ecdsa::public_from_secret(&secret);
+    let msg = b"authenticator data";
+
+    let signature = P256Ecdsa::sign_with_secret(&mut secret, msg).unwrap();
+    let decoded = P256Signature::from_der(&signature.to_der()).unwrap();
+    assert_eq!(signature, decoded);
+    assert!(P256Ecdsa::verify(&public, msg, &decoded));
+    assert!(P256Signature::from_der(&signature.to_bytes()).is_err());
+}
+
+#[test]
+fn test_pre_hashed_signing() {
+    let mut secret = P256Ecdsa::generate_with_seed(None).unwrap();
+    let public = P256Ecdsa::public_from_secret(&secret);
+
+    let digest = [7u8; 32];
+    let signature = P256Ecdsa::sign_with_secret_pre_hashed(&mut secret, &digest).unwrap();
+
+    use p256::ecdsa::signature::hazmat::PrehashVerifier;
+    assert!(public.0.verify_prehash(&digest, &signature.0).is_ok());
+}
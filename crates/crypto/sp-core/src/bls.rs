@@ -257,14 +257,108 @@ macro_rules! impl_sp_core_bls_key_type {
     };
 }
 
+/// Implements signature aggregation for BLS key types.
+///
+/// `sp_core` encodes public keys as `w3f_bls` double public keys and signatures as double
+/// signatures, both of which aggregate component-wise.
+macro_rules! impl_sp_core_bls_aggregate {
+    ($key_type:ident, $module:ident, $engine:ty) => {
+        paste::paste! {
+            impl [<Sp $key_type Public>] {
+                fn to_w3f(&self) -> $crate::error::Result<w3f_bls::DoublePublicKey<$engine>> {
+                    <w3f_bls::DoublePublicKey<$engine> as w3f_bls::SerializableToBytes>::from_bytes(&self.0.to_raw())
+                        .map_err(|e| $crate::error::SpCoreError::AggregationFailed(format!("Invalid public key: {e:?}")))
+                }
+            }
+
+            impl [<Sp $key_type Signature>] {
+                fn to_w3f(&self) -> $crate::error::Result<w3f_bls::DoubleSignature<$engine>> {
+                    <w3f_bls::DoubleSignature<$engine> as w3f_bls::SerializableToBytes>::from_bytes(self.0.as_ref())
+                        .map_err(|e| $crate::error::SpCoreError::AggregationFailed(format!("Invalid signature: {e:?}")))
+                }
+            }
+
+            impl gadget_crypto_core::AggregatableSignature for [<Sp $key_type>] {
+                fn aggregate_signatures(
+                    signatures: &[Self::Signature],
+                ) -> $crate::error::Result<Self::Signature> {
+                    let (first, rest) = signatures.split_first().ok_or_else(|| {
+                        $crate::error::SpCoreError::AggregationFailed("No signatures to aggregate".to_string())
+                    })?;
+                    let mut aggregate = first.to_w3f()?;
+                    for signature in rest {
+                        let signature = signature.to_w3f()?;
+                        aggregate = w3f_bls::DoubleSignature(aggregate.0 + signature.0, aggregate.1 + signature.1);
+                    }
+
+                    let bytes = w3f_bls::SerializableToBytes::to_bytes(&aggregate);
+                    let raw = bytes.as_slice().try_into().map_err(|_| {
+                        $crate::error::SpCoreError::AggregationFailed("Invalid signature length".to_string())
+                    })?;
+                    Ok([<Sp $key_type Signature>](sp_core::$module::Signature::from_raw(raw)))
+                }
+
+                fn aggregate_public_keys(
+                    public_keys: &[Self::Public],
+                ) -> $crate::error::Result<Self::Public> {
+                    let (first, rest) = public_keys.split_first().ok_or_else(|| {
+                        $crate::error::SpCoreError::AggregationFailed("No public keys to aggregate".to_string())
+                    })?;
+                    let mut aggregate = first.to_w3f()?;
+                    for public in rest {
+                        let public = public.to_w3f()?;
+                        aggregate = w3f_bls::DoublePublicKey(aggregate.0 + public.0, aggregate.1 + public.1);
+                    }
+
+                    let bytes = w3f_bls::SerializableToBytes::to_bytes(&aggregate);
+                    let raw = bytes.as_slice().try_into().map_err(|_| {
+                        $crate::error::SpCoreError::AggregationFailed("Invalid public key length".to_string())
+                    })?;
+                    Ok([<Sp $key_type Public>](sp_core::$module::Public::from_raw(raw)))
+                }
+
+                fn verify_multi_message(
+                    items: &[(&Self::Public, &[u8])],
+                    signature: &Self::Signature,
+                ) -> bool {
+                    use w3f_bls::Signed;
+
+                    if items.is_empty() {
+                        return false;
+                    }
+                    let Ok(signature) = signature.to_w3f() else {
+                        return false;
+                    };
+
+                    // `sp_core` signs with an empty context
+                    let mut aggregator =
+                        w3f_bls::multi_pop_aggregator::MultiMessageSignatureAggregatorAssumingPoP::<$engine>::new();
+                    aggregator.add_signature(&w3f_bls::Signature(signature.0));
+                    for (public, msg) in items {
+                        let Ok(public) = public.to_w3f() else {
+                            return false;
+                        };
+                        aggregator.add_message_n_publickey(
+                            &w3f_bls::Message::new(b"", msg),
+                            &w3f_bls::PublicKey(public.1),
+                        );
+                    }
+                    aggregator.verify()
+                }
+            }
+        }
+    };
+}
+
 /// Implements both pair/public and signature traits for a given sp_core crypto type
 macro_rules! impl_sp_core_bls_crypto {
-    ($key_type:ident, $module:ident) => {
+    ($key_type:ident, $module:ident, $engine:ty) => {
         impl_sp_core_bls_pair_public!($key_type, sp_core::$module::Pair, sp_core::$module::Public);
         impl_sp_core_bls_signature!($key_type, sp_core::$module::Signature);
         impl_sp_core_bls_key_type!($key_type, sp_core::$module::Pair);
+        impl_sp_core_bls_aggregate!($key_type, $module, $engine);
     };
 }
 
-impl_sp_core_bls_crypto!(Bls377, bls377);
-impl_sp_core_bls_crypto!(Bls381, bls381);
+impl_sp_core_bls_crypto!(Bls377, bls377, w3f_bls::TinyBLS377);
+impl_sp_core_bls_crypto!(Bls381, bls381, w3f_bls::TinyBLS381);
@@ -180,3 +180,13 @@ mod bls377_tests {
         );
     }
 }
+
+mod bls377_aggregation_tests {
+    use super::*;
+    gadget_crypto_core::impl_aggregation_tests!(SpBls377);
+}
+
+mod bls381_aggregation_tests {
+    use super::*;
+    gadget_crypto_core::impl_aggregation_tests!(SpBls381);
+}
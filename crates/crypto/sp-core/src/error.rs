@@ -8,6 +8,8 @@ pub enum SpCoreError {
     InvalidSeed(String),
     #[error("Invalid secret string: {0}")]
     SecretStringError(SecretStringErrorWrapper),
+    #[error("Aggregation failed: {0}")]
+    AggregationFailed(String),
 }
 
 #[derive(Debug, Clone)]
@@ -135,4 +135,20 @@ impl KeyType for SchnorrkelSr25519 {
         let ctx = schnorrkel::signing_context(b"tangle").bytes(msg);
         public.0.verify(ctx, &signature.0).is_ok()
     }
+
+    #[cfg(feature = "std")]
+    fn verify_batch(items: &[(&Self::Public, &[u8], &Self::Signature)]) -> bool {
+        let transcripts = items
+            .iter()
+            .map(|(_, msg, _)| schnorrkel::signing_context(b"tangle").bytes(msg));
+        let signatures = items
+            .iter()
+            .map(|(_, _, signature)| signature.0)
+            .collect::<Vec<_>>();
+        let public_keys = items
+            .iter()
+            .map(|(public, _, _)| public.0)
+            .collect::<Vec<_>>();
+        schnorrkel::verify_batch(transcripts, &signatures, &public_keys, false).is_ok()
+    }
 }
@@ -7,6 +7,9 @@ pub use gadget_crypto_core::*;
 #[cfg(feature = "k256")]
 pub use gadget_crypto_k256 as k256;
 
+#[cfg(feature = "p256")]
+pub use gadget_crypto_p256 as p256;
+
 #[cfg(feature = "sr25519-schnorrkel")]
 pub use gadget_crypto_sr25519 as sr25519;
 
@@ -22,6 +25,9 @@ pub use gadget_crypto_bn254 as bn254;
 #[cfg(feature = "sp-core")]
 pub use gadget_crypto_sp_core as sp_core;
 
+#[cfg(feature = "frost")]
+pub use gadget_crypto_frost as frost;
+
 #[cfg(feature = "tangle-pair-signer")]
 pub use gadget_crypto_tangle_pair_signer as tangle_pair_signer;
 
@@ -33,6 +39,9 @@ pub enum CryptoCoreError {
     #[cfg(feature = "k256")]
     #[error(transparent)]
     K256(#[from] gadget_crypto_k256::error::K256Error),
+    #[cfg(feature = "p256")]
+    #[error(transparent)]
+    P256(#[from] gadget_crypto_p256::error::P256Error),
     #[cfg(feature = "sr25519-schnorrkel")]
     #[error(transparent)]
     Sr25519(#[from] gadget_crypto_sr25519::error::Sr25519Error),
@@ -48,6 +57,9 @@ pub enum CryptoCoreError {
     #[cfg(feature = "sp-core")]
     #[error(transparent)]
     SpCore(#[from] gadget_crypto_sp_core::error::SpCoreError),
+    #[cfg(feature = "frost")]
+    #[error(transparent)]
+    Frost(#[from] gadget_crypto_frost::error::FrostError),
 }
 
 pub trait IntoCryptoError {
@@ -61,6 +73,13 @@ impl IntoCryptoError for gadget_crypto_k256::error::K256Error {
     }
 }
 
+#[cfg(feature = "p256")]
+impl IntoCryptoError for gadget_crypto_p256::error::P256Error {
+    fn into_crypto_error(self) -> CryptoCoreError {
+        CryptoCoreError::P256(self)
+    }
+}
+
 #[cfg(feature = "sr25519-schnorrkel")]
 impl IntoCryptoError for gadget_crypto_sr25519::error::Sr25519Error {
     fn into_crypto_error(self) -> CryptoCoreError {
@@ -95,3 +114,10 @@ impl IntoCryptoError for gadget_crypto_sp_core::error::SpCoreError {
         CryptoCoreError::SpCore(self)
     }
 }
+
+#[cfg(feature = "frost")]
+impl IntoCryptoError for gadget_crypto_frost::error::FrostError {
+    fn into_crypto_error(self) -> CryptoCoreError {
+        CryptoCoreError::Frost(self)
+    }
+}
@@ -0,0 +1,403 @@
+//! Persistent queue of job calls that failed every attempt, so they can be inspected and replayed.
+
+use crate::error::Error;
+use crate::executor::JobRunner;
+use crate::storage::store_json;
+use async_trait::async_trait;
+use gadget_std::boxed::Box;
+use gadget_std::collections::BTreeMap;
+use gadget_std::fs;
+use gadget_std::path::{Path, PathBuf};
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::{Arc, Mutex};
+use gadget_std::time::{SystemTime, UNIX_EPOCH};
+use gadget_std::vec::Vec;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// The directory (relative to the gadget data directory) holding the dead-letter queues.
+pub const DEAD_LETTER_DIR_NAME: &str = "dead_letters";
+
+/// A job call that failed every attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// Identifies the dead letter within its queue
+    pub id: u64,
+    /// The pre-processed event, as encoded by the job's [`DeadLetterCodec`]
+    pub event: serde_json::Value,
+    /// The error returned by the last attempt
+    pub error: String,
+    /// The number of attempts made, including any replays
+    pub attempts: u32,
+    /// When the last attempt failed, in seconds since the UNIX epoch
+    pub failed_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct DeadLetterState {
+    letters: BTreeMap<u64, DeadLetter>,
+    next_id: u64,
+}
+
+/// Job calls that exhausted their retries, persisted to the gadget data directory.
+///
+/// Every change is written to disk immediately, so no failed call is lost if the blueprint restarts.
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    path: Option<PathBuf>,
+    state: Mutex<DeadLetterState>,
+}
+
+impl DeadLetterQueue {
+    /// Open the queue named `name`, loading any dead letters already stored under `data_dir`.
+    ///
+    /// Without a `data_dir`, dead letters are only kept in memory.
+    #[must_use]
+    pub fn new(data_dir: Option<&Path>, name: &str) -> Self {
+        let path = data_dir.map(|dir| dir.join(DEAD_LETTER_DIR_NAME).join(format!("{name}.json")));
+        let letters = path.as_deref().map(load_letters).unwrap_or_default();
+        let next_id = letters.keys().next_back().map_or(0, |id| id + 1);
+
+        Self {
+            path,
+            state: Mutex::new(DeadLetterState { letters, next_id }),
+        }
+    }
+
+    /// Returns all dead letters, oldest first.
+    pub fn list(&self) -> Vec<DeadLetter> {
+        self.lock().letters.values().cloned().collect()
+    }
+
+    /// Returns the dead letter with the given ID, if any.
+    pub fn get(&self, id: u64) -> Option<DeadLetter> {
+        self.lock().letters.get(&id).cloned()
+    }
+
+    /// The number of dead letters in the queue
+    pub fn len(&self) -> usize {
+        self.lock().letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove the dead letter with the given ID, returning it if it existed.
+    pub fn remove(&self, id: u64) -> Option<DeadLetter> {
+        let mut state = self.lock();
+        let letter = state.letters.remove(&id)?;
+        self.persist(&state);
+        Some(letter)
+    }
+
+    /// Record a failed job call, returning the ID of its dead letter.
+    ///
+    /// If `replayed` is the ID of an existing dead letter, it is updated in place instead.
+    pub(crate) fn push(
+        &self,
+        replayed: Option<u64>,
+        event: serde_json::Value,
+        error: String,
+        attempts: u32,
+    ) -> u64 {
+        let failed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut state = self.lock();
+        let (id, attempts) = match replayed.and_then(|id| state.letters.get(&id)) {
+            Some(previous) => (previous.id, previous.attempts + attempts),
+            None => {
+                let id = state.next_id;
+                state.next_id += 1;
+                (id, attempts)
+            }
+        };
+
+        let _ = state.letters.insert(
+            id,
+            DeadLetter {
+                id,
+                event,
+                error,
+                attempts,
+                failed_at,
+            },
+        );
+        self.persist(&state);
+        id
+    }
+
+    fn lock(&self) -> gadget_std::sync::MutexGuard<'_, DeadLetterState> {
+        self.state
+            .lock()
+            .unwrap_or_else(gadget_std::sync::PoisonError::into_inner)
+    }
+
+    fn persist(&self, state: &DeadLetterState) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let letters = state.letters.values().collect::<Vec<_>>();
+        if let Err(e) = store_json(path, &letters) {
+            gadget_logging::error!("Failed to persist dead letters to {}: {e}", path.display());
+        }
+    }
+}
+
+fn load_letters(path: &Path) -> BTreeMap<u64, DeadLetter> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == gadget_std::io::ErrorKind::NotFound => return BTreeMap::new(),
+        Err(e) => {
+            gadget_logging::error!("Failed to read dead letters at {}: {e}", path.display());
+            return BTreeMap::new();
+        }
+    };
+
+    match serde_json::from_slice::<Vec<DeadLetter>>(&bytes) {
+        Ok(letters) => letters
+            .into_iter()
+            .map(|letter| (letter.id, letter))
+            .collect(),
+        Err(e) => {
+            gadget_logging::error!("Failed to parse dead letters at {}: {e}", path.display());
+            BTreeMap::new()
+        }
+    }
+}
+
+/// How a job's pre-processed events are stored in, and restored from, dead letters
+///
+/// Events that hold runtime state, such as clients or signers, store only the data needed to rebuild
+/// them, and get the rest back from the codec when they are replayed.
+pub trait DeadLetterCodec<Event>: Send + Sync + 'static {
+    /// Encode `event` for storage
+    ///
+    /// # Errors
+    ///
+    /// If the event can't be encoded
+    fn encode(&self, event: &Event) -> serde_json::Result<serde_json::Value>;
+
+    /// Restore an event encoded by [`DeadLetterCodec::encode`]
+    ///
+    /// # Errors
+    ///
+    /// If `value` isn't a valid encoding of an event
+    fn decode(&self, value: serde_json::Value) -> serde_json::Result<Event>;
+}
+
+/// Stores events that can be serialized as they are
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+impl<Event: Serialize + DeserializeOwned> DeadLetterCodec<Event> for JsonCodec {
+    fn encode(&self, event: &Event) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(event)
+    }
+
+    fn decode(&self, value: serde_json::Value) -> serde_json::Result<Event> {
+        serde_json::from_value(value)
+    }
+}
+
+/// Replays dead letters through the job they came from
+///
+/// Obtained from [`EventFlowWrapper::dead_letter_replayer`](crate::executor::EventFlowWrapper::dead_letter_replayer).
+/// Replays run outside the event loop, so they don't count towards its concurrency limit.
+pub struct DeadLetterReplayer<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    pub(crate) runner: JobRunner<PreProcessOut, ProcessorError>,
+    pub(crate) queue: Arc<DeadLetterQueue>,
+    pub(crate) codec: Arc<dyn DeadLetterCodec<PreProcessOut>>,
+}
+
+impl<PreProcessOut, ProcessorError> DeadLetterReplayer<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    /// The queue being replayed from
+    pub fn queue(&self) -> &DeadLetterQueue {
+        &self.queue
+    }
+
+    /// Run the dead letter with the given ID through the job again, with the same retry policy and timeout.
+    ///
+    /// The dead letter is removed from the queue if the job succeeds, and updated with the new error otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such dead letter, its event can't be decoded, or the job fails again.
+    pub async fn replay(&self, id: u64) -> Result<(), Error<ProcessorError>> {
+        let letter = self
+            .queue
+            .get(id)
+            .ok_or_else(|| Error::Other(format!("No dead letter with ID {id}")))?;
+        let event = self
+            .codec
+            .decode(letter.event)
+            .map_err(|e| Error::Other(format!("Failed to decode dead letter {id}: {e}")))?;
+
+        self.runner
+            .run(event, self.runner.next_call(), Some(id))
+            .await
+    }
+
+    /// Replay every dead letter in the queue, oldest first, returning the IDs of those that failed again.
+    pub async fn replay_all(&self) -> Vec<u64> {
+        let mut failed = Vec::new();
+        for letter in self.queue.list() {
+            if let Err(e) = self.replay(letter.id).await {
+                gadget_logging::warn!("Replay of dead letter {} failed: {e}", letter.id);
+                failed.push(letter.id);
+            }
+        }
+        failed
+    }
+}
+
+impl<PreProcessOut, ProcessorError> Clone for DeadLetterReplayer<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            runner: self.runner.clone(),
+            queue: self.queue.clone(),
+            codec: self.codec.clone(),
+        }
+    }
+}
+
+/// A [`DeadLetterReplayer`] for any job, whatever its event and error types
+#[async_trait]
+pub trait ReplayDeadLetters: Send + Sync {
+    /// The queue being replayed from
+    fn queue(&self) -> &DeadLetterQueue;
+
+    /// See [`DeadLetterReplayer::replay`]
+    async fn replay(&self, id: u64) -> Result<(), Box<dyn core::error::Error + Send + Sync>>;
+
+    /// See [`DeadLetterReplayer::replay_all`]
+    async fn replay_all(&self) -> Vec<u64>;
+}
+
+#[async_trait]
+impl<PreProcessOut, ProcessorError> ReplayDeadLetters
+    for DeadLetterReplayer<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    fn queue(&self) -> &DeadLetterQueue {
+        &self.queue
+    }
+
+    async fn replay(&self, id: u64) -> Result<(), Box<dyn core::error::Error + Send + Sync>> {
+        DeadLetterReplayer::replay(self, id)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn core::error::Error + Send + Sync>)
+    }
+
+    async fn replay_all(&self) -> Vec<u64> {
+        DeadLetterReplayer::replay_all(self).await
+    }
+}
+
+/// The dead-letter replayers of the jobs started by a runner, by job name
+///
+/// Jobs register their replayer when their event handler starts, so the handle can be cloned
+/// beforehand and used once the jobs are running.
+#[derive(Clone, Default)]
+pub struct DeadLetterReplayers(Arc<Mutex<BTreeMap<String, Arc<dyn ReplayDeadLetters>>>>);
+
+impl DeadLetterReplayers {
+    /// Register the replayer of the job named `job_name`, replacing any previous one
+    pub fn insert<R: ReplayDeadLetters + 'static>(&self, job_name: &str, replayer: R) {
+        let _ = self.lock().insert(job_name.to_string(), Arc::new(replayer));
+    }
+
+    /// Returns the replayer of the job named `job_name`, if it has a dead-letter queue
+    pub fn get(&self, job_name: &str) -> Option<Arc<dyn ReplayDeadLetters>> {
+        self.lock().get(job_name).cloned()
+    }
+
+    /// The names of all jobs with a dead-letter queue
+    pub fn job_names(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+
+    fn lock(
+        &self,
+    ) -> gadget_std::sync::MutexGuard<'_, BTreeMap<String, Arc<dyn ReplayDeadLetters>>> {
+        self.0
+            .lock()
+            .unwrap_or_else(gadget_std::sync::PoisonError::into_inner)
+    }
+}
+
+impl gadget_std::fmt::Debug for DeadLetterReplayers {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        f.debug_tuple("DeadLetterReplayers")
+            .field(&self.job_names())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dead_letters_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let queue = DeadLetterQueue::new(Some(dir.path()), "job");
+        let first = queue.push(None, serde_json::json!([1, 2]), "failed".into(), 3);
+        let second = queue.push(None, serde_json::json!([3, 4]), "failed".into(), 3);
+        assert_ne!(first, second);
+
+        let queue = DeadLetterQueue::new(Some(dir.path()), "job");
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.get(first).unwrap().event, serde_json::json!([1, 2]));
+
+        assert!(queue.remove(first).is_some());
+        let queue = DeadLetterQueue::new(Some(dir.path()), "job");
+        assert_eq!(
+            queue.list().into_iter().map(|l| l.id).collect::<Vec<_>>(),
+            vec![second]
+        );
+
+        // IDs are not reused after a restart
+        let third = queue.push(None, serde_json::json!(null), "failed".into(), 1);
+        assert!(third > second);
+
+        // Nothing is left behind from the atomic writes
+        let files = fs::read_dir(dir.path().join(DEAD_LETTER_DIR_NAME))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![gadget_std::ffi::OsString::from("job.json")]);
+    }
+
+    #[test]
+    fn replayed_failures_update_the_dead_letter() {
+        let queue = DeadLetterQueue::new(None, "job");
+        let id = queue.push(None, serde_json::json!(1), "first".into(), 3);
+        let updated = queue.push(Some(id), serde_json::json!(1), "second".into(), 3);
+
+        assert_eq!(id, updated);
+        let letter = queue.get(id).unwrap();
+        assert_eq!(letter.error, "second");
+        assert_eq!(letter.attempts, 6);
+        assert_eq!(queue.len(), 1);
+    }
+}
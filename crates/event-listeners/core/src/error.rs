@@ -17,6 +17,9 @@ where
     #[error("Event loop ended unexpectedly")]
     Termination,
 
+    #[error("Job processing timed out after {0:?}")]
+    Timeout(gadget_std::time::Duration),
+
     #[error("{0}")]
     Other(String),
 
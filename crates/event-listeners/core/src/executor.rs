@@ -1,9 +1,19 @@
+use crate::dead_letter::{DeadLetterCodec, DeadLetterQueue, DeadLetterReplayer, JsonCodec};
 use crate::error::Error;
+use crate::middleware::{JobCall, JobLayer, Layers, Stage};
+use crate::retry::RetryPolicy;
 use crate::EventListener;
 use async_trait::async_trait;
+use gadget_std::collections::BTreeMap;
 use gadget_std::future::Future;
 use gadget_std::marker::PhantomData;
+use gadget_std::num::NonZeroUsize;
 use gadget_std::pin::Pin;
+use gadget_std::sync::atomic::{AtomicU64, Ordering};
+use gadget_std::sync::Arc;
+use gadget_std::time::Duration;
+use tokio::sync::{oneshot, Semaphore};
+use tokio::task::JoinSet;
 
 /// [`EventFlowExecutor`]: Allows flexible and organized execution of events
 ///
@@ -73,10 +83,12 @@ where
         self.get_postprocessor()(job_output).await
     }
 
+    /// Handles events one at a time, stopping at the first job call that fails
+    ///
+    /// See [`EventFlowWrapper`] for concurrency, retries and dead-lettering.
     async fn event_loop(
         &mut self,
     ) -> Result<(), Error<<Self as EventListener<T, Ctx>>::ProcessorError>> {
-        // TODO: add exponential backoff logic here
         while let Some(event) = self.next_event().await {
             match self.pre_process(event).await {
                 Ok(Some(preprocessed_event)) => {
@@ -118,6 +130,198 @@ where
 
 pub type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send + 'static>>;
 
+/// Runs the job processor, then the post-processor, on an already pre-processed event
+type ProcessAndPost<PreProcessOut, ProcessorError> = Arc<
+    dyn Fn(PreProcessOut, Layers, JobCall) -> BoxedFuture<Result<(), Error<ProcessorError>>>
+        + Send
+        + Sync,
+>;
+
+/// Extracts the key that events are ordered by when [`EventFlowWrapper::with_ordering_key`] is used
+type OrderingKey<PreProcessOut> = Box<dyn Fn(&PreProcessOut) -> u64 + Send + Sync>;
+
+struct Retry<PreProcessOut> {
+    policy: RetryPolicy,
+    clone_event: fn(&PreProcessOut) -> PreProcessOut,
+}
+
+impl<PreProcessOut> Clone for Retry<PreProcessOut> {
+    fn clone(&self) -> Self {
+        Self {
+            policy: self.policy.clone(),
+            clone_event: self.clone_event,
+        }
+    }
+}
+
+struct DeadLetters<PreProcessOut> {
+    queue: Arc<DeadLetterQueue>,
+    codec: Arc<dyn DeadLetterCodec<PreProcessOut>>,
+}
+
+impl<PreProcessOut> Clone for DeadLetters<PreProcessOut> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            codec: self.codec.clone(),
+        }
+    }
+}
+
+/// Runs pre-processed events through the job and post-processor, applying the layers, timeout, retry
+/// policy and dead-letter queue configured on the [`EventFlowWrapper`]
+pub(crate) struct JobRunner<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    process_and_post: ProcessAndPost<PreProcessOut, ProcessorError>,
+    layers: Layers,
+    job_id: Option<u64>,
+    job_name: Option<&'static str>,
+    sequence: Arc<AtomicU64>,
+    timeout: Option<Duration>,
+    retry: Option<Retry<PreProcessOut>>,
+    dead_letters: Option<DeadLetters<PreProcessOut>>,
+}
+
+impl<PreProcessOut, ProcessorError> Clone for JobRunner<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            process_and_post: self.process_and_post.clone(),
+            layers: self.layers.clone(),
+            job_id: self.job_id,
+            job_name: self.job_name,
+            sequence: self.sequence.clone(),
+            timeout: self.timeout,
+            retry: self.retry.clone(),
+            dead_letters: self.dead_letters.clone(),
+        }
+    }
+}
+
+impl<PreProcessOut, ProcessorError> JobRunner<PreProcessOut, ProcessorError>
+where
+    PreProcessOut: Send + 'static,
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    /// Whether a job call that fails every attempt should stop the event loop
+    fn failure_is_fatal(&self) -> bool {
+        self.retry.is_none() && self.dead_letters.is_none()
+    }
+
+    /// Describes the next job call, before it is pre-processed
+    pub(crate) fn next_call(&self) -> JobCall {
+        JobCall {
+            job_id: self.job_id,
+            job_name: self.job_name,
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
+            stage: Stage::PreProcess,
+        }
+    }
+
+    async fn attempt(
+        &self,
+        event: PreProcessOut,
+        call: JobCall,
+    ) -> Result<(), Error<ProcessorError>> {
+        let job = (self.process_and_post)(event, self.layers.clone(), call);
+        match self.timeout {
+            // Dropping the job future on timeout cancels it
+            Some(timeout) => tokio::time::timeout(timeout, job)
+                .await
+                .map_err(|_| Error::Timeout(timeout))?,
+            None => job.await,
+        }
+    }
+
+    /// Run `event` through the job, retrying on failure, and dead-letter it if every attempt fails
+    ///
+    /// `replayed` is the ID of the dead letter the event came from, if any.
+    pub(crate) async fn run(
+        &self,
+        event: PreProcessOut,
+        call: JobCall,
+        replayed: Option<u64>,
+    ) -> Result<(), Error<ProcessorError>> {
+        let encoded = self
+            .dead_letters
+            .as_ref()
+            .map(|dead_letters| dead_letters.codec.encode(&event));
+        let mut delays = self
+            .retry
+            .as_ref()
+            .map(|retry| retry.policy.delays())
+            .into_iter()
+            .flatten()
+            .peekable();
+
+        let mut event = Some(event);
+        let mut attempts = 0;
+        let err = loop {
+            attempts += 1;
+            // Only the last attempt can consume the event
+            let input = match (&self.retry, delays.peek()) {
+                (Some(retry), Some(_)) => {
+                    (retry.clone_event)(event.as_ref().expect("Only taken on the last attempt"))
+                }
+                _ => event.take().expect("Only taken on the last attempt"),
+            };
+
+            let err = match self.attempt(input, call).await {
+                Ok(()) => {
+                    if let (Some(dead_letters), Some(id)) = (&self.dead_letters, replayed) {
+                        let _ = dead_letters.queue.remove(id);
+                    }
+                    return Ok(());
+                }
+                Err(err) => err,
+            };
+
+            match delays.next() {
+                Some(delay) => {
+                    gadget_logging::warn!(
+                        "Job call failed on attempt {attempts}, retrying in {delay:?}: {err}"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                None => break err,
+            }
+        };
+
+        if !self.failure_is_fatal() {
+            gadget_logging::error!("Job call failed after {attempts} attempt(s): {err}");
+        }
+
+        match (&self.dead_letters, encoded) {
+            (Some(dead_letters), Some(Ok(event))) => {
+                let id = dead_letters
+                    .queue
+                    .push(replayed, event, err.to_string(), attempts);
+                gadget_logging::warn!("Failed job call stored as dead letter {id}");
+            }
+            (Some(_), Some(Err(e))) => {
+                gadget_logging::error!("Failed to encode job call as a dead letter: {e}");
+            }
+            _ => {}
+        }
+
+        Err(err)
+    }
+}
+
+/// [`EventFlowWrapper`]: An [`EventFlowExecutor`] built from an [`EventListener`] and three processor functions
+///
+/// By default, events are handled one at a time. [`EventFlowWrapper::with_max_concurrency`] allows up to
+/// `N` events to be processed at once, each on its own task, and [`EventFlowWrapper::with_ordering_key`]
+/// keeps events that share a key processed in the order they were received.
+///
+/// A job call that fails stops the event loop, unless a [`RetryPolicy`] or [`DeadLetterQueue`] is configured,
+/// in which case calls that fail every attempt are logged, dead-lettered if possible, and skipped.
 #[allow(clippy::type_complexity)]
 pub struct EventFlowWrapper<
     Ctx: Send + 'static,
@@ -133,6 +337,9 @@ pub struct EventFlowWrapper<
     job_processor:
         Box<dyn Fn(PreProcessOut) -> BoxedFuture<Result<JobOutput, Error<ProcessorError>>> + Send>,
     postprocessor: Box<dyn Fn(JobOutput) -> BoxedFuture<Result<(), Error<ProcessorError>>> + Send>,
+    runner: JobRunner<PreProcessOut, ProcessorError>,
+    max_concurrency: NonZeroUsize,
+    ordering_key: Option<OrderingKey<PreProcessOut>>,
     _pd: PhantomData<Ctx>,
 }
 
@@ -155,19 +362,177 @@ impl<
         Pre: Fn(Event) -> PreFut + Send + 'static,
         PreFut:
             Future<Output = Result<Option<PreProcessOut>, Error<ProcessorError>>> + Send + 'static,
-        Job: Fn(PreProcessOut) -> JobFut + Send + 'static,
+        Job: Fn(PreProcessOut) -> JobFut + Send + Sync + 'static,
         JobFut: Future<Output = Result<JobOutput, Error<ProcessorError>>> + Send + 'static,
-        Post: Fn(JobOutput) -> PostFut + Send + 'static,
+        Post: Fn(JobOutput) -> PostFut + Send + Sync + 'static,
         PostFut: Future<Output = Result<(), Error<ProcessorError>>> + Send + 'static,
     {
+        let job_processor = Arc::new(job_processor);
+        let postprocessor = Arc::new(postprocessor);
+
+        let process_and_post: ProcessAndPost<PreProcessOut, ProcessorError> = {
+            let job_processor = job_processor.clone();
+            let postprocessor = postprocessor.clone();
+            Arc::new(move |event, layers: Layers, call: JobCall| {
+                let job_processor = job_processor.clone();
+                let postprocessor = postprocessor.clone();
+                Box::pin(async move {
+                    let Some(job_output) = layers
+                        .wrap(&call.at(Stage::Process), job_processor(event))
+                        .await?
+                    else {
+                        return Ok(());
+                    };
+                    layers
+                        .wrap(&call.at(Stage::PostProcess), postprocessor(job_output))
+                        .await?;
+                    Ok(())
+                })
+            })
+        };
+
         Self {
             event_listener: Box::new(event_listener),
             preprocessor: Box::new(move |event| Box::pin(preprocessor(event))),
             job_processor: Box::new(move |event| Box::pin(job_processor(event))),
             postprocessor: Box::new(move |event| Box::pin(postprocessor(event))),
+            runner: JobRunner {
+                process_and_post,
+                layers: Layers::default(),
+                job_id: None,
+                job_name: None,
+                sequence: Arc::new(AtomicU64::new(0)),
+                timeout: None,
+                retry: None,
+                dead_letters: None,
+            },
+            max_concurrency: NonZeroUsize::MIN,
+            ordering_key: None,
             _pd: PhantomData,
         }
     }
+
+    /// Process up to `max_concurrency` events at once, each on its own task
+    ///
+    /// Pre-processing still happens in the order events are received. Once `max_concurrency` events are
+    /// in flight, the event listener is not polled again until one of them finishes.
+    ///
+    /// Defaults to `1`, which handles events strictly one after another.
+    #[must_use]
+    pub fn with_max_concurrency(mut self, max_concurrency: NonZeroUsize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    /// Keep events that map to the same key processed in the order they were received
+    ///
+    /// Events with different keys may still run concurrently, up to the limit set by
+    /// [`EventFlowWrapper::with_max_concurrency`]. An event waiting for an earlier event with the
+    /// same key doesn't count towards that limit, but at most `max_concurrency` events may wait at
+    /// once. Past that, the event listener is not polled again until one of them starts.
+    #[must_use]
+    pub fn with_ordering_key<F>(mut self, ordering_key: F) -> Self
+    where
+        F: Fn(&PreProcessOut) -> u64 + Send + Sync + 'static,
+    {
+        self.ordering_key = Some(Box::new(ordering_key));
+        self
+    }
+
+    /// Identify the job in the [`JobCall`]s passed to layers
+    #[must_use]
+    pub fn with_job_metadata(mut self, job_id: u64, job_name: &'static str) -> Self {
+        self.runner.job_id = Some(job_id);
+        self.runner.job_name = Some(job_name);
+        self
+    }
+
+    /// Wrap each stage of every job call in `layer`
+    ///
+    /// Layers run in the order they are added, each inside the ones added before it.
+    #[must_use]
+    pub fn with_layer<L: JobLayer>(mut self, layer: L) -> Self {
+        self.runner.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// Wrap each stage of every job call in all of `layers`, such as those a runner passes to every job
+    ///
+    /// See [`EventFlowWrapper::with_layer`] for the order layers run in.
+    #[must_use]
+    pub fn with_layers(mut self, layers: &Layers) -> Self {
+        self.runner.layers.append(layers);
+        self
+    }
+
+    /// Cancel a job call, and its post-processing, if it takes longer than `timeout`
+    ///
+    /// The timeout applies to each attempt separately when a [`RetryPolicy`] is used.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.runner.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry failed job calls according to `policy`
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self
+    where
+        PreProcessOut: Clone,
+    {
+        self.runner.retry = Some(Retry {
+            policy,
+            clone_event: PreProcessOut::clone,
+        });
+        self
+    }
+
+    /// Store job calls that fail every attempt in `queue`, so they can be replayed later
+    ///
+    /// The pre-processed events are stored as JSON. Use [`EventFlowWrapper::with_dead_letter_codec`]
+    /// for events that can't be serialized as they are.
+    ///
+    /// See [`EventFlowWrapper::dead_letter_replayer`].
+    #[must_use]
+    pub fn with_dead_letter_queue(self, queue: Arc<DeadLetterQueue>) -> Self
+    where
+        PreProcessOut: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.with_dead_letter_codec(queue, JsonCodec)
+    }
+
+    /// Store job calls that fail every attempt in `queue`, encoding their events with `codec`
+    ///
+    /// See [`EventFlowWrapper::dead_letter_replayer`].
+    #[must_use]
+    pub fn with_dead_letter_codec<Codec>(
+        mut self,
+        queue: Arc<DeadLetterQueue>,
+        codec: Codec,
+    ) -> Self
+    where
+        Codec: DeadLetterCodec<PreProcessOut>,
+    {
+        self.runner.dead_letters = Some(DeadLetters {
+            queue,
+            codec: Arc::new(codec),
+        });
+        self
+    }
+
+    /// Returns a handle that replays dead letters through this job, if a dead-letter queue is configured
+    ///
+    /// The handle remains usable after the event loop has been started.
+    pub fn dead_letter_replayer(
+        &self,
+    ) -> Option<DeadLetterReplayer<PreProcessOut, ProcessorError>> {
+        let dead_letters = self.runner.dead_letters.as_ref()?;
+        Some(DeadLetterReplayer {
+            runner: self.runner.clone(),
+            queue: dead_letters.queue.clone(),
+            codec: dead_letters.codec.clone(),
+        })
+    }
 }
 
 #[async_trait]
@@ -207,6 +572,128 @@ impl<
     fn get_postprocessor(&mut self) -> &mut Self::PostProcessor {
         &mut self.postprocessor
     }
+
+    /// Unlike the default implementation, pre-processed events are handed off to spawned tasks,
+    /// bounded by [`EventFlowWrapper::with_max_concurrency`].
+    async fn event_loop(&mut self) -> Result<(), Error<ProcessorError>> {
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency.get()));
+        // Bounds the events waiting on an earlier event with the same ordering key
+        let queue = Arc::new(Semaphore::new(self.max_concurrency.get()));
+        let mut tasks = JoinSet::new();
+        // Resolves once the most recently received event for each ordering key has been handled
+        let mut last_by_key: BTreeMap<u64, oneshot::Receiver<()>> = BTreeMap::new();
+
+        loop {
+            // Don't pull another event from the listener until there is capacity to process it
+            let permit = tokio::select! {
+                biased;
+                Some(result) = tasks.join_next() => {
+                    flatten_task_result(result)?;
+                    continue;
+                }
+                permit = semaphore.clone().acquire_owned() => {
+                    permit.expect("The semaphore is never closed")
+                }
+            };
+
+            let Some(event) = self.next_event().await else {
+                break;
+            };
+
+            let call = self.runner.next_call();
+            let layers = self.runner.layers.clone();
+            let preprocessed_event = match layers
+                .wrap(&call, self.pre_process(event))
+                .await
+                .map(Option::flatten)
+            {
+                Ok(Some(preprocessed_event)) => preprocessed_event,
+                // Skipped
+                Ok(None) => continue,
+                Err(Error::BadArgumentDecoding(err)) => {
+                    gadget_logging::warn!("Bad argument decoding, will skip handling event and consequentially triggering the job: {}", err);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+
+            let (predecessor, done) = match &self.ordering_key {
+                Some(ordering_key) => {
+                    let key = ordering_key(&preprocessed_event);
+                    // Forget keys that have no events left in flight
+                    last_by_key.retain(|_, done| {
+                        matches!(done.try_recv(), Err(oneshot::error::TryRecvError::Empty))
+                    });
+                    let (done_tx, done_rx) = oneshot::channel();
+                    (last_by_key.insert(key, done_rx), Some(done_tx))
+                }
+                None => (None, None),
+            };
+
+            // An event queued behind its predecessor trades its permit for a place in the queue, so
+            // that events with other keys can run in the meantime, and takes a new permit once its
+            // predecessor is handled
+            let (permit, queued) = if predecessor.is_some() {
+                drop(permit);
+                let queued = loop {
+                    tokio::select! {
+                        biased;
+                        Some(result) = tasks.join_next() => {
+                            flatten_task_result(result)?;
+                        }
+                        queued = queue.clone().acquire_owned() => {
+                            break queued.expect("The semaphore is never closed");
+                        }
+                    }
+                };
+                (None, Some(queued))
+            } else {
+                (Some(permit), None)
+            };
+
+            let runner = self.runner.clone();
+            let semaphore = semaphore.clone();
+            tasks.spawn(async move {
+                if let Some(predecessor) = predecessor {
+                    // The sender is dropped once the previous event is handled, successfully or not
+                    let _ = predecessor.await;
+                }
+                let permit = match permit {
+                    Some(permit) => permit,
+                    None => semaphore
+                        .acquire_owned()
+                        .await
+                        .expect("The semaphore is never closed"),
+                };
+                drop(queued);
+
+                let result = runner.run(preprocessed_event, call, None).await;
+                drop((permit, done));
+                if runner.failure_is_fatal() {
+                    result
+                } else {
+                    Ok(())
+                }
+            });
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            flatten_task_result(result)?;
+        }
+
+        Err(Error::Termination)
+    }
+}
+
+fn flatten_task_result<ProcessorError>(
+    result: Result<Result<(), Error<ProcessorError>>, tokio::task::JoinError>,
+) -> Result<(), Error<ProcessorError>>
+where
+    ProcessorError: core::error::Error + Send + Sync + 'static,
+{
+    result.map_err(|e| Error::Other(format!("Event processing task failed: {e}")))?
 }
 
 #[async_trait]
@@ -315,4 +802,381 @@ mod tests {
             }
         }
     }
+
+    /// Emits a fixed list of `(key, id)` events, then stops
+    struct ListEventListener(gadget_std::collections::VecDeque<(u64, u64)>);
+
+    #[async_trait]
+    impl EventListener<(u64, u64), ()> for ListEventListener {
+        type ProcessorError = Infallible;
+
+        async fn new(_context: &()) -> Result<Self, Error<Self::ProcessorError>> {
+            unreachable!("Constructed directly")
+        }
+
+        async fn next_event(&mut self) -> Option<(u64, u64)> {
+            self.0.pop_front()
+        }
+    }
+
+    async fn identity(event: (u64, u64)) -> Result<Option<(u64, u64)>, Error<Infallible>> {
+        Ok(Some(event))
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_bounds_concurrency() {
+        let in_flight = Arc::new(AtomicU64::new(0));
+        let max_in_flight = Arc::new(AtomicU64::new(0));
+        let handled = Arc::new(AtomicU64::new(0));
+
+        let job_processor = {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+            move |_event: (u64, u64)| {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, Error<Infallible>>(())
+                }
+            }
+        };
+        let post_process = {
+            let handled = handled.clone();
+            move |()| {
+                handled.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            }
+        };
+
+        let events = (0..12).map(|id| (id, id)).collect();
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener(events),
+            identity,
+            job_processor,
+            post_process,
+        )
+        .with_max_concurrency(NonZeroUsize::new(4).unwrap());
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Termination)));
+        assert_eq!(handled.load(Ordering::SeqCst), 12);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_orders_by_key() {
+        let order = Arc::new(gadget_std::sync::Mutex::new(Vec::new()));
+
+        // Earlier events take longer, so they would finish last without ordering
+        let job_processor = move |(key, id): (u64, u64)| async move {
+            tokio::time::sleep(Duration::from_millis(60 - 10 * (id / 2))).await;
+            Ok::<_, Error<Infallible>>((key, id))
+        };
+        let post_process = {
+            let order = order.clone();
+            move |handled: (u64, u64)| {
+                order.lock().unwrap().push(handled);
+                async { Ok(()) }
+            }
+        };
+
+        let events = (0..6).map(|id| (id % 2, id)).collect();
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener(events),
+            identity,
+            job_processor,
+            post_process,
+        )
+        .with_max_concurrency(NonZeroUsize::new(6).unwrap())
+        .with_ordering_key(|(key, _id): &(u64, u64)| *key);
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Termination)));
+
+        let order = order.lock().unwrap();
+        assert_eq!(order.len(), 6);
+        for key in 0..2 {
+            let ids = order
+                .iter()
+                .filter(|(k, _)| *k == key)
+                .map(|(_, id)| *id)
+                .collect::<Vec<_>>();
+            assert!(
+                ids.is_sorted(),
+                "events for key {key} handled out of order: {ids:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_queued_events_do_not_hold_permits() {
+        let order = Arc::new(gadget_std::sync::Mutex::new(Vec::new()));
+
+        let job_processor = move |(_key, id): (u64, u64)| async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, Error<Infallible>>(id)
+        };
+        let post_process = {
+            let order = order.clone();
+            move |id: u64| {
+                order.lock().unwrap().push(id);
+                async { Ok(()) }
+            }
+        };
+
+        // Events 1 and 2 queue behind event 0, which must not stop event 3 from running
+        let events = [(0, 0), (0, 1), (0, 2), (1, 3)].into_iter().collect();
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener(events),
+            identity,
+            job_processor,
+            post_process,
+        )
+        .with_max_concurrency(NonZeroUsize::new(2).unwrap())
+        .with_ordering_key(|(key, _id): &(u64, u64)| *key);
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Termination)));
+
+        let order = order.lock().unwrap();
+        let position = |id| order.iter().position(|handled| *handled == id).unwrap();
+        assert!(
+            position(3) < position(1),
+            "event 3 waited for event 1: {order:?}"
+        );
+    }
+
+    /// Emits `(0, id)` events for as long as it is polled, counting how many it has emitted
+    struct FloodEventListener(Arc<AtomicU64>);
+
+    #[async_trait]
+    impl EventListener<(u64, u64), ()> for FloodEventListener {
+        type ProcessorError = Infallible;
+
+        async fn new(_context: &()) -> Result<Self, Error<Self::ProcessorError>> {
+            unreachable!("Constructed directly")
+        }
+
+        async fn next_event(&mut self) -> Option<(u64, u64)> {
+            Some((0, self.0.fetch_add(1, Ordering::SeqCst)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_bounds_queued_events() {
+        let polled = Arc::new(AtomicU64::new(0));
+
+        // The first event never finishes, so every later event queues behind it
+        let job_processor = |_event: (u64, u64)| async move {
+            gadget_std::future::pending::<()>().await;
+            Ok::<_, Error<Infallible>>(())
+        };
+
+        let mut event_flow = EventFlowWrapper::new(
+            FloodEventListener(polled.clone()),
+            identity,
+            job_processor,
+            |()| async { Ok(()) },
+        )
+        .with_max_concurrency(NonZeroUsize::new(2).unwrap())
+        .with_ordering_key(|(key, _id): &(u64, u64)| *key);
+
+        let res = tokio::time::timeout(Duration::from_millis(200), event_flow.event_loop()).await;
+        assert!(res.is_err(), "the event loop should still be running");
+
+        // One running event, two queued ones, and one waiting for a place in the queue
+        assert_eq!(polled.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_surfaces_job_errors() {
+        let job_processor = |(_key, id): (u64, u64)| async move {
+            if id == 2 {
+                return Err(Error::Other(String::from("job failed")));
+            }
+            Ok::<_, Error<Infallible>>(())
+        };
+
+        let events = (0..5).map(|id| (id, id)).collect();
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener(events),
+            identity,
+            job_processor,
+            |()| async { Ok(()) },
+        )
+        .with_max_concurrency(NonZeroUsize::new(2).unwrap());
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Other(msg)) if msg == "job failed"));
+    }
+
+    fn fast_retries(max_retries: u32) -> RetryPolicy {
+        RetryPolicy::new(max_retries).backoff(
+            crate::exponential_backoff::ExponentialBackoff::from_millis(1),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_retries_failed_jobs() {
+        let attempts = Arc::new(AtomicU64::new(0));
+        let job_processor = {
+            let attempts = attempts.clone();
+            move |_event: (u64, u64)| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    if attempt < 3 {
+                        return Err(Error::Other(String::from("transient failure")));
+                    }
+                    Ok::<_, Error<Infallible>>(())
+                }
+            }
+        };
+
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener([(0, 0)].into()),
+            identity,
+            job_processor,
+            |()| async { Ok(()) },
+        )
+        .with_retry_policy(fast_retries(2));
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Termination)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_times_out_jobs() {
+        let job_processor = |(_key, id): (u64, u64)| async move {
+            if id == 0 {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            Ok::<_, Error<Infallible>>(())
+        };
+
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener([(0, 0)].into()),
+            identity,
+            job_processor,
+            |()| async { Ok(()) },
+        )
+        .with_timeout(Duration::from_millis(10));
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_dead_letters_and_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = Arc::new(DeadLetterQueue::new(Some(dir.path()), "test_job"));
+        let healthy = Arc::new(gadget_std::sync::atomic::AtomicBool::new(false));
+        let handled = Arc::new(AtomicU64::new(0));
+
+        let job_processor = {
+            let healthy = healthy.clone();
+            move |(_key, id): (u64, u64)| {
+                let healthy = healthy.load(Ordering::SeqCst);
+                async move {
+                    if id == 1 && !healthy {
+                        return Err(Error::Other(String::from("permanent failure")));
+                    }
+                    Ok::<_, Error<Infallible>>(id)
+                }
+            }
+        };
+        let post_process = {
+            let handled = handled.clone();
+            move |_id: u64| {
+                handled.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            }
+        };
+
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener((0..3).map(|id| (id, id)).collect()),
+            identity,
+            job_processor,
+            post_process,
+        )
+        .with_retry_policy(fast_retries(1))
+        .with_dead_letter_queue(queue.clone());
+        let replayer = event_flow.dead_letter_replayer().unwrap();
+
+        // The failing call doesn't stop the other events from being handled
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Termination)));
+        assert_eq!(handled.load(Ordering::SeqCst), 2);
+
+        let letters = queue.list();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].attempts, 2);
+        assert_eq!(letters[0].event, serde_json::json!([1, 1]));
+
+        // Replaying before the job is fixed updates the dead letter
+        assert!(replayer.replay(letters[0].id).await.is_err());
+        assert_eq!(queue.get(letters[0].id).unwrap().attempts, 4);
+
+        healthy.store(true, Ordering::SeqCst);
+        replayer.replay(letters[0].id).await.unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(handled.load(Ordering::SeqCst), 3);
+        assert!(replayer.replay(letters[0].id).await.is_err());
+    }
+
+    /// Records the stages of every job call it sees
+    struct StageLayer(Arc<gadget_std::sync::Mutex<Vec<JobCall>>>);
+
+    #[async_trait]
+    impl JobLayer for StageLayer {
+        async fn call(
+            &self,
+            call: &JobCall,
+            next: crate::middleware::Next<'_>,
+        ) -> Result<(), crate::middleware::LayerError> {
+            self.0.lock().unwrap().push(*call);
+            next.run().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_flow_executor_runs_layers() {
+        let calls = Arc::new(gadget_std::sync::Mutex::new(Vec::new()));
+
+        let mut event_flow = EventFlowWrapper::new(
+            ListEventListener((0..2).map(|id| (id, id)).collect()),
+            identity,
+            |_event: (u64, u64)| async { Ok::<_, Error<Infallible>>(()) },
+            |()| async { Ok(()) },
+        )
+        .with_job_metadata(3, "test_job")
+        .with_layer(StageLayer(calls.clone()));
+
+        let res = event_flow.event_loop().await;
+        assert!(matches!(res, Err(Error::Termination)));
+
+        let calls = calls.lock().unwrap();
+        let stages = calls
+            .iter()
+            .map(|call| (call.sequence, call.stage))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stages,
+            [
+                (0, Stage::PreProcess),
+                (0, Stage::Process),
+                (0, Stage::PostProcess),
+                (1, Stage::PreProcess),
+                (1, Stage::Process),
+                (1, Stage::PostProcess),
+            ]
+        );
+        assert!(calls
+            .iter()
+            .all(|call| call.job_id == Some(3) && call.job_name == Some("test_job")));
+    }
 }
@@ -0,0 +1,470 @@
+//! Persistent record of computed job results and whether they were submitted, so that redelivered
+//! events don't re-run the job or submit the same result twice.
+
+use crate::storage::store_bytes;
+use gadget_std::collections::BTreeMap;
+use gadget_std::fmt::{self, Display};
+use gadget_std::fs;
+use gadget_std::path::{Path, PathBuf};
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::atomic::{AtomicU64, Ordering};
+use gadget_std::sync::{Arc, Mutex};
+use gadget_std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// The directory (relative to the gadget data directory) holding the idempotency stores.
+pub const IDEMPOTENCY_DIR_NAME: &str = "idempotency";
+
+/// How long a submitted result is kept by default, see [`IdempotencyStore::with_confirmed_ttl`].
+pub const DEFAULT_CONFIRMED_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How long a result that was never submitted is kept by default, see [`IdempotencyStore::with_pending_ttl`].
+pub const DEFAULT_PENDING_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Uniquely identifies a job call, regardless of how many times its event is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CallKey {
+    /// A `JobCalled` event on Tangle
+    Tangle {
+        service_id: u64,
+        job_id: u8,
+        call_id: u64,
+    },
+    /// A log emitted by an EVM contract
+    Evm {
+        chain_id: u64,
+        tx_hash: [u8; 32],
+        log_index: u64,
+    },
+}
+
+impl CallKey {
+    #[must_use]
+    pub fn tangle(service_id: u64, job_id: u8, call_id: u64) -> Self {
+        CallKey::Tangle {
+            service_id,
+            job_id,
+            call_id,
+        }
+    }
+
+    #[must_use]
+    pub fn evm(chain_id: u64, tx_hash: [u8; 32], log_index: u64) -> Self {
+        CallKey::Evm {
+            chain_id,
+            tx_hash,
+            log_index,
+        }
+    }
+}
+
+impl Display for CallKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallKey::Tangle {
+                service_id,
+                job_id,
+                call_id,
+            } => write!(f, "tangle/{service_id}/{job_id}/{call_id}"),
+            CallKey::Evm {
+                chain_id,
+                tx_hash,
+                log_index,
+            } => {
+                write!(f, "evm/{chain_id}/0x")?;
+                for byte in tx_hash {
+                    write!(f, "{byte:02x}")?;
+                }
+                write!(f, "/{log_index}")
+            }
+        }
+    }
+}
+
+/// Whether a computed result has been submitted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    /// The result was computed, but not (successfully) submitted
+    #[default]
+    Pending,
+    /// The result was submitted
+    Confirmed,
+}
+
+/// The stored outcome of a job call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallRecord {
+    /// The job's result, as JSON
+    pub result: serde_json::Value,
+    pub status: SubmissionStatus,
+    /// When the result was computed, in seconds since the UNIX epoch
+    pub recorded_at: u64,
+    /// When the result was submitted, in seconds since the UNIX epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmed_at: Option<u64>,
+}
+
+/// Job results keyed by [`CallKey`], persisted to the gadget data directory.
+///
+/// Every change is written to disk as it happens, so a result computed before a restart is reused
+/// rather than recomputed. Within a Tokio runtime, the file is written on the blocking thread pool.
+/// Submitted results are pruned once they are older than the store's TTL, as their events are no
+/// longer expected to be redelivered, and results that were never submitted once they are older than
+/// the pending TTL.
+#[derive(Debug)]
+pub struct IdempotencyStore {
+    writer: Option<Arc<Writer>>,
+    confirmed_ttl: Duration,
+    pending_ttl: Duration,
+    records: Mutex<BTreeMap<String, CallRecord>>,
+    /// Incremented for every snapshot of `records` handed to the writer
+    generation: AtomicU64,
+}
+
+/// Writes snapshots of the records to disk, skipping any older than the last one written
+#[derive(Debug)]
+struct Writer {
+    path: PathBuf,
+    written: Mutex<u64>,
+}
+
+impl Writer {
+    fn write(&self, generation: u64, bytes: &[u8]) {
+        let mut written = self
+            .written
+            .lock()
+            .unwrap_or_else(gadget_std::sync::PoisonError::into_inner);
+        if *written >= generation {
+            return;
+        }
+
+        if let Err(e) = store_bytes(&self.path, bytes) {
+            gadget_logging::error!(
+                "Failed to persist job results to {}: {e}",
+                self.path.display()
+            );
+            return;
+        }
+        *written = generation;
+    }
+}
+
+impl IdempotencyStore {
+    /// Open the store named `name`, loading any records already stored under `data_dir`.
+    ///
+    /// Without a `data_dir`, records are only kept in memory.
+    #[must_use]
+    pub fn new(data_dir: Option<&Path>, name: &str) -> Self {
+        let path = data_dir.map(|dir| dir.join(IDEMPOTENCY_DIR_NAME).join(format!("{name}.json")));
+        let records = path.as_deref().map(load_records).unwrap_or_default();
+
+        let store = Self {
+            writer: path.map(|path| {
+                Arc::new(Writer {
+                    path,
+                    written: Mutex::new(0),
+                })
+            }),
+            confirmed_ttl: DEFAULT_CONFIRMED_TTL,
+            pending_ttl: DEFAULT_PENDING_TTL,
+            records: Mutex::new(records),
+            generation: AtomicU64::new(0),
+        };
+        let _ = store.prune();
+        store
+    }
+
+    /// Keep submitted results for `ttl` after they are submitted, rather than [`DEFAULT_CONFIRMED_TTL`]
+    #[must_use]
+    pub fn with_confirmed_ttl(mut self, ttl: Duration) -> Self {
+        self.confirmed_ttl = ttl;
+        let _ = self.prune();
+        self
+    }
+
+    /// Keep results that were never submitted for `ttl` after they are computed, rather than
+    /// [`DEFAULT_PENDING_TTL`]
+    #[must_use]
+    pub fn with_pending_ttl(mut self, ttl: Duration) -> Self {
+        self.pending_ttl = ttl;
+        let _ = self.prune();
+        self
+    }
+
+    /// Returns the record for the given call, if its result was computed.
+    pub fn get(&self, key: &CallKey) -> Option<CallRecord> {
+        self.lock().get(&key.to_string()).cloned()
+    }
+
+    /// Returns the computed result of the given call, if any.
+    ///
+    /// A stored result that can't be decoded as `T` is logged and ignored.
+    pub fn result<T: DeserializeOwned>(&self, key: &CallKey) -> Option<T> {
+        let record = self.get(key)?;
+        match serde_json::from_value(record.result) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                gadget_logging::warn!(
+                    "Ignoring stored result for {key} that failed to decode: {e}"
+                );
+                None
+            }
+        }
+    }
+
+    /// Whether the result of the given call has been submitted
+    pub fn is_confirmed(&self, key: &CallKey) -> bool {
+        self.get(key)
+            .is_some_and(|record| record.status == SubmissionStatus::Confirmed)
+    }
+
+    /// Record the computed result of a call, as [`SubmissionStatus::Pending`].
+    ///
+    /// A call that already has a result keeps it, along with its status.
+    pub fn record_result<T: Serialize>(&self, key: &CallKey, result: &T) {
+        let result = match serde_json::to_value(result) {
+            Ok(result) => result,
+            Err(e) => {
+                gadget_logging::error!("Failed to encode the result for {key}: {e}");
+                return;
+            }
+        };
+        let recorded_at = now();
+
+        let mut records = self.lock();
+        if records.contains_key(&key.to_string()) {
+            return;
+        }
+        let _ = self.prune_expired(&mut records, recorded_at);
+        let _ = records.insert(
+            key.to_string(),
+            CallRecord {
+                result,
+                status: SubmissionStatus::Pending,
+                recorded_at,
+                confirmed_at: None,
+            },
+        );
+        self.persist(&records);
+    }
+
+    /// Mark the result of the given call as submitted.
+    ///
+    /// Does nothing if the call has no recorded result. Expired results are pruned at the same time.
+    pub fn mark_confirmed(&self, key: &CallKey) {
+        let now = now();
+        let mut records = self.lock();
+        let Some(record) = records.get_mut(&key.to_string()) else {
+            return;
+        };
+        record.status = SubmissionStatus::Confirmed;
+        record.confirmed_at = Some(now);
+        let _ = self.prune_expired(&mut records, now);
+        self.persist(&records);
+    }
+
+    /// Forget expired results, returning how many were removed.
+    ///
+    /// Submitted results expire once they were submitted longer ago than the store's TTL, and pending
+    /// results once they were computed longer ago than its pending TTL.
+    pub fn prune(&self) -> usize {
+        let mut records = self.lock();
+        let pruned = self.prune_expired(&mut records, now());
+        if pruned > 0 {
+            self.persist(&records);
+        }
+        pruned
+    }
+
+    fn prune_expired(&self, records: &mut BTreeMap<String, CallRecord>, now: u64) -> usize {
+        let before = records.len();
+        let confirmed_ttl = self.confirmed_ttl.as_secs();
+        let pending_ttl = self.pending_ttl.as_secs();
+        records.retain(|_, record| match record.status {
+            SubmissionStatus::Pending => now.saturating_sub(record.recorded_at) < pending_ttl,
+            SubmissionStatus::Confirmed => {
+                now.saturating_sub(record.confirmed_at.unwrap_or(record.recorded_at))
+                    < confirmed_ttl
+            }
+        });
+        before - records.len()
+    }
+
+    /// Forget the given call, returning its record if it existed.
+    pub fn remove(&self, key: &CallKey) -> Option<CallRecord> {
+        let mut records = self.lock();
+        let record = records.remove(&key.to_string())?;
+        self.persist(&records);
+        Some(record)
+    }
+
+    /// The number of recorded calls
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> gadget_std::sync::MutexGuard<'_, BTreeMap<String, CallRecord>> {
+        self.records
+            .lock()
+            .unwrap_or_else(gadget_std::sync::PoisonError::into_inner)
+    }
+
+    /// Write a snapshot of `records` to disk, off the async runtime if there is one
+    ///
+    /// Must be called with the records locked, so that snapshots are numbered in the order they were taken.
+    fn persist(&self, records: &BTreeMap<String, CallRecord>) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+
+        let bytes = match serde_json::to_vec(records) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                gadget_logging::error!("Failed to encode job results: {e}");
+                return;
+            }
+        };
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let writer = writer.clone();
+                drop(runtime.spawn_blocking(move || writer.write(generation, &bytes)));
+            }
+            Err(_) => writer.write(generation, &bytes),
+        }
+    }
+}
+
+fn load_records(path: &Path) -> BTreeMap<String, CallRecord> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == gadget_std::io::ErrorKind::NotFound => return BTreeMap::new(),
+        Err(e) => {
+            gadget_logging::error!("Failed to read job results at {}: {e}", path.display());
+            return BTreeMap::new();
+        }
+    };
+
+    match serde_json::from_slice(&bytes) {
+        Ok(records) => records,
+        Err(e) => {
+            gadget_logging::error!("Failed to parse job results at {}: {e}", path.display());
+            BTreeMap::new()
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = CallKey::tangle(1, 0, 42);
+
+        let store = IdempotencyStore::new(Some(dir.path()), "job");
+        assert_eq!(store.result::<u64>(&key), None);
+        store.record_result(&key, &7u64);
+
+        let store = IdempotencyStore::new(Some(dir.path()), "job");
+        assert_eq!(store.result::<u64>(&key), Some(7));
+        assert!(!store.is_confirmed(&key));
+
+        store.mark_confirmed(&key);
+        let store = IdempotencyStore::new(Some(dir.path()), "job");
+        assert!(store.is_confirmed(&key));
+    }
+
+    #[test]
+    fn first_result_is_kept() {
+        let store = IdempotencyStore::new(None, "job");
+        let key = CallKey::evm(1, [0xab; 32], 3);
+
+        store.record_result(&key, &"first");
+        store.mark_confirmed(&key);
+        store.record_result(&key, &"second");
+
+        assert_eq!(store.result::<String>(&key).as_deref(), Some("first"));
+        assert!(store.is_confirmed(&key));
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn expired_results_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let submitted = CallKey::tangle(1, 0, 1);
+        let pending = CallKey::tangle(1, 0, 2);
+
+        let store = IdempotencyStore::new(Some(dir.path()), "job");
+        store.record_result(&submitted, &1u64);
+        store.record_result(&pending, &2u64);
+        store.mark_confirmed(&submitted);
+        assert_eq!(store.prune(), 0);
+
+        // Reopening with no TTL prunes the submitted result, but keeps the pending one
+        let store =
+            IdempotencyStore::new(Some(dir.path()), "job").with_confirmed_ttl(Duration::ZERO);
+        assert!(store.get(&submitted).is_none());
+        assert_eq!(store.result::<u64>(&pending), Some(2));
+
+        let store = IdempotencyStore::new(Some(dir.path()), "job");
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn stale_pending_results_are_pruned() {
+        let store = IdempotencyStore::new(None, "job");
+        let key = CallKey::tangle(1, 0, 1);
+        store.record_result(&key, &1u64);
+
+        let store = store.with_pending_ttl(Duration::ZERO);
+        assert!(store.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn results_are_persisted_off_the_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IdempotencyStore::new(Some(dir.path()), "job");
+        for call_id in 0..10 {
+            store.record_result(&CallKey::tangle(1, 0, call_id), &call_id);
+        }
+
+        // The writes may run in any order, but an older snapshot must never replace a newer one
+        let path = dir.path().join(IDEMPOTENCY_DIR_NAME).join("job.json");
+        for _ in 0..100 {
+            if load_records(&path).len() == 10 {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("results were not persisted");
+    }
+
+    #[test]
+    fn keys_are_distinct() {
+        assert_eq!(CallKey::tangle(1, 2, 3).to_string(), "tangle/1/2/3");
+        assert_eq!(
+            CallKey::evm(1, [0; 32], 3).to_string(),
+            format!("evm/1/0x{}/3", "0".repeat(64))
+        );
+        assert_ne!(
+            CallKey::tangle(1, 2, 3).to_string(),
+            CallKey::tangle(12, 3, 3).to_string()
+        );
+    }
+}
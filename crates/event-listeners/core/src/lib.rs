@@ -1,5 +1,11 @@
+pub mod dead_letter;
 pub mod exponential_backoff;
+pub mod idempotency;
 pub mod marker;
+pub mod middleware;
+pub mod retry;
+mod storage;
+pub mod updates;
 
 pub mod error;
 pub use error::Error;
@@ -8,8 +14,11 @@ pub mod executor;
 pub mod testing;
 
 use async_trait::async_trait;
+use dead_letter::DeadLetterReplayers;
 use exponential_backoff::ExponentialBackoff;
 use gadget_std::iter::Take;
+use gadget_std::path::PathBuf;
+use middleware::Layers;
 
 /// The [`EventListener`] trait defines the interface for event listeners.
 #[async_trait]
@@ -28,9 +37,23 @@ pub fn get_exponential_backoff<const N: usize>() -> Take<ExponentialBackoff> {
     ExponentialBackoff::from_millis(2).factor(1000).take(N)
 }
 
+/// Settings the runner passes to every [`InitializableEventHandler`]
+#[derive(Debug, Clone, Default)]
+pub struct HandlerSettings {
+    /// The gadget data directory, where jobs keep state such as their dead letters
+    ///
+    /// Without one, that state is only kept in memory.
+    pub data_dir: Option<PathBuf>,
+    /// Layers that wrap every job, outside the job's own layers
+    pub layers: Layers,
+    /// Where jobs with a dead-letter queue register their replayer
+    pub dead_letters: DeadLetterReplayers,
+}
+
 #[async_trait]
 pub trait InitializableEventHandler {
     async fn init_event_handler(
         &self,
+        settings: &HandlerSettings,
     ) -> Option<tokio::sync::oneshot::Receiver<Result<(), Box<dyn core::error::Error + Send>>>>;
 }
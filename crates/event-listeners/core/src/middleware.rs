@@ -0,0 +1,397 @@
+//! Middleware that wraps each stage of a job call, for cross-cutting concerns like tracing, metrics and
+//! authorization.
+//!
+//! Layers are attached to a single job with [`EventFlowWrapper::with_layer`], or to every job run by a
+//! `BlueprintRunner`, which passes its layers to each job in [`HandlerSettings::layers`]. The runner's
+//! layers run outermost, followed by the job's own layers in the order they were added.
+//!
+//! [`EventFlowWrapper::with_layer`]: crate::executor::EventFlowWrapper::with_layer
+//! [`HandlerSettings::layers`]: crate::HandlerSettings::layers
+
+use crate::error::Error;
+use async_trait::async_trait;
+use gadget_logging::tracing::Instrument;
+use gadget_std::fmt::{self, Display};
+use gadget_std::future::Future;
+use gadget_std::pin::Pin;
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::Arc;
+use gadget_std::time::Instant;
+use gadget_std::vec::Vec;
+
+/// A stage of a job call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    PreProcess,
+    Process,
+    PostProcess,
+}
+
+impl Stage {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::PreProcess => "pre_process",
+            Stage::Process => "process",
+            Stage::PostProcess => "post_process",
+        }
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Describes the job call passing through a [`JobLayer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobCall {
+    /// The ID of the job, if known
+    pub job_id: Option<u64>,
+    /// The name of the job, if known
+    pub job_name: Option<&'static str>,
+    /// Numbers the job's calls in the order they were received, starting at `0`
+    pub sequence: u64,
+    /// The stage being run
+    pub stage: Stage,
+}
+
+impl JobCall {
+    #[must_use]
+    pub(crate) fn at(self, stage: Stage) -> Self {
+        Self { stage, ..self }
+    }
+}
+
+/// An error returned through a [`JobLayer`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LayerError {
+    /// The stage itself failed. Returning this from a layer passes the stage's original error on.
+    #[error("{0}")]
+    Stage(String),
+    /// A layer rejected the job call, which is skipped without counting as a failure
+    #[error("Job call rejected: {0}")]
+    Rejected(String),
+    /// A layer failed the job call
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// The rest of the middleware stack, ending with the stage itself
+pub struct Next<'a> {
+    inner: Pin<Box<dyn Future<Output = Result<(), LayerError>> + Send + 'a>>,
+}
+
+impl<'a> Next<'a> {
+    fn new(inner: impl Future<Output = Result<(), LayerError>> + Send + 'a) -> Self {
+        Self {
+            inner: Box::pin(inner),
+        }
+    }
+
+    /// Run the remaining layers and the stage
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LayerError::Stage`] if the stage fails, or whatever error an inner layer returns.
+    pub async fn run(self) -> Result<(), LayerError> {
+        self.inner.await
+    }
+}
+
+/// Middleware that wraps every stage of a job call
+///
+/// A layer may act before and after calling [`Next::run`], skip the call by returning without running
+/// `next`, or reject it with [`LayerError::Rejected`].
+#[async_trait]
+pub trait JobLayer: Send + Sync + 'static {
+    async fn call(&self, call: &JobCall, next: Next<'_>) -> Result<(), LayerError>;
+}
+
+/// An ordered stack of [`JobLayer`]s, outermost first
+#[derive(Clone, Default)]
+pub struct Layers {
+    stack: Vec<Arc<dyn JobLayer>>,
+}
+
+impl fmt::Debug for Layers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layers")
+            .field("len", &self.stack.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FromIterator<Arc<dyn JobLayer>> for Layers {
+    fn from_iter<I: IntoIterator<Item = Arc<dyn JobLayer>>>(iter: I) -> Self {
+        Self {
+            stack: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Layers {
+    /// Add an innermost layer
+    pub fn push(&mut self, layer: Arc<dyn JobLayer>) {
+        self.stack.push(layer);
+    }
+
+    /// Add all of `other`'s layers inside the existing ones, keeping their order
+    pub fn append(&mut self, other: &Layers) {
+        self.stack.extend(other.stack.iter().cloned());
+    }
+
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Run `stage` through the layers
+    ///
+    /// Returns `Ok(None)` if a layer skipped or rejected the call.
+    pub(crate) async fn wrap<T, E>(
+        &self,
+        call: &JobCall,
+        stage: impl Future<Output = Result<T, Error<E>>> + Send,
+    ) -> Result<Option<T>, Error<E>>
+    where
+        T: Send,
+        E: core::error::Error + Send + Sync + 'static,
+    {
+        if self.stack.is_empty() {
+            return stage.await.map(Some);
+        }
+
+        let mut output = None;
+        let mut error = None;
+        let result = run_layers(
+            &self.stack,
+            call,
+            Next::new(async {
+                match stage.await {
+                    Ok(value) => {
+                        output = Some(value);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        let message = e.to_string();
+                        error = Some(e);
+                        Err(LayerError::Stage(message))
+                    }
+                }
+            }),
+        )
+        .await;
+
+        match result {
+            // Either the stage ran, or a layer chose to skip it
+            Ok(()) => Ok(output),
+            Err(LayerError::Stage(message)) => Err(error.unwrap_or(Error::Other(message))),
+            Err(LayerError::Rejected(reason)) => {
+                gadget_logging::warn!(
+                    "Job call {} rejected during {}: {reason}",
+                    call.sequence,
+                    call.stage
+                );
+                Ok(None)
+            }
+            Err(LayerError::Failed(message)) => Err(Error::Other(message)),
+        }
+    }
+}
+
+fn run_layers<'a>(
+    layers: &'a [Arc<dyn JobLayer>],
+    call: &'a JobCall,
+    stage: Next<'a>,
+) -> Pin<Box<dyn Future<Output = Result<(), LayerError>> + Send + 'a>> {
+    match layers.split_first() {
+        Some((layer, rest)) => Box::pin(async move {
+            layer
+                .call(call, Next::new(run_layers(rest, call, stage)))
+                .await
+        }),
+        None => stage.inner,
+    }
+}
+
+/// Runs each stage of a job call inside a `job` tracing span, logging how long it took
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceLayer;
+
+#[async_trait]
+impl JobLayer for TraceLayer {
+    async fn call(&self, call: &JobCall, next: Next<'_>) -> Result<(), LayerError> {
+        let span = gadget_logging::tracing::info_span!(
+            target: "gadget",
+            "job",
+            job_id = call.job_id,
+            job_name = call.job_name,
+            sequence = call.sequence,
+            stage = call.stage.as_str(),
+        );
+
+        async move {
+            let start = Instant::now();
+            let result = next.run().await;
+            match &result {
+                Ok(()) => gadget_logging::debug!("Completed in {:?}", start.elapsed()),
+                Err(e) => gadget_logging::debug!("Failed after {:?}: {e}", start.elapsed()),
+            }
+            result
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+/// Records the outcome and duration of each stage of a job call
+///
+/// Uses the [`metrics`] facade, so a recorder (for example, a Prometheus exporter) must be installed
+/// for anything to be collected. Every metric is labelled with the job name and stage.
+///
+/// * `gadget_job_stage_total`: Counter of stage runs, also labelled with the outcome (`ok`, `skipped`,
+///   `rejected` or `failed`)
+/// * `gadget_job_stage_duration_seconds`: Histogram of stage durations
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsLayer;
+
+#[async_trait]
+impl JobLayer for MetricsLayer {
+    async fn call(&self, call: &JobCall, next: Next<'_>) -> Result<(), LayerError> {
+        let job = call.job_name.unwrap_or("unknown");
+        let stage = call.stage.as_str();
+
+        let start = Instant::now();
+        let result = next.run().await;
+        let elapsed = start.elapsed();
+
+        let outcome = match &result {
+            Ok(()) => "ok",
+            Err(LayerError::Rejected(_)) => "rejected",
+            Err(_) => "failed",
+        };
+        metrics::counter!(
+            "gadget_job_stage_total",
+            "job" => job,
+            "stage" => stage,
+            "outcome" => outcome
+        )
+        .increment(1);
+        metrics::histogram!(
+            "gadget_job_stage_duration_seconds",
+            "job" => job,
+            "stage" => stage
+        )
+        .record(elapsed.as_secs_f64());
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gadget_std::convert::Infallible;
+    use gadget_std::sync::Mutex;
+
+    const CALL: JobCall = JobCall {
+        job_id: Some(0),
+        job_name: Some("test_job"),
+        sequence: 0,
+        stage: Stage::Process,
+    };
+
+    /// Records when it is entered and exited
+    struct RecordingLayer(&'static str, Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl JobLayer for RecordingLayer {
+        async fn call(&self, _call: &JobCall, next: Next<'_>) -> Result<(), LayerError> {
+            self.1.lock().unwrap().push(format!("enter {}", self.0));
+            let result = next.run().await;
+            self.1.lock().unwrap().push(format!("exit {}", self.0));
+            result
+        }
+    }
+
+    struct RejectLayer;
+
+    #[async_trait]
+    impl JobLayer for RejectLayer {
+        async fn call(&self, _call: &JobCall, _next: Next<'_>) -> Result<(), LayerError> {
+            Err(LayerError::Rejected(String::from("unauthorized caller")))
+        }
+    }
+
+    #[tokio::test]
+    async fn layers_run_outermost_first() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        // As when a runner's layers are added before the job's own
+        let runner_layers = [Arc::new(RecordingLayer("outer", log.clone())) as Arc<dyn JobLayer>]
+            .into_iter()
+            .collect::<Layers>();
+        let mut layers = Layers::default();
+        layers.append(&runner_layers);
+        layers.push(Arc::new(RecordingLayer("inner", log.clone())));
+
+        let output = layers
+            .wrap(&CALL, async {
+                log.lock().unwrap().push(String::from("stage"));
+                Ok::<_, Error<Infallible>>(7)
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output, Some(7));
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                "enter outer",
+                "enter inner",
+                "stage",
+                "exit inner",
+                "exit outer"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stage_errors_pass_through() {
+        let mut layers = Layers::default();
+        layers.push(Arc::new(TraceLayer));
+        layers.push(Arc::new(MetricsLayer));
+
+        let res = layers
+            .wrap(&CALL, async {
+                Err::<(), _>(Error::<Infallible>::BadArgumentDecoding(String::from(
+                    "bad args",
+                )))
+            })
+            .await;
+
+        assert!(matches!(res, Err(Error::BadArgumentDecoding(_))));
+    }
+
+    #[tokio::test]
+    async fn rejected_calls_are_skipped() {
+        let ran = Arc::new(Mutex::new(false));
+        let mut layers = Layers::default();
+        layers.push(Arc::new(RejectLayer));
+
+        let output = layers
+            .wrap(&CALL, async {
+                *ran.lock().unwrap() = true;
+                Ok::<_, Error<Infallible>>(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output, None);
+        assert!(!*ran.lock().unwrap());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::exponential_backoff::ExponentialBackoff;
+use gadget_std::iter::Take;
+use gadget_std::time::Duration;
+
+/// How many times, and how quickly, a failed job call is retried
+///
+/// Each retry re-runs both the job and its post-processor on a copy of the pre-processed event.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    backoff: ExponentialBackoff,
+}
+
+impl RetryPolicy {
+    /// The longest a single retry is delayed by the default back-off
+    pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+    /// Retry a failed job call up to `max_retries` times
+    ///
+    /// Retries are delayed by 2, 4, 8, ... seconds, up to [`RetryPolicy::DEFAULT_MAX_DELAY`].
+    #[must_use]
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            backoff: ExponentialBackoff::from_millis(2)
+                .factor(1000)
+                .max_delay(Self::DEFAULT_MAX_DELAY),
+        }
+    }
+
+    /// Use a custom back-off strategy to delay retries
+    #[must_use]
+    pub fn backoff(mut self, backoff: ExponentialBackoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// The maximum number of retries, not counting the initial attempt
+    #[must_use]
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// The delay before each retry, in order
+    #[must_use]
+    pub fn delays(&self) -> Take<ExponentialBackoff> {
+        self.backoff.clone().take(self.max_retries as usize)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+#[test]
+fn delays_are_bounded_by_max_retries() {
+    let policy = RetryPolicy::new(3).backoff(ExponentialBackoff::from_millis(10));
+
+    assert_eq!(
+        policy.delays().collect::<Vec<_>>(),
+        vec![
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+            Duration::from_millis(1000)
+        ]
+    );
+    assert_eq!(RetryPolicy::new(0).delays().count(), 0);
+}
+
+#[test]
+fn default_backoff_is_capped() {
+    let policy = RetryPolicy::new(10);
+
+    let delays = policy.delays().collect::<Vec<_>>();
+    assert_eq!(delays[0], Duration::from_secs(2));
+    assert_eq!(delays.last(), Some(&RetryPolicy::DEFAULT_MAX_DELAY));
+}
@@ -0,0 +1,28 @@
+//! Helpers for the stores persisted to the gadget data directory.
+
+use gadget_std::fs;
+use gadget_std::io::{self, Write};
+use gadget_std::path::Path;
+use serde::Serialize;
+
+/// Write `value` to `path` as JSON, replacing the file in a single step, see [`store_bytes`]
+pub(crate) fn store_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+    store_bytes(path, &serde_json::to_vec(value)?)
+}
+
+/// Write `bytes` to `path`, replacing the file in a single step
+///
+/// The bytes are written and synced beside the old file first, then renamed over it, so a crash never
+/// leaves a truncated file behind.
+pub(crate) fn store_bytes(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(tmp, path)
+}
@@ -0,0 +1,223 @@
+//! Intermediate results and heartbeats emitted by a job while it runs.
+//!
+//! A job that opts into streaming can get an [`UpdateSender`] with [`UpdateSender::current`]. Its
+//! updates are published by an [`UpdatePublisher`] while the job runs, and all of them are published
+//! before the job's final result is submitted.
+
+use crate::idempotency::CallKey;
+use async_trait::async_trait;
+use gadget_std::future::Future;
+use gadget_std::string::String;
+use gadget_std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+tokio::task_local! {
+    static UPDATES: Option<UpdateSender>;
+}
+
+/// An update emitted by a job before its final result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobUpdate {
+    /// The job is still running
+    Heartbeat,
+    /// A description of the job's progress
+    Progress(String),
+    /// An intermediate result, as JSON
+    Partial(serde_json::Value),
+}
+
+/// An error returned by an [`UpdatePublisher`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Failed to publish job update: {0}")]
+pub struct PublishError(pub String);
+
+/// Publishes the updates of a job call, for example on-chain or to an external service
+#[async_trait]
+pub trait UpdatePublisher: Send + Sync + 'static {
+    async fn publish(&self, call: &CallKey, update: &JobUpdate) -> Result<(), PublishError>;
+}
+
+/// Publishes updates to the log
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogPublisher;
+
+#[async_trait]
+impl UpdatePublisher for LogPublisher {
+    async fn publish(&self, call: &CallKey, update: &JobUpdate) -> Result<(), PublishError> {
+        match update {
+            JobUpdate::Heartbeat => gadget_logging::info!("Call {call} is still running"),
+            JobUpdate::Progress(message) => gadget_logging::info!("Call {call}: {message}"),
+            JobUpdate::Partial(result) => {
+                gadget_logging::info!("Call {call} produced a partial result: {result}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Emits updates for the job call it was created for
+#[derive(Debug, Clone)]
+pub struct UpdateSender {
+    tx: mpsc::UnboundedSender<JobUpdate>,
+}
+
+impl UpdateSender {
+    /// The sender of the job call running on this task, if it streams updates
+    ///
+    /// Clone the sender to emit updates from other tasks.
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        UPDATES.try_with(Clone::clone).ok().flatten()
+    }
+
+    pub fn heartbeat(&self) {
+        self.send(JobUpdate::Heartbeat);
+    }
+
+    pub fn progress(&self, message: impl Into<String>) {
+        self.send(JobUpdate::Progress(message.into()));
+    }
+
+    /// Emit an intermediate result
+    ///
+    /// A result that can't be encoded is logged and dropped.
+    pub fn partial<T: Serialize>(&self, result: &T) {
+        match serde_json::to_value(result) {
+            Ok(result) => self.send(JobUpdate::Partial(result)),
+            Err(e) => gadget_logging::error!("Failed to encode a partial result: {e}"),
+        }
+    }
+
+    /// Emit an update, which is dropped if the job call already finished
+    pub fn send(&self, update: JobUpdate) {
+        if self.tx.send(update).is_err() {
+            gadget_logging::warn!("Dropping an update emitted after the job call finished");
+        }
+    }
+}
+
+/// Publishes the updates of a single job call, in the order they were emitted
+#[derive(Debug)]
+pub struct UpdateStream {
+    sender: UpdateSender,
+    close: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+impl UpdateStream {
+    /// Start publishing the updates of the given call with `publisher`
+    #[must_use]
+    pub fn start(call: CallKey, publisher: Arc<dyn UpdatePublisher>) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (close, mut closed) = oneshot::channel();
+
+        let task = tokio::task::spawn(async move {
+            loop {
+                tokio::select! {
+                    biased;
+                    update = rx.recv() => match update {
+                        Some(update) => publish(&*publisher, &call, &update).await,
+                        None => break,
+                    },
+                    _ = &mut closed => {
+                        // Publish whatever was emitted before the call finished
+                        rx.close();
+                        while let Some(update) = rx.recv().await {
+                            publish(&*publisher, &call, &update).await;
+                        }
+                        break;
+                    }
+                }
+            }
+        });
+
+        Self {
+            sender: UpdateSender { tx },
+            close,
+            task,
+        }
+    }
+
+    #[must_use]
+    pub fn sender(&self) -> UpdateSender {
+        self.sender.clone()
+    }
+
+    /// Wait until every update emitted so far is published
+    ///
+    /// Updates emitted afterwards, by clones of the sender, are dropped.
+    pub async fn finish(self) {
+        let Self {
+            sender,
+            close,
+            task,
+        } = self;
+        drop(sender);
+        let _ = close.send(());
+        if let Err(e) = task.await {
+            gadget_logging::error!("Job update publisher failed: {e}");
+        }
+    }
+}
+
+async fn publish(publisher: &dyn UpdatePublisher, call: &CallKey, update: &JobUpdate) {
+    if let Err(e) = publisher.publish(call, update).await {
+        gadget_logging::warn!("{e}");
+    }
+}
+
+/// Run `future` with `sender` available through [`UpdateSender::current`]
+pub async fn scope<F: Future>(sender: Option<UpdateSender>, future: F) -> F::Output {
+    UPDATES.scope(sender, future).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gadget_std::sync::Mutex;
+    use gadget_std::vec::Vec;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<JobUpdate>>);
+
+    #[async_trait]
+    impl UpdatePublisher for Recorder {
+        async fn publish(&self, _call: &CallKey, update: &JobUpdate) -> Result<(), PublishError> {
+            self.0.lock().unwrap().push(update.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn updates_are_published_in_order() {
+        let recorder = Arc::new(Recorder::default());
+        let stream = UpdateStream::start(CallKey::tangle(0, 0, 1), recorder.clone());
+
+        scope(Some(stream.sender()), async {
+            let updates = UpdateSender::current().expect("in scope");
+            updates.heartbeat();
+            updates.progress("halfway");
+            updates.partial(&42u64);
+        })
+        .await;
+        stream.finish().await;
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                JobUpdate::Heartbeat,
+                JobUpdate::Progress(String::from("halfway")),
+                JobUpdate::Partial(serde_json::json!(42)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_sender_outside_of_scope() {
+        assert!(UpdateSender::current().is_none());
+        scope(None, async { assert!(UpdateSender::current().is_none()) }).await;
+    }
+}
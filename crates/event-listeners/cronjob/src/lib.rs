@@ -3,10 +3,11 @@ pub mod error;
 use error::Error;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use gadget_event_listeners_core::{Error as CoreError, EventListener};
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::sync::Mutex;
 use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
 
@@ -26,29 +27,30 @@ pub trait CronJobDefinition: Send + Sync + 'static {
     fn cron(&self) -> impl Into<String>;
 }
 
-#[async_trait]
-impl<Ctx: CronJobDefinition> EventListener<(), Ctx> for CronJob<Ctx> {
-    type ProcessorError = Error;
+impl<Ctx: Send + Sync + 'static> CronJob<Ctx> {
+    /// A listener that fires every `interval`, rather than on a cron schedule
+    ///
+    /// # Errors
+    ///
+    /// The scheduler could not be started
+    pub async fn every(interval: Duration) -> Result<Self, CoreError<Error>> {
+        Self::schedule(|tx| {
+            Job::new_repeated(interval, move |_uuid, _l| send_event(&tx)).map_err(err_map)
+        })
+        .await
+    }
 
-    async fn new(context: &Ctx) -> Result<Self, CoreError<Self::ProcessorError>>
-    where
-        Self: Sized,
-    {
+    /// Wait for the next time the listener fires
+    pub async fn next_tick(&mut self) -> Option<()> {
+        self.1.lock().await.recv().await
+    }
+
+    async fn schedule(
+        job: impl FnOnce(UnboundedSender<()>) -> Result<Job, CoreError<Error>>,
+    ) -> Result<Self, CoreError<Error>> {
         let sched = JobScheduler::new().await.map_err(err_map)?;
-        let cron_syntax = context.cron().into();
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        // Add basic cron job
-        sched
-            .add(
-                Job::new(cron_syntax, move |_uuid, _l| {
-                    if let Err(err) = tx.send(()) {
-                        gadget_logging::error!("Failed to send event to cronjob worker: {err}");
-                    }
-                })
-                .map_err(err_map)?,
-            )
-            .await
-            .map_err(err_map)?;
+        sched.add(job(tx)?).await.map_err(err_map)?;
 
         let task = async move {
             if let Err(err) = sched.start().await {
@@ -60,9 +62,31 @@ impl<Ctx: CronJobDefinition> EventListener<(), Ctx> for CronJob<Ctx> {
 
         Ok(Self(PhantomData, Arc::new(Mutex::new(rx))))
     }
+}
+
+fn send_event(tx: &UnboundedSender<()>) {
+    if let Err(err) = tx.send(()) {
+        gadget_logging::error!("Failed to send event to cronjob worker: {err}");
+    }
+}
+
+#[async_trait]
+impl<Ctx: CronJobDefinition> EventListener<(), Ctx> for CronJob<Ctx> {
+    type ProcessorError = Error;
+
+    async fn new(context: &Ctx) -> Result<Self, CoreError<Self::ProcessorError>>
+    where
+        Self: Sized,
+    {
+        let cron_syntax = context.cron().into();
+        Self::schedule(|tx| {
+            Job::new(cron_syntax, move |_uuid, _l| send_event(&tx)).map_err(err_map)
+        })
+        .await
+    }
 
     async fn next_event(&mut self) -> Option<()> {
-        self.1.lock().await.recv().await
+        self.next_tick().await
     }
 }
 
@@ -90,4 +114,12 @@ mod tests {
         let next_event = cronjob.next_event().await;
         assert!(next_event.is_some());
     }
+
+    #[tokio::test]
+    async fn interval_event_listener() {
+        let mut cronjob = CronJob::<()>::every(Duration::from_millis(100))
+            .await
+            .unwrap();
+        assert!(cronjob.next_tick().await.is_some());
+    }
 }
@@ -9,8 +9,12 @@ use alloy_provider::RootProvider;
 use alloy_rpc_types::{BlockNumberOrTag, Filter};
 use alloy_sol_types::SolEvent;
 pub use alloy_transport::BoxTransport;
+use gadget_event_listeners_core::idempotency::CallKey;
 use gadget_event_listeners_core::{Error as CoreError, EventListener};
+use gadget_std::boxed::Box;
 use gadget_std::collections::VecDeque;
+use gadget_std::future::Future;
+use gadget_std::pin::Pin;
 use gadget_std::time::Duration;
 use gadget_stores::local_database::LocalDatabase;
 use uuid::Uuid;
@@ -18,6 +22,46 @@ use uuid::Uuid;
 pub type AlloyRootProvider = RootProvider<BoxTransport>;
 pub type AlloyContractInstance = ContractInstance<BoxTransport, AlloyRootProvider, Ethereum>;
 
+/// The [`CallKey`] identifying the job call triggered by `log`, for use with an
+/// [`IdempotencyStore`](gadget_event_listeners_core::idempotency::IdempotencyStore)
+///
+/// Returns `None` for pending logs, which have no transaction hash or log index yet.
+#[must_use]
+pub fn call_key(chain_id: u64, log: &alloy_rpc_types::Log) -> Option<CallKey> {
+    Some(CallKey::evm(
+        chain_id,
+        log.transaction_hash?.0,
+        log.log_index?,
+    ))
+}
+
+/// Wraps an EVM job's `pre_processor` so that its output is paired with the [`call_key`] of the
+/// log that triggered it
+pub fn keyed_pre_processor<E, T, Err, F, Fut>(
+    chain_id: u64,
+    pre_processor: F,
+) -> impl Fn(
+    (E, alloy_rpc_types::Log),
+) -> Pin<Box<dyn Future<Output = Result<Option<(Option<CallKey>, T)>, Err>> + Send>>
+       + Send
+       + 'static
+where
+    F: Fn((E, alloy_rpc_types::Log)) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Option<T>, Err>> + Send + 'static,
+    T: Send + 'static,
+    Err: Send + 'static,
+{
+    move |(event, log)| {
+        let call_key = call_key(chain_id, &log);
+        let inputs = pre_processor((event, log));
+        Box::pin(async move {
+            inputs
+                .await
+                .map(|inputs| inputs.map(|inputs| (call_key, inputs)))
+        })
+    }
+}
+
 pub struct EvmContractEventListener<E: SolEvent + Send + 'static> {
     instance: AlloyContractInstance,
     chain_id: u64,
@@ -26,6 +70,14 @@ pub struct EvmContractEventListener<E: SolEvent + Send + 'static> {
     enqueued_events: VecDeque<(E, alloy_rpc_types::Log)>,
 }
 
+impl<E: SolEvent + Send + 'static> EvmContractEventListener<E> {
+    /// The ID of the chain the contract is deployed on
+    #[must_use]
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+}
+
 #[async_trait::async_trait]
 impl<E: SolEvent + Send + Sync + 'static>
     EventListener<(E, alloy_rpc_types::Log), AlloyContractInstance>
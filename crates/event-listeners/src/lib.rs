@@ -8,3 +8,6 @@ pub use gadget_event_listeners_tangle as tangle;
 
 #[cfg(feature = "cronjob")]
 pub use gadget_event_listeners_cronjob as cronjob;
+
+#[cfg(feature = "webhook")]
+pub use gadget_event_listeners_webhook as webhook;
@@ -2,12 +2,16 @@ use crate::error::TangleEventListenerError;
 use async_trait::async_trait;
 use gadget_clients::tangle::client::{OnlineClient, TangleConfig};
 use gadget_crypto::tangle_pair_signer::TanglePairSigner;
+use gadget_event_listeners_core::dead_letter::DeadLetterCodec;
 use gadget_event_listeners_core::marker::IsTangle;
 use gadget_event_listeners_core::{Error, EventListener};
 use gadget_std::collections::VecDeque;
 use gadget_std::sync::atomic::{AtomicBool, Ordering};
 use gadget_std::sync::Arc;
+use gadget_std::vec::Vec;
+use serde::{Deserialize, Serialize};
 use subxt::backend::StreamOfResults;
+use subxt::ext::codec::{Decode, Encode};
 use subxt_core::events::{EventDetails, StaticEvent};
 use tangle_subxt::subxt;
 use tangle_subxt::subxt_core;
@@ -71,6 +75,74 @@ impl<C, E: EventMatcher> TangleEvent<C, E> {
     }
 }
 
+/// The part of a [`TangleEvent`] kept in a dead letter: everything but its runtime handles
+#[derive(Serialize, Deserialize)]
+struct StoredTangleEvent {
+    /// The SCALE-encoded event
+    evt: Vec<u8>,
+    call_id: Option<CallId>,
+    /// The SCALE-encoded job arguments
+    args: Vec<u8>,
+    block_number: BlockNumber,
+    job_id: Job,
+    service_id: ServiceId,
+}
+
+/// Stores [`TangleEvent`]s in dead letters
+///
+/// The event and its job arguments are stored SCALE-encoded. The client, signer and context of a
+/// replayed event are taken from the listener input the codec was created with, and stopping the
+/// listener from a replayed event has no effect.
+#[derive(Clone)]
+pub struct TangleDeadLetterCodec<C> {
+    input: TangleListenerInput<C>,
+}
+
+impl<C> TangleDeadLetterCodec<C> {
+    #[must_use]
+    pub fn new(input: TangleListenerInput<C>) -> Self {
+        Self { input }
+    }
+}
+
+impl<C, E> DeadLetterCodec<TangleEvent<C, E>> for TangleDeadLetterCodec<C>
+where
+    C: ThreadSafeCloneable,
+    E: EventMatcher,
+    E::Output: Encode + Decode,
+{
+    fn encode(&self, event: &TangleEvent<C, E>) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(StoredTangleEvent {
+            evt: event.evt.encode(),
+            call_id: event.call_id,
+            args: event.args.encode(),
+            block_number: event.block_number,
+            job_id: event.job_id,
+            service_id: event.service_id,
+        })
+    }
+
+    fn decode(&self, value: serde_json::Value) -> serde_json::Result<TangleEvent<C, E>> {
+        use serde::de::Error as _;
+
+        let stored = serde_json::from_value::<StoredTangleEvent>(value)?;
+        Ok(TangleEvent {
+            evt: E::Output::decode(&mut stored.evt.as_slice())
+                .map_err(serde_json::Error::custom)?,
+            context: self.input.context.clone(),
+            call_id: stored.call_id,
+            args: job_called::Args::decode(&mut stored.args.as_slice())
+                .map_err(serde_json::Error::custom)?,
+            block_number: stored.block_number,
+            signer: self.input.signer.clone(),
+            client: self.input.client.clone(),
+            job_id: stored.job_id,
+            service_id: stored.service_id,
+            stopper: Arc::new(parking_lot::Mutex::new(None)),
+        })
+    }
+}
+
 pub trait EventMatcher: Send + 'static {
     type Output: Send + 'static;
     fn try_decode(event: EventDetails<TangleConfig>) -> Option<Self::Output>;
@@ -56,6 +56,8 @@ pub async fn services_pre_processor<C: ServicesContext, E: EventMatcher<Output:
 }
 
 /// By default, the tangle post-processor takes in a job result and submits the result on-chain
+///
+/// Results wrapped in [`Outputs`](gadget_blueprint_serde::Outputs) are submitted as one field per element.
 pub async fn services_post_processor<R: serde::Serialize>(
     TangleResult {
         results,
@@ -71,8 +73,8 @@ pub async fn services_post_processor<R: serde::Serialize>(
     let response = api::tx().services().submit_result(
         service_id,
         call_id,
-        vec![gadget_blueprint_serde::to_field(results)
-            .map_err(Into::<TangleEventListenerError>::into)?],
+        gadget_blueprint_serde::to_fields(results)
+            .map_err(Into::<TangleEventListenerError>::into)?,
     );
     let _ = gadget_utils_tangle::tx::send(&client, &signer, &response)
         .await
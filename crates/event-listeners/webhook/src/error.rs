@@ -0,0 +1,21 @@
+use gadget_std::string::String;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to bind the webhook server: {0}")]
+    Bind(String),
+    #[error(
+        "Invalid webhook path `{0}`, it must start with `/` and can't contain route parameters"
+    )]
+    InvalidPath(String),
+    #[error("The webhook HMAC secret is empty")]
+    EmptySecret,
+    #[error("Missing parameter `{0}`")]
+    MissingParam(String),
+    #[error("Failed to decode parameter `{0}`: {1}")]
+    InvalidParam(String, serde_json::Error),
+    #[error("Invalid request body: {0}")]
+    InvalidBody(serde_json::Error),
+}
+
+pub type Result<T> = gadget_std::result::Result<T, Error>;
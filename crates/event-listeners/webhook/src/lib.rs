@@ -0,0 +1,588 @@
+pub mod error;
+
+use error::Error;
+use gadget_std::collections::BTreeMap;
+use gadget_std::net::SocketAddr;
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::{Arc, Mutex};
+use gadget_std::time::{Duration, SystemTime, UNIX_EPOCH};
+use gadget_std::vec::Vec;
+
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use gadget_crypto_core::{KeyEncoding, KeyType};
+use gadget_crypto_ed25519::{Ed25519Signature, Ed25519VerificationKey, Ed25519Zebra};
+use gadget_event_listeners_core::{Error as CoreError, EventListener};
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+/// The header carrying the hex encoded signature of the request, see [`signed_payload`]
+pub const SIGNATURE_HEADER: &str = "x-signature";
+/// The header carrying the hex encoded ed25519 key that signed the request
+pub const PUBLIC_KEY_HEADER: &str = "x-public-key";
+/// The header carrying the time the request was signed at, in seconds since the UNIX epoch
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+
+/// How far the [`TIMESTAMP_HEADER`] may be from the current time by default
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(5 * 60);
+
+/// The number of accepted requests that may wait for the job, further requests are answered with
+/// `503 Service Unavailable` until it catches up
+pub const MAX_QUEUED_REQUESTS: usize = 1024;
+
+/// The number of signatures remembered within the tolerance to reject replays, further requests are
+/// answered with `503 Service Unavailable` until older signatures expire
+pub const MAX_SEEN_SIGNATURES: usize = 65536;
+
+/// The bytes a request's signature covers: its [`TIMESTAMP_HEADER`], a `.`, then its body
+#[must_use]
+pub fn signed_payload(timestamp: u64, body: &[u8]) -> Vec<u8> {
+    let mut payload = timestamp.to_string().into_bytes();
+    payload.push(b'.');
+    payload.extend_from_slice(body);
+    payload
+}
+
+/// Used for executing a job whenever an authenticated HTTP request is received
+///
+/// The listener serves `POST` requests on the address and path of the context's [`WebhookConfig`].
+/// Every request must be authenticated as described by its [`WebhookAuth`], and signed within
+/// [`WebhookConfig::tolerance`] of the current time. Anything else, including a request whose signature
+/// was already accepted, is rejected with `401 Unauthorized`. Accepted requests are answered with `202 Accepted` and become a
+/// [`WebhookRequest`] event, whose JSON body holds the job's params by name. At most
+/// [`MAX_QUEUED_REQUESTS`] events wait for the job at once.
+///
+/// The context must contain the webhook configuration. See: [`WebhookDefinition`]
+pub struct WebhookEventListener {
+    rx: Receiver<WebhookRequest>,
+    local_addr: SocketAddr,
+}
+
+pub trait WebhookDefinition: Send + Sync + 'static {
+    fn webhook(&self) -> WebhookConfig;
+}
+
+/// Where a [`WebhookEventListener`] listens, and how requests are authenticated
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// The address to bind the HTTP server to
+    pub bind_addr: SocketAddr,
+    /// The path requests are accepted on, `/` by default
+    pub path: String,
+    pub auth: WebhookAuth,
+    /// How far a request's [`TIMESTAMP_HEADER`] may be from the current time, [`DEFAULT_TOLERANCE`]
+    /// by default
+    pub tolerance: Duration,
+}
+
+impl WebhookConfig {
+    #[must_use]
+    pub fn new(bind_addr: SocketAddr, auth: WebhookAuth) -> Self {
+        Self {
+            bind_addr,
+            path: String::from("/"),
+            auth,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    /// Accept requests on `path` instead of `/`
+    ///
+    /// The path must start with `/` and can't contain route parameters.
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Accept requests signed up to `tolerance` before or after the current time
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Check that the configuration can be served
+    ///
+    /// # Errors
+    ///
+    /// * [`WebhookConfig::path`] doesn't start with `/`, or contains route parameters
+    /// * The [`WebhookAuth::Hmac`] secret is empty
+    pub fn validate(&self) -> error::Result<()> {
+        let has_params = self.path.split('/').any(|segment| {
+            segment.starts_with(':') || segment.starts_with('*') || segment.contains(['{', '}'])
+        });
+        if !self.path.starts_with('/') || has_params {
+            return Err(Error::InvalidPath(self.path.clone()));
+        }
+
+        if let WebhookAuth::Hmac { secret } = &self.auth {
+            if secret.is_empty() {
+                return Err(Error::EmptySecret);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How the requests of a [`WebhookEventListener`] are authenticated
+#[derive(Clone)]
+pub enum WebhookAuth {
+    /// The [`SIGNATURE_HEADER`] holds the HMAC-SHA256 of the [`signed_payload`], keyed with `secret`
+    ///
+    /// The signature may be prefixed with `sha256=`. The secret can't be empty.
+    Hmac { secret: Vec<u8> },
+    /// The [`SIGNATURE_HEADER`] holds an ed25519 signature of the [`signed_payload`], made by the key
+    /// in the [`PUBLIC_KEY_HEADER`], which must be one of `allowed_keys`
+    Ed25519 {
+        allowed_keys: Vec<Ed25519VerificationKey>,
+    },
+}
+
+impl gadget_std::fmt::Debug for WebhookAuth {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        match self {
+            Self::Hmac { .. } => f.debug_struct("Hmac").finish_non_exhaustive(),
+            Self::Ed25519 { allowed_keys } => f
+                .debug_struct("Ed25519")
+                .field("allowed_keys", allowed_keys)
+                .finish(),
+        }
+    }
+}
+
+impl WebhookAuth {
+    /// Check `signature` over `payload`, returning the key that signed it, if any
+    fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        signature: &[u8],
+        payload: &[u8],
+    ) -> Result<Option<Ed25519VerificationKey>, Unauthorized> {
+        match self {
+            Self::Hmac { secret } => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret).map_err(|_| Unauthorized)?;
+                mac.update(payload);
+                mac.verify_slice(signature).map_err(|_| Unauthorized)?;
+                Ok(None)
+            }
+            Self::Ed25519 { allowed_keys } => {
+                let public = header_bytes(headers, PUBLIC_KEY_HEADER)?;
+                let public =
+                    Ed25519VerificationKey::from_bytes(&public).map_err(|_| Unauthorized)?;
+                if !allowed_keys.contains(&public) {
+                    return Err(Unauthorized);
+                }
+
+                let signature =
+                    Ed25519Signature::from_bytes(signature).map_err(|_| Unauthorized)?;
+                if !Ed25519Zebra::verify(&public, payload, &signature) {
+                    return Err(Unauthorized);
+                }
+
+                Ok(Some(public))
+            }
+        }
+    }
+}
+
+struct Unauthorized;
+
+impl From<Unauthorized> for StatusCode {
+    fn from(_: Unauthorized) -> Self {
+        StatusCode::UNAUTHORIZED
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, Unauthorized> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(Unauthorized)
+}
+
+fn header_bytes(headers: &HeaderMap, name: &str) -> Result<Vec<u8>, Unauthorized> {
+    let value = header_str(headers, name)?;
+    let value = value.strip_prefix("sha256=").unwrap_or(value);
+    hex::decode(value).map_err(|_| Unauthorized)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// An authenticated request received by a [`WebhookEventListener`]
+#[derive(Debug, Clone)]
+pub struct WebhookRequest {
+    pub body: Vec<u8>,
+    /// The key that signed the request, with [`WebhookAuth::Ed25519`]
+    pub signer: Option<Ed25519VerificationKey>,
+}
+
+impl WebhookRequest {
+    /// Decode the whole body as JSON
+    ///
+    /// # Errors
+    ///
+    /// The body is not a valid JSON encoding of `T`
+    pub fn json<T: DeserializeOwned>(&self) -> error::Result<T> {
+        serde_json::from_slice(&self.body).map_err(Error::InvalidBody)
+    }
+
+    /// Decode the body as a JSON object of job params
+    ///
+    /// # Errors
+    ///
+    /// The body is not a JSON object
+    pub fn params(&self) -> error::Result<WebhookParams> {
+        self.json().map(WebhookParams)
+    }
+}
+
+/// The job params of a [`WebhookRequest`], by name
+#[derive(Debug, Clone, Default)]
+pub struct WebhookParams(pub serde_json::Map<String, serde_json::Value>);
+
+impl WebhookParams {
+    /// Remove the param `name`, decoding it as `T`
+    ///
+    /// A missing param is decoded from `null`, so it's only accepted for types such as [`Option`].
+    ///
+    /// # Errors
+    ///
+    /// * The param is missing
+    /// * The param is not a valid JSON encoding of `T`
+    pub fn take<T: DeserializeOwned>(&mut self, name: &str) -> error::Result<T> {
+        let value = self.0.remove(name);
+        let missing = value.is_none();
+        serde_json::from_value(value.unwrap_or_default()).map_err(|e| {
+            if missing {
+                Error::MissingParam(name.to_string())
+            } else {
+                Error::InvalidParam(name.to_string(), e)
+            }
+        })
+    }
+}
+
+struct ServerState {
+    auth: WebhookAuth,
+    tolerance: Duration,
+    /// The signatures accepted within the tolerance, with their timestamps
+    seen: Mutex<BTreeMap<Vec<u8>, u64>>,
+    tx: Sender<WebhookRequest>,
+}
+
+impl ServerState {
+    /// Authenticate a request, rejecting it if it was signed outside the tolerance, or was already
+    /// accepted
+    ///
+    /// Requests are unavailable while [`MAX_SEEN_SIGNATURES`] signatures are remembered, as they
+    /// couldn't be checked for replays.
+    fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Option<Ed25519VerificationKey>, StatusCode> {
+        let timestamp = header_str(headers, TIMESTAMP_HEADER)?
+            .parse::<u64>()
+            .map_err(|_| Unauthorized)?;
+        let now = now();
+        let tolerance = self.tolerance.as_secs();
+        if timestamp.abs_diff(now) > tolerance {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        let signature = header_bytes(headers, SIGNATURE_HEADER)?;
+        let signer =
+            self.auth
+                .authenticate(headers, &signature, &signed_payload(timestamp, body))?;
+
+        // Signatures older than the tolerance are rejected by their timestamp, so only newer ones
+        // need to be remembered
+        let mut seen = self
+            .seen
+            .lock()
+            .unwrap_or_else(gadget_std::sync::PoisonError::into_inner);
+        seen.retain(|_, signed_at| signed_at.abs_diff(now) <= tolerance);
+        if seen.contains_key(&signature) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        if seen.len() >= MAX_SEEN_SIGNATURES {
+            return Err(StatusCode::SERVICE_UNAVAILABLE);
+        }
+        let _ = seen.insert(signature, timestamp);
+
+        Ok(signer)
+    }
+}
+
+impl WebhookEventListener {
+    /// Start serving requests as described by `config`
+    ///
+    /// # Errors
+    ///
+    /// * The config is invalid, see [`WebhookConfig::validate`]
+    /// * The server could not bind to [`WebhookConfig::bind_addr`]
+    pub async fn bind(config: WebhookConfig) -> Result<Self, CoreError<Error>> {
+        config
+            .validate()
+            .map_err(|e| CoreError::EventHandler(e.to_string()))?;
+        let listener = tokio::net::TcpListener::bind(config.bind_addr)
+            .await
+            .map_err(|e| CoreError::EventHandler(Error::Bind(e.to_string()).to_string()))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| CoreError::EventHandler(Error::Bind(e.to_string()).to_string()))?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(MAX_QUEUED_REQUESTS);
+        let state = Arc::new(ServerState {
+            auth: config.auth,
+            tolerance: config.tolerance,
+            seen: Mutex::default(),
+            tx,
+        });
+        let router = Router::new()
+            .route(&config.path, post(handle_request))
+            .with_state(state);
+
+        let task = async move {
+            if let Err(err) = axum::serve(listener, router).await {
+                gadget_logging::error!("Webhook server failed (fatal): {err}");
+            }
+        };
+
+        drop(tokio::spawn(task));
+
+        gadget_logging::info!("Listening for webhooks on {local_addr}{}", config.path);
+        Ok(Self { rx, local_addr })
+    }
+
+    /// The address the server is bound to
+    ///
+    /// This differs from [`WebhookConfig::bind_addr`] when binding to port `0`.
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+async fn handle_request(
+    State(state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    // Reserve a place in the queue first, so a request turned away for being busy isn't remembered
+    // as seen, and can be retried
+    let permit = match state.tx.try_reserve() {
+        Ok(permit) => permit,
+        Err(TrySendError::Full(())) => {
+            gadget_logging::warn!("Rejected a webhook request, too many requests are queued");
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+        Err(TrySendError::Closed(())) => {
+            gadget_logging::error!(
+                "Failed to send event to webhook worker, the listener was dropped"
+            );
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+    };
+
+    let signer = match state.authenticate(&headers, &body) {
+        Ok(signer) => signer,
+        Err(status) => {
+            if status == StatusCode::UNAUTHORIZED {
+                gadget_logging::warn!("Rejected an unauthenticated webhook request");
+            } else {
+                gadget_logging::warn!(
+                    "Rejected a webhook request, too many signatures are remembered"
+                );
+            }
+            return status;
+        }
+    };
+
+    permit.send(WebhookRequest {
+        body: body.to_vec(),
+        signer,
+    });
+    StatusCode::ACCEPTED
+}
+
+#[async_trait]
+impl<Ctx: WebhookDefinition> EventListener<WebhookRequest, Ctx> for WebhookEventListener {
+    type ProcessorError = Error;
+
+    async fn new(context: &Ctx) -> Result<Self, CoreError<Self::ProcessorError>>
+    where
+        Self: Sized,
+    {
+        Self::bind(context.webhook()).await
+    }
+
+    async fn next_event(&mut self) -> Option<WebhookRequest> {
+        self.rx.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const BODY: &str = r#"{"x":5,"name":"squared"}"#;
+
+    struct TestContext(WebhookAuth);
+
+    impl WebhookDefinition for TestContext {
+        fn webhook(&self) -> WebhookConfig {
+            WebhookConfig::new(([127, 0, 0, 1], 0).into(), self.0.clone()).with_path("/jobs")
+        }
+    }
+
+    async fn post(addr: SocketAddr, headers: &[(&str, String)], body: &str) -> u16 {
+        let mut request = format!(
+            "POST /jobs HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n",
+            body.len()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response[9..12].parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn hmac_webhook_event_listener() {
+        let secret = b"secret".to_vec();
+        let context = TestContext(WebhookAuth::Hmac {
+            secret: secret.clone(),
+        });
+        let mut listener = WebhookEventListener::new(&context).await.unwrap();
+        let addr = listener.local_addr();
+
+        let headers = |timestamp: u64| {
+            let mut mac = Hmac::<Sha256>::new_from_slice(&secret).unwrap();
+            mac.update(&signed_payload(timestamp, BODY.as_bytes()));
+            let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+            [
+                (SIGNATURE_HEADER, signature),
+                (TIMESTAMP_HEADER, timestamp.to_string()),
+            ]
+        };
+
+        assert_eq!(post(addr, &[], BODY).await, 401);
+        let forged = [
+            (SIGNATURE_HEADER, hex::encode([0; 32])),
+            (TIMESTAMP_HEADER, now().to_string()),
+        ];
+        assert_eq!(post(addr, &forged, BODY).await, 401);
+
+        // The timestamp is signed, so it can't be moved into the window
+        let stale = now() - DEFAULT_TOLERANCE.as_secs() - 1;
+        assert_eq!(post(addr, &headers(stale), BODY).await, 401);
+        let mut moved = headers(stale);
+        moved[1].1 = now().to_string();
+        assert_eq!(post(addr, &moved, BODY).await, 401);
+
+        let fresh = headers(now());
+        assert_eq!(post(addr, &fresh, BODY).await, 202);
+        // Replaying an accepted request is rejected
+        assert_eq!(post(addr, &fresh, BODY).await, 401);
+
+        let request = EventListener::<_, TestContext>::next_event(&mut listener)
+            .await
+            .unwrap();
+        assert!(request.signer.is_none());
+
+        let mut params = request.params().unwrap();
+        assert_eq!(params.take::<u64>("x").unwrap(), 5);
+        assert_eq!(params.take::<String>("name").unwrap(), "squared");
+        assert_eq!(params.take::<Option<u64>>("missing").unwrap(), None);
+        assert!(matches!(
+            params.take::<u64>("missing"),
+            Err(Error::MissingParam(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn ed25519_webhook_event_listener() {
+        let mut allowed = Ed25519Zebra::generate_with_seed(Some(&[1; 32])).unwrap();
+        let mut other = Ed25519Zebra::generate_with_seed(Some(&[2; 32])).unwrap();
+        let allowed_public = Ed25519Zebra::public_from_secret(&allowed);
+        let other_public = Ed25519Zebra::public_from_secret(&other);
+
+        let context = TestContext(WebhookAuth::Ed25519 {
+            allowed_keys: vec![allowed_public.clone()],
+        });
+        let mut listener = WebhookEventListener::new(&context).await.unwrap();
+        let addr = listener.local_addr();
+
+        let headers = |secret: &mut _, public: &Ed25519VerificationKey| {
+            let timestamp = now();
+            let payload = signed_payload(timestamp, BODY.as_bytes());
+            let signature = Ed25519Zebra::sign_with_secret(secret, &payload).unwrap();
+            [
+                (SIGNATURE_HEADER, hex::encode(signature.to_bytes())),
+                (PUBLIC_KEY_HEADER, hex::encode(public.to_bytes())),
+                (TIMESTAMP_HEADER, timestamp.to_string()),
+            ]
+        };
+
+        let from_other = headers(&mut other, &other_public);
+        assert_eq!(post(addr, &from_other, BODY).await, 401);
+        let from_allowed = headers(&mut allowed, &allowed_public);
+        assert_eq!(post(addr, &from_allowed, "{}").await, 401);
+        assert_eq!(post(addr, &from_allowed, BODY).await, 202);
+
+        let request = EventListener::<_, TestContext>::next_event(&mut listener)
+            .await
+            .unwrap();
+        assert_eq!(request.signer, Some(allowed_public));
+        assert_eq!(request.body, BODY.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn invalid_configs_are_rejected() {
+        let auth = WebhookAuth::Hmac {
+            secret: b"secret".to_vec(),
+        };
+        let config = WebhookConfig::new(([127, 0, 0, 1], 0).into(), auth);
+        assert!(config.validate().is_ok());
+        assert!(config.clone().with_path("/jobs/square").validate().is_ok());
+
+        for path in ["", "jobs", "/jobs/:id", "/*rest", "/{id}"] {
+            assert!(matches!(
+                config.clone().with_path(path).validate(),
+                Err(Error::InvalidPath(_))
+            ));
+            assert!(WebhookEventListener::bind(config.clone().with_path(path))
+                .await
+                .is_err());
+        }
+
+        let empty = WebhookConfig::new(
+            ([127, 0, 0, 1], 0).into(),
+            WebhookAuth::Hmac { secret: Vec::new() },
+        );
+        assert!(matches!(empty.validate(), Err(Error::EmptySecret)));
+        assert!(WebhookEventListener::bind(empty).await.is_err());
+    }
+}
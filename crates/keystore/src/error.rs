@@ -46,6 +46,18 @@ pub enum Error {
     /// Remote key fetch failed
     #[error("Remote key fetch failed: {0}")]
     RemoteKeyFetchFailed(String),
+    /// Invalid keystore URI
+    #[error("Invalid keystore URI: {0}")]
+    InvalidUri(String),
+    /// An encrypted key was found, but no password was given
+    #[error("The keystore is encrypted, but no password was given")]
+    PasswordRequired,
+    /// The password does not decrypt the keystore
+    #[error("Wrong keystore password")]
+    WrongPassword,
+    /// Failed to encrypt or decrypt a key
+    #[error("Keystore encryption failed: {0}")]
+    Encryption(String),
 
     /* Crypto errors */
     #[error(transparent)]
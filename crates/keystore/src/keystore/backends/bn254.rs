@@ -3,6 +3,7 @@ use crate::error::{Error, Result};
 use crate::Keystore;
 use gadget_crypto::bn254::{
     ArkBlsBn254, ArkBlsBn254Public, ArkBlsBn254Secret, ArkBlsBn254Signature,
+    PubkeyRegistrationParams,
 };
 use gadget_crypto::{KeyEncoding, KeyTypeId};
 
@@ -29,6 +30,16 @@ pub trait Bn254Backend: Send + Sync {
 
     /// Iterate over all BN254 public keys
     fn iter_bls_bn254(&self) -> Box<dyn Iterator<Item = ArkBlsBn254Public> + '_>;
+
+    /// Create the parameters for registering a BN254 key with EigenLayer's `BLSApkRegistry`
+    ///
+    /// `message_hash` is the G1 point returned by the registry coordinator's
+    /// `pubkeyRegistrationMessageHash(operator)`.
+    fn bls_bn254_registration_params(
+        &self,
+        public: &ArkBlsBn254Public,
+        message_hash: ark_bn254::G1Affine,
+    ) -> Result<Option<PubkeyRegistrationParams>>;
 }
 
 impl Bn254Backend for Keystore {
@@ -95,6 +106,16 @@ impl Bn254Backend for Keystore {
         }
         Box::new(keys.into_iter())
     }
+
+    fn bls_bn254_registration_params(
+        &self,
+        public: &ArkBlsBn254Public,
+        message_hash: ark_bn254::G1Affine,
+    ) -> Result<Option<PubkeyRegistrationParams>> {
+        Ok(self
+            .expose_bls_bn254_secret(public)?
+            .map(|secret| PubkeyRegistrationParams::new(&secret, message_hash)))
+    }
 }
 
 fn insert_bls_bn254_key(
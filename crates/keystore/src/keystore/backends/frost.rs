@@ -0,0 +1,145 @@
+use crate::error::{Error, Result};
+use crate::Keystore;
+use gadget_crypto::frost::frost_core::VerifyingKey;
+use gadget_crypto::frost::{FrostCiphersuite, KeyShare};
+use gadget_crypto::KeyEncoding;
+use gadget_std::string::ToString;
+use gadget_std::vec::Vec;
+
+/// Storage for FROST key shares, keyed by the group public key
+pub trait FrostBackend: Send + Sync {
+    /// Store a key share produced by distributed key generation
+    fn frost_store_key_share<C: FrostCiphersuite>(&self, share: &KeyShare<C>) -> Result<()>;
+
+    /// Get our key share for the group public key `group_key`
+    fn frost_key_share<C: FrostCiphersuite>(
+        &self,
+        group_key: &VerifyingKey<C>,
+    ) -> Result<Option<KeyShare<C>>>;
+
+    /// List the group public keys we hold a share of
+    fn frost_group_keys<C: FrostCiphersuite>(&self) -> Result<Vec<VerifyingKey<C>>>;
+
+    /// Remove our key share for the group public key `group_key`
+    fn frost_remove_key_share<C: FrostCiphersuite>(
+        &self,
+        group_key: &VerifyingKey<C>,
+    ) -> Result<()>;
+}
+
+impl FrostBackend for Keystore {
+    fn frost_store_key_share<C: FrostCiphersuite>(&self, share: &KeyShare<C>) -> Result<()> {
+        let public_bytes = group_key_bytes(share.verifying_key())?;
+        let secret_bytes = share.to_bytes();
+
+        if let Some(storages) = self.storages.get(&C::KEY_TYPE_ID) {
+            for entry in storages {
+                entry.storage.store_raw(
+                    C::KEY_TYPE_ID,
+                    public_bytes.clone(),
+                    secret_bytes.clone(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn frost_key_share<C: FrostCiphersuite>(
+        &self,
+        group_key: &VerifyingKey<C>,
+    ) -> Result<Option<KeyShare<C>>> {
+        let public_bytes = group_key_bytes(group_key)?;
+
+        if let Some(storages) = self.storages.get(&C::KEY_TYPE_ID) {
+            for entry in storages {
+                if let Some(secret_bytes) = entry
+                    .storage
+                    .load_secret_raw(C::KEY_TYPE_ID, public_bytes.clone())?
+                {
+                    return Ok(Some(KeyShare::from_bytes(&secret_bytes)?));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn frost_group_keys<C: FrostCiphersuite>(&self) -> Result<Vec<VerifyingKey<C>>> {
+        let Some(storages) = self.storages.get(&C::KEY_TYPE_ID) else {
+            return Ok(Vec::new());
+        };
+
+        let mut keys = Vec::new();
+        for entry in storages {
+            let mut storage_keys = entry
+                .storage
+                .list_raw(C::KEY_TYPE_ID)
+                .filter_map(|bytes| VerifyingKey::<C>::deserialize(&bytes).ok())
+                .collect::<Vec<_>>();
+            keys.append(&mut storage_keys);
+        }
+        Ok(keys)
+    }
+
+    fn frost_remove_key_share<C: FrostCiphersuite>(
+        &self,
+        group_key: &VerifyingKey<C>,
+    ) -> Result<()> {
+        let public_bytes = group_key_bytes(group_key)?;
+
+        if let Some(storages) = self.storages.get(&C::KEY_TYPE_ID) {
+            for entry in storages {
+                entry
+                    .storage
+                    .remove_raw(C::KEY_TYPE_ID, public_bytes.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn group_key_bytes<C: FrostCiphersuite>(group_key: &VerifyingKey<C>) -> Result<Vec<u8>> {
+    group_key
+        .serialize()
+        .map_err(|e| Error::Other(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeystoreConfig;
+    use gadget_crypto::frost::frost_core::keys::{
+        generate_with_dealer, IdentifierList, KeyPackage,
+    };
+    use gadget_crypto::frost::Ed25519Sha512;
+    use gadget_std::rand::rngs::OsRng;
+
+    #[test]
+    fn test_frost_key_share_persistence() -> Result<()> {
+        let keystore = Keystore::new(KeystoreConfig::new())?;
+        let (shares, public_key_package) =
+            generate_with_dealer::<Ed25519Sha512, _>(3, 2, IdentifierList::Default, OsRng).unwrap();
+        let share = shares.into_values().next().unwrap();
+        let share =
+            KeyShare::new(KeyPackage::try_from(share).unwrap(), public_key_package).unwrap();
+
+        keystore.frost_store_key_share(&share)?;
+        assert_eq!(
+            keystore.frost_group_keys::<Ed25519Sha512>()?,
+            vec![*share.verifying_key()]
+        );
+        let loaded = keystore
+            .frost_key_share::<Ed25519Sha512>(share.verifying_key())?
+            .expect("share should be stored");
+        assert!(loaded == share);
+
+        keystore.frost_remove_key_share(share.verifying_key())?;
+        assert!(keystore
+            .frost_key_share::<Ed25519Sha512>(share.verifying_key())?
+            .is_none());
+
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@ pub mod bn254;
 pub mod eigenlayer;
 #[cfg(feature = "evm")]
 pub mod evm;
+#[cfg(feature = "frost")]
+pub mod frost;
 
 cfg_remote! {
     pub mod remote;
@@ -17,6 +19,7 @@ use crate::error::Result;
 use crate::storage::RawStorage;
 use gadget_crypto::IntoCryptoError;
 use gadget_crypto::KeyType;
+use gadget_crypto::ProofOfPossession;
 use gadget_std::{boxed::Box, vec::Vec};
 use serde::de::DeserializeOwned;
 
@@ -82,6 +85,14 @@ pub trait Backend: Send + Sync {
         T::Public: DeserializeOwned,
         T::Secret: DeserializeOwned;
 
+    /// Get whichever key of the given type occurs first in local storage, together with a proof
+    /// of possession for registering it
+    fn first_local_with_proof<T: ProofOfPossession>(&self) -> Result<(T::Public, T::Signature)>
+    where
+        T::Public: DeserializeOwned,
+        T::Secret: DeserializeOwned,
+        T::Error: IntoCryptoError;
+
     /// Get storage backends for a key type
     fn get_storage_backends<T: KeyType>(&self) -> Result<&[LocalStorageEntry]>;
 }
@@ -1,3 +1,17 @@
+use crate::error::{Error, Result};
+use gadget_std::format;
+#[cfg(feature = "std")]
+use gadget_std::{fmt, string::String};
+#[cfg(any(
+    feature = "aws-signer",
+    feature = "gcp-signer",
+    feature = "ledger-browser",
+    feature = "ledger-node"
+))]
+use gadget_std::{string::ToString, vec};
+#[cfg(feature = "std")]
+use zeroize::Zeroizing;
+
 /// The config for a [`Keystore`]
 ///
 /// Depending on the features enabled, this provides methods to enable different storage backends.
@@ -35,6 +49,8 @@ pub struct KeystoreConfig {
     pub(crate) in_memory: bool,
     #[cfg(feature = "std")]
     pub(crate) fs_root: Option<std::path::PathBuf>,
+    #[cfg(feature = "std")]
+    pub(crate) password: Option<Password>,
     #[cfg(any(
         feature = "aws-signer",
         feature = "gcp-signer",
@@ -107,6 +123,157 @@ impl KeystoreConfig {
         self
     }
 
+    /// Encrypt the keys of the [`FileStorage`] backend with `password`
+    ///
+    /// This has no effect without [`KeystoreConfig::fs_root()`]. See [`FileStorage::encrypted()`] for notes
+    /// on how existing keys are handled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use gadget_keystore::{Keystore, KeystoreConfig};
+    ///
+    /// # fn main() -> gadget_keystore::Result<()> {
+    /// let config = KeystoreConfig::new()
+    ///     .fs_root("path/to/keystore")
+    ///     .password("hunter2");
+    /// let keystore = Keystore::new(config)?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`FileStorage`]: crate::storage::FileStorage
+    /// [`FileStorage::encrypted()`]: crate::storage::FileStorage::encrypted
+    #[cfg(feature = "std")]
+    pub fn password<S: Into<String>>(mut self, password: S) -> Self {
+        self.password = Some(Password(Zeroizing::new(password.into())));
+        self
+    }
+
+    /// Create a `KeystoreConfig` from a keystore URI
+    ///
+    /// The supported URIs are:
+    ///
+    /// * `memory://`: An [`InMemoryStorage`]
+    /// * `file://path/to/keystore`, or a plain path: A [`FileStorage`] at the path
+    /// * `aws-kms://<region>/<key id>`: An AWS KMS key (requires the `aws-signer` feature)
+    /// * `gcp-kms://projects/<project>/locations/<location>/keyRings/<keyring>/cryptoKeys/<key>/cryptoKeyVersions/<version>`:
+    ///   A GCP KMS key (requires the `gcp-signer` feature)
+    /// * `ledger://<account index>`: A Ledger Live account, the first by default (requires the
+    ///   `ledger-browser` or `ledger-node` feature)
+    ///
+    /// Remote signer URIs accept a `chain_id` query parameter, as in `aws-kms://us-east-1/my-key?chain_id=1`.
+    ///
+    /// # Errors
+    ///
+    /// The URI is malformed, or has a scheme that is unknown or not enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gadget_keystore::{Keystore, KeystoreConfig};
+    ///
+    /// # fn main() -> gadget_keystore::Result<()> {
+    /// let config = KeystoreConfig::from_uri("memory://")?;
+    /// let keystore = Keystore::new(config)?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`InMemoryStorage`]: crate::storage::InMemoryStorage
+    /// [`FileStorage`]: crate::storage::FileStorage
+    pub fn from_uri(uri: &str) -> Result<Self> {
+        let Some((scheme, rest)) = uri.split_once("://") else {
+            return Self::from_path(uri, uri);
+        };
+
+        match scheme {
+            "memory" => Ok(Self::new().in_memory(true)),
+            "file" => Self::from_path(uri, rest),
+            #[cfg(feature = "aws-signer")]
+            "aws-kms" => {
+                let (path, chain_id) = split_chain_id(uri, rest)?;
+                let (region, key_id) = path
+                    .split_once('/')
+                    .filter(|(region, key_id)| !region.is_empty() && !key_id.is_empty())
+                    .ok_or_else(|| invalid_uri(uri, "expected `aws-kms://<region>/<key id>`"))?;
+                Ok(Self::new().remote(crate::remote::RemoteConfig::Aws {
+                    keys: vec![crate::remote::aws::AwsKeyConfig {
+                        key_id: key_id.to_string(),
+                        region: region.to_string(),
+                        chain_id,
+                    }],
+                }))
+            }
+            #[cfg(feature = "gcp-signer")]
+            "gcp-kms" => {
+                let (path, chain_id) = split_chain_id(uri, rest)?;
+                let mut segments = path.split('/');
+                let mut segment = |name: &str| match (segments.next(), segments.next()) {
+                    (Some(segment), Some(value)) if segment == name && !value.is_empty() => {
+                        Ok(value)
+                    }
+                    _ => Err(invalid_uri(uri, GCP_KMS_URI_FORMAT)),
+                };
+                let project_id = segment("projects")?;
+                let location = segment("locations")?;
+                let keyring = segment("keyRings")?;
+                let key_name = segment("cryptoKeys")?;
+                let key_version = segment("cryptoKeyVersions")?
+                    .parse()
+                    .map_err(|_| invalid_uri(uri, "the key version must be a number"))?;
+                if segments.next().is_some() {
+                    return Err(invalid_uri(
+                        uri,
+                        "unexpected segments after the key version",
+                    ));
+                }
+                Ok(Self::new().remote(crate::remote::RemoteConfig::Gcp {
+                    keys: vec![crate::remote::gcp::GcpKeyConfig {
+                        project_id: project_id.to_string(),
+                        location: location.to_string(),
+                        keyring: keyring.to_string(),
+                        key_name: key_name.to_string(),
+                        key_version,
+                        chain_id,
+                    }],
+                }))
+            }
+            #[cfg(any(feature = "ledger-browser", feature = "ledger-node"))]
+            "ledger" => {
+                let (path, chain_id) = split_chain_id(uri, rest)?;
+                let index = if path.is_empty() {
+                    0
+                } else {
+                    path.parse()
+                        .map_err(|_| invalid_uri(uri, "the account index must be a number"))?
+                };
+                Ok(Self::new().remote(crate::remote::RemoteConfig::Ledger {
+                    keys: vec![crate::remote::ledger::LedgerKeyConfig {
+                        hd_path: crate::remote::ledger::HDPathWrapper(
+                            alloy_signer_ledger::HDPath::LedgerLive(index),
+                        ),
+                        chain_id,
+                    }],
+                }))
+            }
+            _ => Err(invalid_uri(uri, &format!("unsupported scheme `{scheme}`"))),
+        }
+    }
+
+    fn from_path(uri: &str, path: &str) -> Result<Self> {
+        if path.is_empty() {
+            return Err(invalid_uri(uri, "the path is empty"));
+        }
+
+        #[cfg(feature = "std")]
+        {
+            Ok(Self::new().fs_root(path))
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            Err(invalid_uri(uri, "file storage requires the `std` feature"))
+        }
+    }
+
     cfg_remote! {
         /// Register a remote backend
         ///
@@ -163,3 +330,105 @@ impl KeystoreConfig {
         self
     }
 }
+
+/// The keystore password, which is never printed
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub(crate) struct Password(pub(crate) Zeroizing<String>);
+
+#[cfg(feature = "std")]
+impl fmt::Debug for Password {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Password(<redacted>)")
+    }
+}
+
+#[cfg(feature = "gcp-signer")]
+const GCP_KMS_URI_FORMAT: &str = "expected `gcp-kms://projects/<project>/locations/<location>/keyRings/<keyring>/cryptoKeys/<key>/cryptoKeyVersions/<version>`";
+
+fn invalid_uri(uri: &str, reason: &str) -> Error {
+    Error::InvalidUri(format!("`{uri}`: {reason}"))
+}
+
+/// Split the `chain_id` query parameter from a remote signer URI
+#[cfg(any(
+    feature = "aws-signer",
+    feature = "gcp-signer",
+    feature = "ledger-browser",
+    feature = "ledger-node"
+))]
+fn split_chain_id<'a>(uri: &str, rest: &'a str) -> Result<(&'a str, Option<u64>)> {
+    let Some((path, query)) = rest.split_once('?') else {
+        return Ok((rest, None));
+    };
+
+    let chain_id = query
+        .strip_prefix("chain_id=")
+        .ok_or_else(|| invalid_uri(uri, "the only supported query parameter is `chain_id`"))?
+        .parse()
+        .map_err(|_| invalid_uri(uri, "`chain_id` must be a number"))?;
+    Ok((path, Some(chain_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_uri() -> Result<()> {
+        let config = KeystoreConfig::from_uri("memory://")?;
+        assert!(config.in_memory);
+
+        let config = KeystoreConfig::from_uri("file:///tmp/keystore")?;
+        assert_eq!(
+            config.fs_root.as_deref(),
+            Some(std::path::Path::new("/tmp/keystore"))
+        );
+
+        let config = KeystoreConfig::from_uri("./keystore")?;
+        assert_eq!(
+            config.fs_root.as_deref(),
+            Some(std::path::Path::new("./keystore"))
+        );
+
+        assert!(matches!(
+            KeystoreConfig::from_uri("ftp://keystore"),
+            Err(Error::InvalidUri(_))
+        ));
+        assert!(matches!(
+            KeystoreConfig::from_uri("file://"),
+            Err(Error::InvalidUri(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "aws-signer")]
+    fn from_remote_uri() -> Result<()> {
+        let config = KeystoreConfig::from_uri("aws-kms://us-east-1/my-key?chain_id=1")?;
+        let [crate::remote::RemoteConfig::Aws { keys }] = &config.remote_configs[..] else {
+            panic!("expected an AWS remote config");
+        };
+        assert_eq!(keys[0].region, "us-east-1");
+        assert_eq!(keys[0].key_id, "my-key");
+        assert_eq!(keys[0].chain_id, Some(1));
+
+        assert!(matches!(
+            KeystoreConfig::from_uri("aws-kms://us-east-1"),
+            Err(Error::InvalidUri(_))
+        ));
+        assert!(matches!(
+            KeystoreConfig::from_uri("aws-kms://us-east-1/my-key?chain=1"),
+            Err(Error::InvalidUri(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn password_is_redacted() {
+        let config = KeystoreConfig::new().password("hunter2");
+        assert!(!format!("{config:?}").contains("hunter2"));
+    }
+}
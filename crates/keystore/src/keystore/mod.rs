@@ -2,13 +2,14 @@ pub mod backends;
 use backends::Backend;
 use backends::BackendConfig;
 cfg_remote! {
-    use backends::remote::RemoteEntry;
+    use backends::remote::{RemoteCapabilities, RemoteEntry};
 }
 
 mod config;
 pub use config::KeystoreConfig;
 use gadget_crypto::KeyType;
 use gadget_crypto::KeyTypeId;
+use gadget_crypto::ProofOfPossession;
 use gadget_crypto::{IntoCryptoError, KeyEncoding};
 
 use crate::error::{Error, Result};
@@ -82,10 +83,15 @@ impl Keystore {
 
         #[cfg(feature = "std")]
         if let Some(fs_root) = config.fs_root {
+            // A single storage is shared by every key type, so the password is only checked once
+            let storage = match &config.password {
+                Some(password) => FileStorage::encrypted(fs_root.as_path(), &password.0)?,
+                None => FileStorage::new(fs_root.as_path())?,
+            };
             for key_type in KeyTypeId::ENABLED {
                 keystore.register_storage(
                     *key_type,
-                    BackendConfig::Local(Box::new(FileStorage::new(fs_root.as_path())?)),
+                    BackendConfig::Local(Box::new(storage.clone())),
                     0,
                 )?;
             }
@@ -98,13 +104,8 @@ impl Keystore {
             feature = "ledger-node"
         ))]
         for remote_config in config.remote_configs {
-            for key_type in KeyTypeId::ENABLED {
-                keystore.register_storage(
-                    *key_type,
-                    BackendConfig::Remote(remote_config.clone()),
-                    0,
-                )?;
-            }
+            // Remote signers only hold EVM (secp256k1 ECDSA) keys
+            keystore.register_storage(KeyTypeId::Ecdsa, BackendConfig::Remote(remote_config), 0)?;
         }
 
         Ok(keystore)
@@ -130,7 +131,16 @@ impl Keystore {
                 feature = "ledger-browser",
                 feature = "ledger-node"
             ))]
-            BackendConfig::Remote(_config) => return Err(Error::StorageNotSupported),
+            BackendConfig::Remote(config) => {
+                let capabilities = RemoteCapabilities {
+                    signing: true,
+                    ..RemoteCapabilities::default()
+                };
+                self.remotes
+                    .entry(key_type_id)
+                    .or_default()
+                    .push(RemoteEntry::new(config, capabilities));
+            }
         }
         Ok(())
     }
@@ -302,6 +312,18 @@ impl Backend for Keystore {
         Err(Error::KeyNotFound)
     }
 
+    fn first_local_with_proof<T: ProofOfPossession>(&self) -> Result<(T::Public, T::Signature)>
+    where
+        T::Public: DeserializeOwned,
+        T::Secret: DeserializeOwned,
+        T::Error: IntoCryptoError,
+    {
+        let public = self.first_local::<T>()?;
+        let mut secret = self.get_secret::<T>(&public)?;
+        let proof = T::prove_possession(&mut secret).map_err(IntoCryptoError::into_crypto_error)?;
+        Ok((public, proof))
+    }
+
     // Helper methods
     fn get_storage_backends<T: KeyType>(&self) -> Result<&[LocalStorageEntry]> {
         self.storages
@@ -384,4 +406,16 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_first_local_with_proof() -> Result<()> {
+        let keystore = Keystore::new(KeystoreConfig::new())?;
+        let public = keystore.generate::<W3fBls381>(None)?;
+
+        let (proof_public, proof) = keystore.first_local_with_proof::<W3fBls381>()?;
+        assert_eq!(proof_public, public);
+        assert!(W3fBls381::verify_possession(&public, &proof));
+
+        Ok(())
+    }
 }
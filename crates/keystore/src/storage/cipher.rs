@@ -0,0 +1,95 @@
+use crate::error::{Error, Result};
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use gadget_std::collections::BTreeMap;
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::Arc;
+use gadget_std::vec::Vec;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// A secret encrypted with a key derived from the keystore password
+#[derive(Serialize, Deserialize)]
+pub(crate) struct EncryptedSecret {
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+/// Encrypts secrets with XChaCha20-Poly1305, under keys derived from a password with Argon2id
+///
+/// Key derivation is deliberately slow, so derived keys are cached by salt, and every secret
+/// encrypted by the same `KeyCipher` shares a salt.
+#[derive(Clone)]
+pub(crate) struct KeyCipher {
+    password: Arc<Zeroizing<String>>,
+    salt: [u8; SALT_LEN],
+    keys: Arc<Mutex<BTreeMap<Vec<u8>, Zeroizing<[u8; KEY_LEN]>>>>,
+}
+
+impl KeyCipher {
+    pub(crate) fn new(password: &str) -> Self {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        Self {
+            password: Arc::new(Zeroizing::new(password.to_string())),
+            salt,
+            keys: Arc::default(),
+        }
+    }
+
+    fn derive_key(&self, salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+        let mut keys = self.keys.lock();
+        if let Some(key) = keys.get(salt) {
+            return Ok(key.clone());
+        }
+
+        let mut key = Zeroizing::new([0; KEY_LEN]);
+        Argon2::default()
+            .hash_password_into(self.password.as_bytes(), salt, &mut key[..])
+            .map_err(|e| Error::Encryption(e.to_string()))?;
+        keys.insert(salt.to_vec(), key.clone());
+        Ok(key)
+    }
+
+    pub(crate) fn encrypt(&self, secret: &[u8]) -> Result<EncryptedSecret> {
+        let key = self.derive_key(&self.salt)?;
+        let cipher = XChaCha20Poly1305::new_from_slice(&key[..])
+            .map_err(|e| Error::Encryption(e.to_string()))?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, secret)
+            .map_err(|e| Error::Encryption(e.to_string()))?;
+
+        Ok(EncryptedSecret {
+            salt: self.salt.to_vec(),
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Decrypt `encrypted`, failing with [`Error::WrongPassword`] if it wasn't encrypted with this password
+    pub(crate) fn decrypt(&self, encrypted: &EncryptedSecret) -> Result<Vec<u8>> {
+        if encrypted.nonce.len() != NONCE_LEN {
+            return Err(Error::Encryption(String::from("invalid nonce length")));
+        }
+
+        let key = self.derive_key(&encrypted.salt)?;
+        let cipher = XChaCha20Poly1305::new_from_slice(&key[..])
+            .map_err(|e| Error::Encryption(e.to_string()))?;
+        cipher
+            .decrypt(
+                XNonce::from_slice(&encrypted.nonce),
+                encrypted.ciphertext.as_slice(),
+            )
+            .map_err(|_| Error::WrongPassword)
+    }
+}
@@ -1,14 +1,39 @@
+use super::cipher::{EncryptedSecret, KeyCipher};
 use super::RawStorage;
 use crate::error::{Error, Result};
 use gadget_crypto::KeyTypeId;
 use gadget_std::fs;
 use gadget_std::io;
 use gadget_std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
 
 /// A filesystem-backed local storage
+///
+/// Secrets are stored in plaintext, unless the storage is created with [`FileStorage::encrypted()`].
 #[derive(Clone)]
 pub struct FileStorage {
     root: PathBuf,
+    cipher: Option<KeyCipher>,
+}
+
+/// The contents of a key file
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredKey {
+    /// A `(public, secret)` pair
+    Plain(Vec<u8>, Vec<u8>),
+    Encrypted {
+        public: Vec<u8>,
+        secret: EncryptedSecret,
+    },
+}
+
+impl StoredKey {
+    fn public(&self) -> &[u8] {
+        match self {
+            StoredKey::Plain(public, _) | StoredKey::Encrypted { public, .. } => public,
+        }
+    }
 }
 
 impl FileStorage {
@@ -52,9 +77,62 @@ impl FileStorage {
         fs::create_dir_all(root)?;
         Ok(Self {
             root: root.to_path_buf(),
+            cipher: None,
         })
     }
 
+    /// Create a new `FileStorage` that encrypts secrets with `password`
+    ///
+    /// Keys are encrypted with XChaCha20-Poly1305, using a key derived from `password` with Argon2id.
+    /// Plaintext keys already in the storage can still be read, but are not encrypted until they are
+    /// stored again.
+    ///
+    /// # Errors
+    ///
+    /// * See [`FileStorage::new()`]
+    /// * [`Error::WrongPassword`]: The storage has encrypted keys, and `password` does not decrypt them
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use gadget_keystore::storage::FileStorage;
+    ///
+    /// # fn main() -> gadget_keystore::Result<()> {
+    /// let storage = FileStorage::encrypted("/path/to/keystore", "hunter2")?;
+    /// # Ok(()) }
+    /// ```
+    pub fn encrypted<P: AsRef<Path>>(path: P, password: &str) -> Result<Self> {
+        let mut storage = Self::new(path)?;
+        storage.cipher = Some(KeyCipher::new(password));
+        storage.verify_password()?;
+        Ok(storage)
+    }
+
+    /// Decrypt the first encrypted key, so a wrong password is reported before any key is used
+    fn verify_password(&self) -> Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(());
+        };
+
+        let key_files = fs::read_dir(&self.root)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|type_dir| fs::read_dir(type_dir.path()).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok());
+        for key_file in key_files {
+            let Ok(data) = fs::read(key_file.path()) else {
+                continue;
+            };
+            if let Ok(StoredKey::Encrypted { secret, .. }) = serde_json::from_slice(&data) {
+                cipher.decrypt(&secret)?;
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
     fn type_dir(&self, type_id: KeyTypeId) -> PathBuf {
         self.root.join(format!("{:?}", type_id))
     }
@@ -77,7 +155,13 @@ impl RawStorage for FileStorage {
             fs::create_dir_all(parent)?;
         }
 
-        let data = (public_bytes.to_vec(), secret_bytes.to_vec());
+        let data = match &self.cipher {
+            Some(cipher) => StoredKey::Encrypted {
+                secret: cipher.encrypt(&secret_bytes)?,
+                public: public_bytes,
+            },
+            None => StoredKey::Plain(public_bytes, secret_bytes),
+        };
         let encoded = serde_json::to_vec(&data)?;
         fs::write(path, encoded)?;
         Ok(())
@@ -94,13 +178,22 @@ impl RawStorage for FileStorage {
         }
 
         let data = fs::read(&path)?;
-        let (stored_pub, secret): (Vec<u8>, Vec<u8>) = serde_json::from_slice(&data)?;
+        let stored: StoredKey = serde_json::from_slice(&data)?;
 
         // Verify the public key matches
-        if stored_pub != public_bytes {
+        if stored.public() != public_bytes {
             return Ok(None);
         }
 
+        let secret = match stored {
+            StoredKey::Plain(_, secret) => secret,
+            StoredKey::Encrypted { secret, .. } => self
+                .cipher
+                .as_ref()
+                .ok_or(Error::PasswordRequired)?
+                .decrypt(&secret)?,
+        };
+
         Ok(Some(secret.into_boxed_slice()))
     }
 
@@ -128,9 +221,9 @@ impl RawStorage for FileStorage {
             .filter_map(|entry| entry.ok())
             .filter_map(|entry| fs::read(entry.path()).ok())
             .filter_map(|data| {
-                serde_json::from_slice::<(Vec<u8>, Vec<u8>)>(&data)
+                serde_json::from_slice::<StoredKey>(&data)
                     .ok()
-                    .map(|(pub_key, _)| pub_key.into_boxed_slice())
+                    .map(|stored| stored.public().into())
             });
 
         Box::new(iter)
@@ -199,4 +292,76 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_encrypted_storage() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = TypedStorage::new(FileStorage::encrypted(temp_dir.path(), "hunter2")?);
+
+        let secret =
+            K256Ecdsa::generate_with_seed(None).map_err(IntoCryptoError::into_crypto_error)?;
+        let public = K256Ecdsa::public_from_secret(&secret);
+        storage.store::<K256Ecdsa>(&public, &secret)?;
+
+        // The secret is not stored in plaintext
+        let key_file = fs::read_dir(
+            temp_dir
+                .path()
+                .join(format!("{:?}", K256Ecdsa::key_type_id())),
+        )?
+        .next()
+        .unwrap()?;
+        let data = fs::read(key_file.path())?;
+        assert!(serde_json::from_slice::<(Vec<u8>, Vec<u8>)>(&data).is_err());
+
+        // Reopening with the same password decrypts it
+        let storage = TypedStorage::new(FileStorage::encrypted(temp_dir.path(), "hunter2")?);
+        assert_eq!(storage.load::<K256Ecdsa>(&public)?, Some(secret));
+        assert_eq!(
+            storage.list::<K256Ecdsa>().collect::<Vec<_>>(),
+            vec![public]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_storage_errors() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = TypedStorage::new(FileStorage::encrypted(temp_dir.path(), "hunter2")?);
+
+        let secret =
+            K256Ecdsa::generate_with_seed(None).map_err(IntoCryptoError::into_crypto_error)?;
+        let public = K256Ecdsa::public_from_secret(&secret);
+        storage.store::<K256Ecdsa>(&public, &secret)?;
+
+        assert!(matches!(
+            FileStorage::encrypted(temp_dir.path(), "wrong"),
+            Err(Error::WrongPassword)
+        ));
+
+        let storage = TypedStorage::new(FileStorage::new(temp_dir.path())?);
+        assert!(matches!(
+            storage.load::<K256Ecdsa>(&public),
+            Err(Error::PasswordRequired)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_storage_reads_plaintext_keys() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = TypedStorage::new(FileStorage::new(temp_dir.path())?);
+
+        let secret =
+            K256Ecdsa::generate_with_seed(None).map_err(IntoCryptoError::into_crypto_error)?;
+        let public = K256Ecdsa::public_from_secret(&secret);
+        storage.store::<K256Ecdsa>(&public, &secret)?;
+
+        let storage = TypedStorage::new(FileStorage::encrypted(temp_dir.path(), "hunter2")?);
+        assert_eq!(storage.load::<K256Ecdsa>(&public)?, Some(secret));
+
+        Ok(())
+    }
 }
@@ -5,6 +5,8 @@ use gadget_crypto::{KeyEncoding, KeyType, KeyTypeId};
 use gadget_std::{boxed::Box, vec::Vec};
 use serde::de::DeserializeOwned;
 
+#[cfg(feature = "std")]
+mod cipher;
 #[cfg(feature = "std")]
 mod fs;
 #[cfg(feature = "std")]
@@ -7,6 +7,7 @@ use syn::{ItemFn, Token};
 mod kw {
     syn::custom_keyword!(cores);
     syn::custom_keyword!(job_id);
+    syn::custom_keyword!(iterations);
 }
 
 /// `BenchmarkArgs` is a struct that holds the arguments for the `benchmark` macro.
@@ -19,6 +20,10 @@ pub(crate) struct BenchmarkArgs {
     ///
     /// `#[benchmark(job_id = 1)]`
     job_id: syn::LitInt,
+    /// The number of times the benchmark is run, 10 by default.
+    ///
+    /// `#[benchmark(iterations = 100)]`
+    iterations: Option<syn::LitInt>,
 }
 
 pub(crate) fn benchmark_impl(args: &BenchmarkArgs, input: &ItemFn) -> syn::Result<TokenStream> {
@@ -26,12 +31,16 @@ pub(crate) fn benchmark_impl(args: &BenchmarkArgs, input: &ItemFn) -> syn::Resul
     let job_id = &args.job_id;
     let original_name = &input.sig.ident;
     let name = format_ident!("{}_benchmark", original_name);
+    let test_name = format_ident!("__blueprint_benchmark_{}", original_name);
     let block = &input.block;
+    let with_iterations = args
+        .iterations
+        .as_ref()
+        .map(|iterations| quote! { .with_iterations(#iterations) });
     let expanded = quote! {
         #[doc(hidden)]
-        #[cfg(feature = "gadget")]
-        pub fn #name() -> gadget_sdk::benchmark::BenchmarkSummary {
-            use gadget_sdk::benchmark::*;
+        pub fn #name() -> ::blueprint_sdk::macros::ext::benchmarking::BenchmarkSummary {
+            use ::blueprint_sdk::macros::ext::benchmarking::*;
 
             let cores: usize = #cores;
             let rt = tokio::runtime::Builder::new_multi_thread()
@@ -40,13 +49,23 @@ pub(crate) fn benchmark_impl(args: &BenchmarkArgs, input: &ItemFn) -> syn::Resul
                 .enable_all()
                 .build()
                 .expect("build tokio runtime");
-            let _guard = rt.enter();
-            let b = Bencher::new(cores, TokioRuntime);
-            let _ = b.block_on(async move { #block });
+            let guard = rt.enter();
+            let mut b = Bencher::new(cores, TokioRuntime) #with_iterations;
+            b.iter(|| async #block);
             // shutdown the runtime.
+            drop(guard);
             rt.shutdown_background();
-            let summary = b.stop(stringify!(#original_name), #job_id);
-            return summary;
+            b.stop(stringify!(#original_name), #job_id)
+        }
+
+        // Discovered and run by `cargo tangle blueprint bench`
+        #[cfg(test)]
+        #[test]
+        #[ignore = "benchmark, run with `cargo tangle blueprint bench`"]
+        fn #test_name() {
+            let summary = #name();
+            ::blueprint_sdk::macros::ext::benchmarking::record(&summary, module_path!())
+                .expect("failed to record the benchmark summary");
         }
     };
     Ok(expanded.into())
@@ -56,6 +75,7 @@ impl Parse for BenchmarkArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut cores = None;
         let mut job_id = None;
+        let mut iterations = None;
         while !input.is_empty() {
             let lookahead = input.lookahead1();
             if lookahead.peek(kw::cores) {
@@ -66,6 +86,10 @@ impl Parse for BenchmarkArgs {
                 let _ = input.parse::<kw::job_id>()?;
                 let _ = input.parse::<Token![=]>()?;
                 job_id = Some(input.parse()?);
+            } else if lookahead.peek(kw::iterations) {
+                let _ = input.parse::<kw::iterations>()?;
+                let _ = input.parse::<Token![=]>()?;
+                iterations = Some(input.parse()?);
             } else if lookahead.peek(Token![,]) {
                 let _ = input.parse::<Token![,]>()?;
             } else {
@@ -77,6 +101,10 @@ impl Parse for BenchmarkArgs {
 
         let job_id = job_id.ok_or_else(|| input.error("Missing `job_id` argument in attribute"))?;
 
-        Ok(Self { cores, job_id })
+        Ok(Self {
+            cores,
+            job_id,
+            iterations,
+        })
     }
 }
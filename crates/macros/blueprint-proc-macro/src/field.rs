@@ -0,0 +1,456 @@
+use crate::shared::{ident_to_field_type, reject_unsupported_type, type_to_field_type};
+use gadget_blueprint_proc_macro_core::{FieldType, FieldTypeDefinition};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Field, Fields, LitStr, Type};
+
+/// `#[derive(BlueprintField)]` implementation
+pub(crate) fn derive_blueprint_field(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "generic types don't have a single `FieldType`, use a concrete type instead",
+        ));
+    }
+
+    let ContainerAttrs { name, transparent } = ContainerAttrs::parse(input)?;
+
+    let (field_type_expr, field_type) = match &input.data {
+        Data::Struct(data) => fields_field_type(&name, &data.fields, transparent)?,
+        Data::Enum(data) => {
+            if transparent {
+                return Err(syn::Error::new_spanned(
+                    data.enum_token,
+                    "`#[serde(transparent)]` is only supported on structs",
+                ));
+            }
+
+            // Unit variants are encoded as their name, so an enum without data is just a string
+            if data
+                .variants
+                .iter()
+                .all(|variant| matches!(variant.fields, Fields::Unit))
+            {
+                (quote! { FieldType::String }, FieldType::String)
+            } else {
+                let mut exprs = Vec::new();
+                let mut variants = Vec::new();
+                for variant in &data.variants {
+                    let variant_name = serde_name(variant.ident.to_string(), &variant.attrs)?;
+                    let (expr, field_type) = match variant.fields {
+                        Fields::Unit => (quote! { FieldType::String }, FieldType::String),
+                        _ => fields_field_type(&variant_name, &variant.fields, false)?,
+                    };
+                    exprs.push(quote! {
+                        (::std::string::String::from(#variant_name), ::std::boxed::Box::new(#expr))
+                    });
+                    variants.push((variant_name, Box::new(field_type)));
+                }
+
+                (
+                    quote! {
+                        FieldType::Enum(::std::string::String::from(#name), ::std::vec![#(#exprs),*])
+                    },
+                    FieldType::Enum(name.clone(), variants),
+                )
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "unions don't have a `FieldType`, use a struct or enum instead",
+            ))
+        }
+    };
+
+    let definition = FieldTypeDefinition {
+        name: name.clone(),
+        field_type,
+    };
+    let definition_str = serde_json::to_string(&definition).map_err(|err| {
+        syn::Error::new_spanned(
+            input,
+            format!("Failed to serialize field type to json: {err}"),
+        )
+    })?;
+    let definition_name = format_ident!("{}_FIELD_TYPE", screaming_snake_case(&ident.to_string()));
+    let ident_string = ident.to_string();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl ::blueprint_sdk::macros::core::BlueprintField for #ident {
+            fn field_type() -> ::blueprint_sdk::macros::core::FieldType {
+                use ::blueprint_sdk::macros::core::FieldType;
+                #field_type_expr
+            }
+        }
+
+        #[doc = "Field type definition for "]
+        #[doc = "[`"]
+        #[doc = #ident_string]
+        #[doc = "`]"]
+        #[automatically_derived]
+        #[doc(hidden)]
+        pub const #definition_name: &str = #definition_str;
+    })
+}
+
+/// The `FieldType` of a struct, or of an enum variant's data, named `name`
+///
+/// Mirrors how `gadget_blueprint_serde` encodes each kind of struct.
+fn fields_field_type(
+    name: &str,
+    fields: &Fields,
+    transparent: bool,
+) -> syn::Result<(TokenStream, FieldType)> {
+    if transparent && fields.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            fields,
+            "`#[serde(transparent)]` requires exactly one field",
+        ));
+    }
+
+    match fields {
+        Fields::Unit => Ok((quote! { FieldType::Void }, FieldType::Void)),
+        // Newtype and transparent structs are encoded as the type they contain
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            let ty = &unnamed.unnamed[0].ty;
+            Ok((field_type_tokens(ty)?, type_to_field_type(ty)?.ty))
+        }
+        Fields::Named(named) if transparent => {
+            let ty = &named.named[0].ty;
+            Ok((field_type_tokens(ty)?, type_to_field_type(ty)?.ty))
+        }
+        Fields::Unnamed(unnamed) => struct_field_type(
+            name,
+            unnamed
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, field)| Ok((format!("field_{i}"), field))),
+        ),
+        Fields::Named(named) => struct_field_type(
+            name,
+            named.named.iter().map(|field| {
+                let name = field_name(field)?;
+                Ok((name, field))
+            }),
+        ),
+    }
+}
+
+fn struct_field_type<'a>(
+    name: &str,
+    fields: impl Iterator<Item = syn::Result<(String, &'a Field)>>,
+) -> syn::Result<(TokenStream, FieldType)> {
+    let mut exprs = Vec::new();
+    let mut field_types = Vec::new();
+    for field in fields {
+        let (field_name, field) = field?;
+        let expr = field_type_tokens(&field.ty)?;
+        exprs.push(quote! {
+            (::std::string::String::from(#field_name), ::std::boxed::Box::new(#expr))
+        });
+        field_types.push((field_name, Box::new(type_to_field_type(&field.ty)?.ty)));
+    }
+
+    Ok((
+        quote! {
+            FieldType::Struct(::std::string::String::from(#name), ::std::vec![#(#exprs),*])
+        },
+        FieldType::Struct(name.to_string(), field_types),
+    ))
+}
+
+/// Generates an expression for the `FieldType` of `ty`, deferring to `BlueprintField` for user-defined types
+fn field_type_tokens(ty: &Type) -> syn::Result<TokenStream> {
+    match ty {
+        Type::Array(arr) => {
+            let len = arr
+                .len
+                .to_token_stream()
+                .to_string()
+                .parse::<u64>()
+                .map_err(|_| {
+                    syn::Error::new_spanned(&arr.len, "array length must be a constant")
+                })?;
+            let elem = field_type_tokens(&arr.elem)?;
+            Ok(quote! { FieldType::Array(#len, ::std::boxed::Box::new(#elem)) })
+        }
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Ok(quote! { FieldType::Void }),
+        Type::Tuple(tuple) => {
+            let elems = tuple
+                .elems
+                .iter()
+                .map(field_type_tokens)
+                .collect::<syn::Result<Vec<_>>>()?;
+            Ok(quote! { FieldType::Tuple(::std::vec![#(#elems),*]) })
+        }
+        Type::Path(path) => {
+            let seg = path.path.segments.last().ok_or_else(|| {
+                syn::Error::new_spanned(path, "path must have at least one segment")
+            })?;
+            reject_unsupported_type(&seg.ident)?;
+
+            match &seg.arguments {
+                syn::PathArguments::None => match ident_to_field_type(&seg.ident) {
+                    Ok(field_type) => {
+                        let variant = format_ident!("{field_type:?}");
+                        Ok(quote! { FieldType::#variant })
+                    }
+                    Err(_) => Ok(blueprint_field_call(ty)),
+                },
+                syn::PathArguments::AngleBracketed(args)
+                    if args.args.len() == 1 && (seg.ident == "Vec" || seg.ident == "Option") =>
+                {
+                    let syn::GenericArgument::Type(inner_ty) = &args.args[0] else {
+                        return Err(syn::Error::new_spanned(args, "unsupported complex type"));
+                    };
+                    let inner = field_type_tokens(inner_ty)?;
+                    if seg.ident == "Vec" {
+                        Ok(quote! { FieldType::List(::std::boxed::Box::new(#inner)) })
+                    } else {
+                        Ok(quote! { FieldType::Optional(::std::boxed::Box::new(#inner)) })
+                    }
+                }
+                _ => Ok(blueprint_field_call(ty)),
+            }
+        }
+        Type::Reference(_) => Err(syn::Error::new_spanned(
+            ty,
+            "references can't be decoded from a job call, use an owned type instead",
+        )),
+        _ => Err(syn::Error::new_spanned(ty, "unsupported field type")),
+    }
+}
+
+fn blueprint_field_call(ty: &Type) -> TokenStream {
+    quote::quote_spanned! {ty.span()=>
+        <#ty as ::blueprint_sdk::macros::core::BlueprintField>::field_type()
+    }
+}
+
+struct ContainerAttrs {
+    name: String,
+    transparent: bool,
+}
+
+impl ContainerAttrs {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut attrs = ContainerAttrs {
+            name: input.ident.to_string(),
+            transparent: false,
+        };
+
+        for attr in serde_attrs(&input.attrs) {
+            attr.parse_nested_meta(|meta| {
+                match meta_name(&meta).as_str() {
+                    "rename" => attrs.name = meta.value()?.parse::<LitStr>()?.value(),
+                    "transparent" => attrs.transparent = true,
+                    "deny_unknown_fields" | "crate" | "bound" | "default" | "expecting" => {
+                        skip_meta_value(&meta)?;
+                    }
+                    _ => return Err(unsupported_serde_attr(&meta)),
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// The name a field is encoded with, following `#[serde(rename = "...")]`
+fn field_name(field: &Field) -> syn::Result<String> {
+    let name = field
+        .ident
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    serde_name(name, &field.attrs)
+}
+
+/// The name a field or enum variant is encoded with, following `#[serde(rename = "...")]`
+fn serde_name(mut name: String, attrs: &[Attribute]) -> syn::Result<String> {
+    for attr in serde_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            match meta_name(&meta).as_str() {
+                "rename" => name = meta.value()?.parse::<LitStr>()?.value(),
+                "default" | "alias" | "bound" => skip_meta_value(&meta)?,
+                _ => return Err(unsupported_serde_attr(&meta)),
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(name)
+}
+
+fn serde_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("serde"))
+}
+
+fn meta_name(meta: &syn::meta::ParseNestedMeta<'_>) -> String {
+    meta.path
+        .get_ident()
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
+
+fn unsupported_serde_attr(meta: &syn::meta::ParseNestedMeta<'_>) -> syn::Error {
+    meta.error("this serde attribute changes the encoding in a way `BlueprintField` can't describe")
+}
+
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta<'_>) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        let _ = meta.value()?.parse::<syn::Expr>()?;
+    }
+    Ok(())
+}
+
+/// Convert a `PascalCase` string to `SCREAMING_SNAKE_CASE`
+fn screaming_snake_case(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            out.push('_');
+        }
+        out.extend(c.to_uppercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screaming_snake_case_works() {
+        let input = ["Point", "KeygenArgs", "A"];
+        let expected = ["POINT", "KEYGEN_ARGS", "A"];
+
+        for (i, e) in input.iter().zip(expected.iter()) {
+            assert_eq!(screaming_snake_case(i), *e);
+        }
+    }
+
+    #[test]
+    fn named_structs_are_described() {
+        let input: DeriveInput = syn::parse_quote! {
+            struct Point {
+                x: u64,
+                #[serde(rename = "why")]
+                y: Option<u64>,
+                inner: Inner,
+            }
+        };
+
+        let tokens = derive_blueprint_field(&input).unwrap().to_string();
+        let expected = serde_json::to_string(&FieldTypeDefinition {
+            name: String::from("Point"),
+            field_type: FieldType::Struct(
+                String::from("Point"),
+                vec![
+                    (String::from("x"), Box::new(FieldType::Uint64)),
+                    (
+                        String::from("why"),
+                        Box::new(FieldType::Optional(Box::new(FieldType::Uint64))),
+                    ),
+                    (
+                        String::from("inner"),
+                        Box::new(FieldType::Struct(String::from("Inner"), Vec::new())),
+                    ),
+                ],
+            ),
+        })
+        .unwrap();
+
+        assert!(tokens.contains(&format!("{expected:?}")));
+        assert!(tokens.contains("POINT_FIELD_TYPE"));
+    }
+
+    #[test]
+    fn enums_are_described() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum Shape {
+                Empty,
+                Circle(u32),
+                #[serde(rename = "line")]
+                Line(u8, String),
+                Rect { width: u16, height: u16 },
+            }
+        };
+
+        let tokens = derive_blueprint_field(&input).unwrap().to_string();
+        let expected = serde_json::to_string(&FieldTypeDefinition {
+            name: String::from("Shape"),
+            field_type: FieldType::Enum(
+                String::from("Shape"),
+                vec![
+                    (String::from("Empty"), Box::new(FieldType::String)),
+                    (String::from("Circle"), Box::new(FieldType::Uint32)),
+                    (
+                        String::from("line"),
+                        Box::new(FieldType::Struct(
+                            String::from("line"),
+                            vec![
+                                (String::from("field_0"), Box::new(FieldType::Uint8)),
+                                (String::from("field_1"), Box::new(FieldType::String)),
+                            ],
+                        )),
+                    ),
+                    (
+                        String::from("Rect"),
+                        Box::new(FieldType::Struct(
+                            String::from("Rect"),
+                            vec![
+                                (String::from("width"), Box::new(FieldType::Uint16)),
+                                (String::from("height"), Box::new(FieldType::Uint16)),
+                            ],
+                        )),
+                    ),
+                ],
+            ),
+        })
+        .unwrap();
+
+        assert!(tokens.contains(&format!("{expected:?}")));
+    }
+
+    #[test]
+    fn unit_enums_are_strings() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum Curve {
+                Ed25519,
+                #[serde(rename = "secp256k1")]
+                Secp256k1,
+            }
+        };
+
+        let tokens = derive_blueprint_field(&input).unwrap().to_string();
+        let expected = serde_json::to_string(&FieldTypeDefinition {
+            name: String::from("Curve"),
+            field_type: FieldType::String,
+        })
+        .unwrap();
+
+        assert!(tokens.contains(&format!("{expected:?}")));
+    }
+
+    #[test]
+    fn unsupported_types_are_rejected() {
+        let cases: [DeriveInput; 5] = [
+            syn::parse_quote! { struct A { x: usize } },
+            syn::parse_quote! { struct A { x: std::collections::HashMap<u8, u8> } },
+            syn::parse_quote! { struct A<T> { x: T } },
+            syn::parse_quote! { #[serde(untagged)] enum A { X(u8) } },
+            syn::parse_quote! { struct A { #[serde(flatten)] x: B } },
+        ];
+
+        for input in cases {
+            assert!(derive_blueprint_field(&input).is_err());
+        }
+    }
+}
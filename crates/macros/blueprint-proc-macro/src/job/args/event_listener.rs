@@ -9,12 +9,17 @@ use quote::{format_ident, quote};
 #[cfg(feature = "tangle")]
 use std::str::FromStr;
 use syn::parse::{Parse, ParseBuffer, ParseStream};
-#[cfg(any(not(feature = "evm"), not(feature = "tangle")))]
+#[cfg(any(
+    not(feature = "evm"),
+    not(feature = "tangle"),
+    not(feature = "webhook")
+))]
 use syn::spanned::Spanned;
 use syn::{Index, Token, Type};
 
 const EVM_EVENT_LISTENER_TAG: &str = "EvmContractEventListener";
 const TANGLE_EVENT_LISTENER_TAG: &str = "TangleEventListener";
+const WEBHOOK_EVENT_LISTENER_TAG: &str = "WebhookEventListener";
 
 /// Defines custom keywords for defining Job arguments
 mod kw {
@@ -61,6 +66,8 @@ pub enum ListenerType {
     Evm,
     #[cfg(feature = "tangle")]
     Tangle,
+    #[cfg(feature = "webhook")]
+    Webhook,
     Custom,
 }
 
@@ -221,6 +228,15 @@ impl Parse for EventListenerArgs {
 
                 #[cfg(feature = "tangle")]
                 ListenerType::Tangle
+            } else if ty_str.contains(WEBHOOK_EVENT_LISTENER_TAG) {
+                #[cfg(not(feature = "webhook"))]
+                return Err(syn::Error::new(
+                    listener.span(),
+                    "Webhook event listeners require the `webhook` feature to be enabled",
+                ));
+
+                #[cfg(feature = "webhook")]
+                ListenerType::Webhook
             } else {
                 ListenerType::Custom
             };
@@ -281,6 +297,13 @@ impl EventListenerArgs {
                         }
 					}
 
+					// Webhook params are decoded by name, see `get_webhook_job_processor_wrapper`
+					#[cfg(feature = "webhook")]
+					ListenerType::Webhook => {
+                        let _ = param_ty;
+						quote! {}
+					}
+
 					// All other event listeners will return just one type
 					ListenerType::Custom => {
 						quote! {
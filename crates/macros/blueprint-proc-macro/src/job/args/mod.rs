@@ -7,7 +7,7 @@ use proc_macro2::Ident;
 use std::collections::HashSet;
 use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
-use syn::{LitInt, Token};
+use syn::{Expr, LitInt, Token};
 
 /// Defines custom keywords for defining Job arguments
 mod kw {
@@ -17,6 +17,14 @@ mod kw {
     syn::custom_keyword!(event_listener);
     syn::custom_keyword!(instance);
     syn::custom_keyword!(skip_codegen);
+    syn::custom_keyword!(max_concurrency);
+    syn::custom_keyword!(ordering_key);
+    syn::custom_keyword!(max_retries);
+    syn::custom_keyword!(dead_letters);
+    syn::custom_keyword!(timeout_secs);
+    syn::custom_keyword!(layers);
+    syn::custom_keyword!(stream);
+    syn::custom_keyword!(split_result);
 }
 
 /// `JobArgs` type to handle parsing of attributes
@@ -39,6 +47,40 @@ pub(crate) struct JobArgs {
     /// this is useful if the developer want to impl a custom event handler
     /// for this job.
     pub skip_codegen: bool,
+    /// Optional: Maximum number of job calls processed at once, defaults to 1.
+    /// `#[job(max_concurrency = 4)]`
+    pub max_concurrency: Option<LitInt>,
+    /// Optional: Job calls that map to the same key are processed in the order they were received.
+    /// Takes a function from the pre-processed event to a `u64`.
+    /// `#[job(max_concurrency = 4, ordering_key = |event| event.service_id)]`
+    pub ordering_key: Option<Expr>,
+    /// Optional: Number of times a failed job call is retried, with exponential back-off.
+    /// `#[job(max_retries = 3)]`
+    pub max_retries: Option<LitInt>,
+    /// Optional: Keep job calls that fail every retry in a dead-letter queue under the gadget data
+    /// directory, so they can be replayed. Requires `max_retries`, and a serializable pre-processed event
+    /// for listeners other than Tangle.
+    /// `#[job(max_retries = 3, dead_letters)]`
+    pub dead_letters: bool,
+    /// Optional: Seconds after which a job call is cancelled, per attempt.
+    /// `#[job(timeout_secs = 60)]`
+    pub timeout_secs: Option<LitInt>,
+    /// Optional: Middleware wrapping each stage of the job's calls, outermost first.
+    /// `#[job(layers(TraceLayer, MetricsLayer))]`
+    pub layers: Vec<Expr>,
+    /// Optional: Publish the updates the job emits while it runs, with the given `UpdatePublisher`.
+    /// `#[job(stream)]`
+    /// `#[job(stream(MyPublisher::new()))]`
+    pub stream: Option<StreamArgs>,
+    /// Optional: Submit each element of the job's tuple result as a separate field.
+    /// `#[job(split_result)]`
+    pub split_result: Option<kw::split_result>,
+}
+
+/// Options for a job that streams updates before its final result
+pub(crate) struct StreamArgs {
+    /// Publishes the job's updates, defaults to `LogPublisher`
+    pub publisher: Option<Expr>,
 }
 
 impl MacroExt for JobArgs {
@@ -53,6 +95,15 @@ impl Parse for JobArgs {
         let mut result = None;
         let mut id = None;
         let mut skip_codegen = false;
+        let mut max_concurrency = None;
+        let mut ordering_key = None;
+        let mut max_retries = None;
+        let mut dead_letters = None;
+        let mut timeout_secs = None;
+        let mut layers = Vec::new();
+        let mut stream = None;
+        let mut stream_keyword = None;
+        let mut split_result = None;
         let mut event_listener = EventListenerArgs { listeners: vec![] };
 
         while !input.is_empty() {
@@ -70,6 +121,56 @@ impl Parse for JobArgs {
             } else if lookahead.peek(kw::skip_codegen) {
                 let _ = input.parse::<kw::skip_codegen>()?;
                 skip_codegen = true;
+            } else if lookahead.peek(kw::max_concurrency) {
+                let _ = input.parse::<kw::max_concurrency>()?;
+                let _ = input.parse::<Token![=]>()?;
+                let limit: LitInt = input.parse()?;
+                if limit.base10_parse::<usize>()? == 0 {
+                    return Err(syn::Error::new_spanned(
+                        limit,
+                        "`max_concurrency` must be at least 1",
+                    ));
+                }
+                max_concurrency = Some(limit);
+            } else if lookahead.peek(kw::ordering_key) {
+                let _ = input.parse::<kw::ordering_key>()?;
+                let _ = input.parse::<Token![=]>()?;
+                ordering_key = Some(input.parse()?);
+            } else if lookahead.peek(kw::max_retries) {
+                let _ = input.parse::<kw::max_retries>()?;
+                let _ = input.parse::<Token![=]>()?;
+                let retries: LitInt = input.parse()?;
+                let _ = retries.base10_parse::<u32>()?;
+                max_retries = Some(retries);
+            } else if lookahead.peek(kw::dead_letters) {
+                dead_letters = Some(input.parse::<kw::dead_letters>()?);
+            } else if lookahead.peek(kw::timeout_secs) {
+                let _ = input.parse::<kw::timeout_secs>()?;
+                let _ = input.parse::<Token![=]>()?;
+                let timeout: LitInt = input.parse()?;
+                if timeout.base10_parse::<u64>()? == 0 {
+                    return Err(syn::Error::new_spanned(
+                        timeout,
+                        "`timeout_secs` must be at least 1",
+                    ));
+                }
+                timeout_secs = Some(timeout);
+            } else if lookahead.peek(kw::layers) {
+                let _ = input.parse::<kw::layers>()?;
+                let content;
+                let _ = syn::parenthesized!(content in input);
+                layers.extend(content.parse_terminated(Expr::parse, Token![,])?);
+            } else if lookahead.peek(kw::stream) {
+                stream_keyword = Some(input.parse::<kw::stream>()?);
+                let mut publisher = None;
+                if input.peek(syn::token::Paren) {
+                    let content;
+                    let _ = syn::parenthesized!(content in input);
+                    publisher = Some(content.parse()?);
+                }
+                stream = Some(StreamArgs { publisher });
+            } else if lookahead.peek(kw::split_result) {
+                split_result = Some(input.parse()?);
             } else if lookahead.peek(Token![,]) {
                 let _ = input.parse::<Token![,]>()?;
             } else if lookahead.peek(kw::event_listener) {
@@ -100,12 +201,40 @@ impl Parse for JobArgs {
             return Err(input.error("Only one event listener is currently allowed"));
         }
 
+        #[cfg(feature = "tangle")]
+        let streams_supported = event_listener.has_tangle();
+        #[cfg(not(feature = "tangle"))]
+        let streams_supported = false;
+        if let Some(keyword) = stream_keyword.filter(|_| !streams_supported) {
+            return Err(syn::Error::new_spanned(
+                keyword,
+                "`stream` is only supported for Tangle jobs",
+            ));
+        }
+
+        if let Some(keyword) = &dead_letters {
+            if max_retries.is_none() {
+                return Err(syn::Error::new_spanned(
+                    keyword,
+                    "`dead_letters` requires `max_retries`",
+                ));
+            }
+        }
+
         Ok(JobArgs {
             id,
             params,
             result,
             skip_codegen,
             event_listener,
+            max_concurrency,
+            ordering_key,
+            max_retries,
+            dead_letters: dead_letters.is_some(),
+            timeout_secs,
+            layers,
+            stream,
+            split_result,
         })
     }
 }
@@ -1,5 +1,5 @@
 use super::args::EventListenerArgs;
-use super::declared_params_to_field_types;
+use super::{declared_params_to_field_types, IsResultType};
 use crate::shared::{get_non_job_arguments, get_return_type_wrapper};
 use indexmap::IndexMap;
 use proc_macro2::{Span, TokenStream};
@@ -160,19 +160,39 @@ pub(crate) fn get_evm_job_processor_wrapper(
     let params = declared_params_to_field_types(params, param_types)?;
     let params_tokens = event_listeners.get_param_name_tokenstream(&params);
 
+    // Reuse the result recorded for this call, if the log was delivered before
+    let cached_result = if return_type.is_result_type() {
+        quote! { Ok(cached) }
+    } else {
+        quote! { cached }
+    };
+    let job_call = |call: TokenStream| {
+        quote! {
+            let res = match call_key.as_ref().and_then(|key| IDEMPOTENCY.get().unwrap().result(key)) {
+                Some(cached) => {
+                    ::blueprint_sdk::macros::ext::logging::info!("Reusing the recorded result for call {}", call_key.expect("Only found with a key"));
+                    #cached_result
+                }
+                None => #call,
+            };
+        }
+    };
+
     let job_processor_call = if params_tokens.is_empty() {
         let second_param = ordered_inputs
             .pop()
             .ok_or_else(|| syn::Error::new(Span::call_site(), "Context type required"))?;
+        let call_raw = job_call(quote! { #fn_name_ident (param0, #second_param) #asyncness });
         quote! {
             // If no args are specified, assume this job has no parameters and thus takes in the raw event
-            let res = #fn_name_ident (param0, #second_param) #asyncness;
+            #call_raw
         }
     } else {
+        let call_with_args = job_call(quote! { #fn_name_ident (#(#ordered_inputs),*) #asyncness });
         quote! {
             let inputs = param0;
             #(#params_tokens)*
-            let res = #fn_name_ident (#(#ordered_inputs),*) #asyncness;
+            #call_with_args
         }
     };
 
@@ -186,9 +206,38 @@ pub(crate) fn get_evm_job_processor_wrapper(
         .collect::<Vec<_>>();
 
     Ok(quote! {
-        move |param0: (#(#inner_param_type)*)| async move {
+        move |(call_key, param0): (_, (#(#inner_param_type)*))| async move {
             #job_processor_call
-            #job_processor_call_return
+
+            let output = #job_processor_call_return;
+            if let (Some(key), Ok(result)) = (&call_key, &output) {
+                IDEMPOTENCY.get().unwrap().record_result(key, result);
+            }
+            output.map(|result| (call_key, result))
         }
     })
 }
+
+/// Wraps an EVM job's `post_processor`, so that each result is only handled once, and is marked as
+/// submitted once handled
+pub(crate) fn evm_post_processor(postprocessor: Option<&Type>) -> TokenStream {
+    let post_process = match postprocessor {
+        Some(postprocessor) => quote! { #postprocessor(job_result).await?; },
+        None => quote! { let _ = job_result; },
+    };
+    quote! {
+        |(call_key, job_result): (Option<::blueprint_sdk::macros::ext::event_listeners::core::idempotency::CallKey>, _)| async move {
+            let store = IDEMPOTENCY.get().unwrap();
+            if call_key.as_ref().is_some_and(|key| store.is_confirmed(key)) {
+                ::blueprint_sdk::macros::ext::logging::info!("Result for {} was already handled, skipping", call_key.expect("Only confirmed with a key"));
+                return Ok(());
+            }
+
+            #post_process
+            if let Some(key) = &call_key {
+                store.mark_confirmed(key);
+            }
+            Ok(())
+        }
+    }
+}
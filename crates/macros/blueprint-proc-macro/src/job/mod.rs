@@ -1,16 +1,24 @@
 mod args;
-pub(crate) use args::{EventListenerArgs, JobArgs, ListenerType};
+pub(crate) use args::{EventListenerArgs, JobArgs, ListenerType, StreamArgs};
 
 #[cfg(feature = "evm")]
 mod evm;
 #[cfg(feature = "evm")]
-use evm::{generate_evm_specific_impl, get_evm_instance_data, get_evm_job_processor_wrapper};
+use evm::{
+    evm_post_processor, generate_evm_specific_impl, get_evm_instance_data,
+    get_evm_job_processor_wrapper,
+};
 
 #[cfg(feature = "tangle")]
 mod tangle;
 #[cfg(feature = "tangle")]
 use tangle::{generate_tangle_specific_impl, get_tangle_job_processor_wrapper};
 
+#[cfg(feature = "webhook")]
+mod webhook;
+#[cfg(feature = "webhook")]
+use webhook::get_webhook_job_processor_wrapper;
+
 use crate::shared::{self, get_return_type_wrapper, pascal_case, type_to_field_type, MacroExt};
 
 use gadget_blueprint_proc_macro_core::{FieldType, JobDefinition, JobMetadata};
@@ -62,17 +70,35 @@ impl JobDef {
         let params_type = declared_params_to_field_types(&self.args.params, &param_map)?;
 
         let result = get_return_type(&self.input);
-        let result_type = self.args.result_to_field_types(&result)?;
+        let mut result_type = self.args.result_to_field_types(&result)?;
+        if let Some(split_result) = &self.args.split_result {
+            if matches!(self.args.return_type(), ResultsKind::Infered) {
+                result_type = split_tuple_result(result_type);
+            }
+            if result_type.len() < 2 {
+                return Err(syn::Error::new_spanned(
+                    split_result,
+                    "`split_result` requires a tuple result with at least two elements",
+                ));
+            }
+        }
+        let result_options = ResultOptions {
+            multiple_outputs: self.args.split_result.is_some(),
+            stream: self.args.stream.as_ref(),
+        };
 
         let job_id = &self.args.id;
         let job_const_block =
             generate_job_const_block(&self.input, params_type, result_type, job_id)?;
+        let field_type_assertions = self.generate_field_type_assertions(&param_map, &result);
 
         if self.args.skip_codegen {
             let input = &self.input;
             return Ok(quote! {
                 #job_const_block
 
+                #field_type_assertions
+
                 #[allow(unused_variables)]
                 #input
             });
@@ -84,6 +110,9 @@ impl JobDef {
             &self.args.event_listener,
             &param_map,
             &self.args.params,
+            &generate_event_flow_config(&self.args, &self.input),
+            &result_options,
+            self.args.dead_letters,
         )?;
 
         // Generate Event Workflow
@@ -109,6 +138,8 @@ impl JobDef {
         Ok(quote! {
             #job_const_block
 
+            #field_type_assertions
+
             #autogen_struct
 
             #(#event_listener_gen)*
@@ -121,6 +152,37 @@ impl JobDef {
     }
 }
 
+impl JobDef {
+    /// Checks that the user-defined types in the job's parameters and results implement `BlueprintField`
+    ///
+    /// EVM jobs are exempt, since their inputs are decoded from contract events rather than `Field`s.
+    fn generate_field_type_assertions(
+        &self,
+        param_map: &IndexMap<Ident, Type>,
+        result: &Type,
+    ) -> TokenStream {
+        #[cfg(feature = "evm")]
+        if self.args.event_listener.has_evm() {
+            return TokenStream::new();
+        }
+
+        let result_types = match self.args.return_type() {
+            ResultsKind::Infered => core::slice::from_ref(result),
+            ResultsKind::Types(types) => types.as_slice(),
+        };
+        let custom_types = self
+            .args
+            .params
+            .iter()
+            .filter_map(|param| param_map.get(param))
+            .chain(result_types)
+            .flat_map(shared::custom_field_types)
+            .collect::<Vec<_>>();
+
+        shared::generate_field_type_assertions(&custom_types)
+    }
+}
+
 /// Job Macro implementation
 pub(crate) fn job_impl(args: JobArgs, input: ItemFn) -> syn::Result<TokenStream> {
     let def = JobDef { args, input };
@@ -128,6 +190,36 @@ pub(crate) fn job_impl(args: JobArgs, input: ItemFn) -> syn::Result<TokenStream>
     def.generate()
 }
 
+/// How the results of a job are produced and submitted
+#[derive(Default)]
+pub(crate) struct ResultOptions<'a> {
+    /// The job's result is a tuple, submitted as one field per element, see `#[job(split_result)]`
+    pub multiple_outputs: bool,
+    /// The job streams updates before its final result
+    pub stream: Option<&'a StreamArgs>,
+}
+
+/// A job that returns a tuple has a result for each of its elements
+fn split_tuple_result(result: Vec<ParameterType>) -> Vec<ParameterType> {
+    if let [ParameterType {
+        ty: FieldType::Tuple(elems),
+        span,
+    }] = result.as_slice()
+    {
+        if elems.len() > 1 {
+            return elems
+                .iter()
+                .map(|ty| ParameterType {
+                    ty: ty.clone(),
+                    span: *span,
+                })
+                .collect();
+        }
+    }
+
+    result
+}
+
 pub fn get_return_type(input: &ItemFn) -> Type {
     match input.sig.output.clone() {
         syn::ReturnType::Type(_, result) => *result,
@@ -212,6 +304,9 @@ pub(crate) fn generate_event_workflow_tokenstream(
     event_listeners: &EventListenerArgs,
     param_types: &IndexMap<Ident, Type>,
     params: &[Ident],
+    event_flow_config: &TokenStream,
+    result_options: &ResultOptions<'_>,
+    dead_letters: bool,
 ) -> syn::Result<(Vec<TokenStream>, Vec<TokenStream>)> {
     let return_type = get_return_type(input);
 
@@ -222,6 +317,8 @@ pub(crate) fn generate_event_workflow_tokenstream(
     let (fn_name_string, _job_def_name, job_id_name) = get_job_id_field_name(input);
     #[cfg(not(feature = "tangle"))]
     let (fn_name_string, _job_def_name, _job_id_name) = get_job_id_field_name(input);
+    #[cfg(not(feature = "tangle"))]
+    let _ = result_options;
 
     // Generate Event Listener
     let mut event_listener_gen = vec![];
@@ -284,7 +381,7 @@ pub(crate) fn generate_event_workflow_tokenstream(
         }
 
         event_listener_calls.push(quote! {
-            listeners.push(#listener_function_name(&self).await.expect("Event listener already initialized"));
+            listeners.push(#listener_function_name(&self, settings).await.expect("Event listener already initialized"));
         });
 
         let pre_processor_function = if let Some(preprocessor) = &listener_meta.pre_processor {
@@ -297,6 +394,27 @@ pub(crate) fn generate_event_workflow_tokenstream(
                 }
             }
         };
+        // EVM jobs pass the key of the log that triggered them along with its inputs, so their results
+        // are recorded like those of Tangle jobs
+        let (pre_processor_function, listener_setup) = match listener_meta.listener_type {
+            #[cfg(feature = "evm")]
+            ListenerType::Evm => {
+                let store_name = struct_name.to_string();
+                (
+                    quote! {
+                        ::blueprint_sdk::macros::ext::event_listeners::evm::keyed_pre_processor(chain_id, #pre_processor_function)
+                    },
+                    quote! {
+                        let chain_id = listener.chain_id();
+                        static IDEMPOTENCY: ::std::sync::OnceLock<::blueprint_sdk::macros::ext::event_listeners::core::idempotency::IdempotencyStore> = ::std::sync::OnceLock::new();
+                        let _ = IDEMPOTENCY.set(
+                            ::blueprint_sdk::macros::ext::event_listeners::core::idempotency::IdempotencyStore::new(settings.data_dir.as_deref(), #store_name)
+                        );
+                    },
+                )
+            }
+            _ => (pre_processor_function, TokenStream::new()),
+        };
 
         // The job_processor is just the job function. Since it may contain multiple params, we need a new function to call it.
         let job_processor_wrapper = match listener_meta.listener_type {
@@ -310,6 +428,7 @@ pub(crate) fn generate_event_workflow_tokenstream(
                 &asyncness,
                 &return_type,
                 ctx_pos_in_ordered_inputs,
+                result_options.stream,
             )?,
 
             #[cfg(feature = "evm")]
@@ -323,6 +442,16 @@ pub(crate) fn generate_event_workflow_tokenstream(
                 &return_type,
             )?,
 
+            #[cfg(feature = "webhook")]
+            ListenerType::Webhook => get_webhook_job_processor_wrapper(
+                params,
+                param_types,
+                &ordered_inputs,
+                fn_name_ident,
+                &asyncness,
+                &return_type,
+            )?,
+
             ListenerType::Custom => {
                 let job_processor_call_return = get_return_type_wrapper(&return_type, None);
 
@@ -339,25 +468,41 @@ pub(crate) fn generate_event_workflow_tokenstream(
             match listener_meta.listener_type {
                 #[cfg(feature = "tangle")]
                 ListenerType::Tangle => {
+                    let results = if result_options.multiple_outputs {
+                        quote! { ::blueprint_sdk::macros::ext::blueprint_serde::Outputs(job_result) }
+                    } else {
+                        quote! { job_result }
+                    };
                     quote! {
                         |(mut client_context, job_result)| async move {
                             let ctx = CTX.get().unwrap();
                             let call_id = ::blueprint_sdk::macros::ext::contexts::services::ServicesContext::get_call_id(&mut client_context).expect("Tangle call ID was not injected into context");
+                            let call_key = ::blueprint_sdk::macros::ext::event_listeners::core::idempotency::CallKey::tangle(ctx.service_id, #job_id_name, call_id);
+                            if ctx.idempotency.is_confirmed(&call_key) {
+                                ::blueprint_sdk::macros::ext::logging::info!("Result for {call_key} was already submitted, skipping");
+                                return Ok(());
+                            }
+
                             let tangle_job_result = ::blueprint_sdk::macros::ext::event_listeners::tangle::events::TangleResult::<_> {
-                                results: job_result,
+                                results: #results,
                                 service_id: ctx.service_id,
                                 call_id,
                                 client: ctx.client.subxt_client().clone(),
                                 signer: ctx.signer.clone(),
                             };
 
-                            #postprocessor(tangle_job_result).await
+                            #postprocessor(tangle_job_result).await?;
+                            ctx.idempotency.mark_confirmed(&call_key);
+                            Ok(())
                         }
                     }
                 }
 
                 #[cfg(feature = "evm")]
-                ListenerType::Evm => {
+                ListenerType::Evm => evm_post_processor(Some(postprocessor)),
+
+                #[cfg(feature = "webhook")]
+                ListenerType::Webhook => {
                     quote! { #postprocessor }
                 }
 
@@ -366,8 +511,12 @@ pub(crate) fn generate_event_workflow_tokenstream(
                 }
             }
         } else {
-            // no-op default
-            quote! { |_evt| async move { Ok(()) } }
+            match listener_meta.listener_type {
+                #[cfg(feature = "evm")]
+                ListenerType::Evm => evm_post_processor(None),
+                // no-op default
+                _ => quote! { |_evt| async move { Ok(()) } },
+            }
         };
 
         let context_declaration = match listener_meta.listener_type {
@@ -391,13 +540,41 @@ pub(crate) fn generate_event_workflow_tokenstream(
                 }
             }
 
+            #[cfg(feature = "webhook")]
+            ListenerType::Webhook => {
+                quote! { let context = #field_in_self_getter; }
+            }
+
             ListenerType::Custom => {
                 quote! { let context = #field_in_self_getter; }
             }
         };
 
+        // Tangle events hold a client and signer, so only the job call itself is stored
+        let (dead_letter_codec, register_dead_letters) = if dead_letters {
+            let codec = match listener_meta.listener_type {
+                #[cfg(feature = "tangle")]
+                ListenerType::Tangle => quote! {
+                    ::blueprint_sdk::macros::ext::event_listeners::tangle::events::TangleDeadLetterCodec::new(context.clone())
+                },
+                _ => {
+                    quote! { ::blueprint_sdk::macros::ext::event_listeners::core::dead_letter::JsonCodec }
+                }
+            };
+            (
+                quote! { let dead_letter_codec = #codec; },
+                quote! {
+                    if let Some(replayer) = event_workflow.dead_letter_replayer() {
+                        settings.dead_letters.insert(#fn_name_string, replayer);
+                    }
+                },
+            )
+        } else {
+            (TokenStream::new(), TokenStream::new())
+        };
+
         let next_listener = quote! {
-            async fn #listener_function_name (ctx: &#autogen_struct_name) -> Option<::blueprint_sdk::macros::ext::tokio::sync::oneshot::Receiver<Result<(), Box<dyn ::core::error::Error + Send>>>> {
+            async fn #listener_function_name (ctx: &#autogen_struct_name, settings: &::blueprint_sdk::macros::ext::event_listeners::core::HandlerSettings) -> Option<::blueprint_sdk::macros::ext::tokio::sync::oneshot::Receiver<Result<(), Box<dyn ::core::error::Error + Send>>>> {
                 static ONCE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
                 if !ONCE.fetch_or(true, std::sync::atomic::Ordering::Relaxed) {
                     let (tx, rx) = ::blueprint_sdk::macros::ext::tokio::sync::oneshot::channel();
@@ -412,12 +589,15 @@ pub(crate) fn generate_event_workflow_tokenstream(
                     let job_processor = #job_processor_wrapper;
 
                     let listener = <#listener as ::blueprint_sdk::macros::ext::event_listeners::core::EventListener<_, _>>::new(&context).await.expect("Failed to create event listener");
+                    #listener_setup
+                    #dead_letter_codec
                     let mut event_workflow = ::blueprint_sdk::macros::ext::event_listeners::core::executor::EventFlowWrapper::new(
                         listener,
                         #pre_processor_function,
                         job_processor,
                         #post_processor_function,
-                    );
+                    )#event_flow_config;
+                    #register_dead_letters
 
                     let task = async move {
                         let res = ::blueprint_sdk::macros::ext::event_listeners::core::executor::EventFlowExecutor::event_loop(&mut event_workflow).await.map_err(|e| Box::new(e) as Box<dyn ::core::error::Error + Send>);
@@ -437,6 +617,49 @@ pub(crate) fn generate_event_workflow_tokenstream(
     Ok((event_listener_gen, event_listener_calls))
 }
 
+/// Generates the builder calls that configure how the `EventFlowWrapper` processes job calls
+fn generate_event_flow_config(args: &JobArgs, input: &ItemFn) -> TokenStream {
+    let (fn_name_string, _job_def_name, job_id_name) = get_job_id_field_name(input);
+    let job_metadata = quote! {
+        .with_job_metadata(u64::from(#job_id_name), #fn_name_string)
+        .with_layers(&settings.layers)
+    };
+    let layers = args.layers.iter().map(|layer| {
+        quote! { .with_layer(#layer) }
+    });
+    let max_concurrency = args.max_concurrency.as_ref().map(|limit| {
+        quote! {
+            .with_max_concurrency(::blueprint_sdk::macros::ext::std::num::NonZeroUsize::new(#limit).expect("checked to be non-zero"))
+        }
+    });
+    let ordering_key = args.ordering_key.as_ref().map(|ordering_key| {
+        quote! { .with_ordering_key(#ordering_key) }
+    });
+    let retry_policy = args.max_retries.as_ref().map(|max_retries| {
+        quote! {
+            .with_retry_policy(::blueprint_sdk::macros::ext::event_listeners::core::retry::RetryPolicy::new(#max_retries))
+        }
+    });
+    // `dead_letter_codec` is declared by each listener, see `generate_event_workflow_tokenstream`
+    let dead_letters = args.dead_letters.then(|| {
+        quote! {
+            .with_dead_letter_codec(
+                ::std::sync::Arc::new(
+                    ::blueprint_sdk::macros::ext::event_listeners::core::dead_letter::DeadLetterQueue::new(settings.data_dir.as_deref(), #fn_name_string)
+                ),
+                dead_letter_codec,
+            )
+        }
+    });
+    let timeout = args.timeout_secs.as_ref().map(|timeout_secs| {
+        quote! {
+            .with_timeout(::blueprint_sdk::macros::ext::std::time::Duration::from_secs(#timeout_secs))
+        }
+    });
+
+    quote! { #job_metadata #max_concurrency #ordering_key #retry_policy #dead_letters #timeout #(#layers)* }
+}
+
 /// Get all the params names inside the param_types map
 /// and not in the params list to be added to the event handler.
 pub(crate) fn get_event_handler_args<'a>(
@@ -512,6 +735,7 @@ pub fn generate_autogen_struct(
             pub service_id: u64,
             pub signer: ::blueprint_sdk::macros::ext::crypto::tangle_pair_signer::TanglePairSigner<::blueprint_sdk::macros::ext::crypto::tangle_pair_signer::sp_core::sr25519::Pair>,
             pub client: ::blueprint_sdk::macros::ext::clients::tangle::client::TangleClient,
+            pub idempotency: ::std::sync::Arc<::blueprint_sdk::macros::ext::event_listeners::core::idempotency::IdempotencyStore>,
         })
     }
 
@@ -543,6 +767,7 @@ pub fn generate_autogen_struct(
         impl ::blueprint_sdk::macros::ext::event_listeners::core::InitializableEventHandler for #struct_name {
             async fn init_event_handler(
                 &self,
+                settings: &::blueprint_sdk::macros::ext::event_listeners::core::HandlerSettings,
             ) -> Option<
                 ::blueprint_sdk::macros::ext::tokio::sync::oneshot::Receiver<
                     Result<(), Box<dyn ::core::error::Error + Send>>
@@ -658,6 +883,9 @@ pub fn generate_specialized_logic(
             generate_tangle_specific_impl(&struct_name, param_map, job_params, event_listener_args)
         }
 
+        #[cfg(feature = "webhook")]
+        ListenerType::Webhook => Ok(TokenStream::default()),
+
         ListenerType::Custom => Ok(TokenStream::default()),
     }
 }
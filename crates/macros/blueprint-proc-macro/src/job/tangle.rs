@@ -1,5 +1,5 @@
 use crate::job::args::EventListenerArgs;
-use crate::job::declared_params_to_field_types;
+use crate::job::{declared_params_to_field_types, IsResultType, StreamArgs};
 use crate::shared::{get_non_job_arguments, get_return_type_wrapper};
 use indexmap::IndexMap;
 use proc_macro2::{Span, TokenStream};
@@ -34,6 +34,7 @@ pub(crate) fn generate_tangle_specific_impl(
         client,
         signer,
         service_id,
+        idempotency,
     });
 
     for (param_name, param_type) in non_job_param_map {
@@ -61,6 +62,9 @@ pub(crate) fn generate_tangle_specific_impl(
             /// - The client fails to connect
             /// - The signer is not found
             /// - The service ID is not found.
+            ///
+            /// Results are recorded in an idempotency store under the gadget data directory (or in memory,
+            /// if there is none), so redelivered job calls aren't recomputed or resubmitted.
             pub async fn new(#(#new_function_signature)*) -> Result<Self, Box<dyn core::error::Error>> {
                 use ::blueprint_sdk::macros::ext::keystore::backends::tangle::TangleBackend as _;
                 use ::blueprint_sdk::macros::ext::keystore::backends::Backend as _;
@@ -86,6 +90,10 @@ pub(crate) fn generate_tangle_specific_impl(
                     .service_id
                     .ok_or_else(|| Into::<Box<dyn core::error::Error>>::into(::blueprint_sdk::macros::ext::config::Error::MissingServiceId))?;
 
+                let idempotency = ::std::sync::Arc::new(
+                    ::blueprint_sdk::macros::ext::event_listeners::core::idempotency::IdempotencyStore::new(env.data_dir.as_deref(), #struct_name_as_literal)
+                );
+
                 Ok(Self {
                     #(#constructor_args)*
                 })
@@ -107,6 +115,7 @@ pub(crate) fn get_tangle_job_processor_wrapper(
     asyncness: &TokenStream,
     return_type: &Type,
     ctx_pos_in_ordered_inputs: usize,
+    stream: Option<&StreamArgs>,
 ) -> syn::Result<TokenStream> {
     let params = declared_params_to_field_types(job_params, param_map)?;
     let params_tokens = event_listeners.get_param_name_tokenstream(&params);
@@ -129,16 +138,71 @@ pub(crate) fn get_tangle_job_processor_wrapper(
     // Clone to allow passing to the post-processor closure
     ordered_inputs[ctx_pos_in_ordered_inputs] = quote! { injected_context.clone() };
 
+    // Reuse the result recorded for this call, if the event was delivered before
+    let cached_result = if return_type.is_result_type() {
+        quote! { Ok(cached) }
+    } else {
+        quote! { cached }
+    };
+    // Publish the updates the job emits while it runs, all of which are published before its result
+    let (start_updates, finish_updates) = match stream {
+        Some(stream) => {
+            let publisher = stream.publisher.as_ref().map_or_else(
+                || quote! { ::blueprint_sdk::macros::ext::event_listeners::core::updates::LogPublisher },
+                |publisher| quote! { #publisher },
+            );
+            (
+                quote! {
+                    static UPDATE_PUBLISHER: ::std::sync::OnceLock<::std::sync::Arc<dyn ::blueprint_sdk::macros::ext::event_listeners::core::updates::UpdatePublisher>> = ::std::sync::OnceLock::new();
+                    let publisher = UPDATE_PUBLISHER.get_or_init(|| ::std::sync::Arc::new(#publisher) as ::std::sync::Arc<dyn ::blueprint_sdk::macros::ext::event_listeners::core::updates::UpdatePublisher>).clone();
+                    let update_stream = call_key.map(|key| {
+                        ::blueprint_sdk::macros::ext::event_listeners::core::updates::UpdateStream::start(key, publisher)
+                    });
+                    let updates = update_stream.as_ref().map(::blueprint_sdk::macros::ext::event_listeners::core::updates::UpdateStream::sender);
+                },
+                quote! {
+                    if let Some(update_stream) = update_stream {
+                        update_stream.finish().await;
+                    }
+                },
+            )
+        }
+        None => (TokenStream::new(), TokenStream::new()),
+    };
+    let run_job = |call: TokenStream| {
+        if stream.is_some() {
+            quote! { ::blueprint_sdk::macros::ext::event_listeners::core::updates::scope(updates, async { #call }).await }
+        } else {
+            call
+        }
+    };
+    let job_call = |call: TokenStream| {
+        let call = run_job(call);
+        quote! {
+            #start_updates
+            let res = match call_key.as_ref().and_then(|key| CTX.get().unwrap().idempotency.result(key)) {
+                Some(cached) => {
+                    ::blueprint_sdk::macros::ext::logging::info!("Reusing the recorded result for call {}", call_key.expect("Only found with a key"));
+                    #cached_result
+                }
+                None => #call,
+            };
+            #finish_updates
+        }
+    };
+
     let job_processor_call = if params_tokens.is_empty() {
         let second_param = ordered_inputs
             .pop()
             .ok_or_else(|| syn::Error::new(Span::call_site(), "Context type required"))?;
+        let call_raw = job_call(quote! { #fn_name_ident (tangle_event, #second_param) #asyncness });
         quote! {
             #call_id_injector
             // If no args are specified, assume this job has no parameters and thus takes in the raw event
-            let res = #fn_name_ident (tangle_event, #second_param) #asyncness;
+            #call_raw
         }
     } else {
+        let call_with_args = job_call(quote! { #fn_name_ident (#(#ordered_inputs),*) #asyncness });
         quote! {
             #parameter_count_const
 
@@ -153,7 +217,7 @@ pub(crate) fn get_tangle_job_processor_wrapper(
 
             #call_id_injector
 
-            let res = #fn_name_ident (#(#ordered_inputs),*) #asyncness;
+            #call_with_args
         }
     };
 
@@ -162,9 +226,17 @@ pub(crate) fn get_tangle_job_processor_wrapper(
 
     Ok(quote! {
         move |tangle_event: ::blueprint_sdk::macros::ext::event_listeners::tangle::events::TangleEvent<_, _>| async move {
+            let call_key = tangle_event.call_id.map(|call_id| {
+                ::blueprint_sdk::macros::ext::event_listeners::core::idempotency::CallKey::tangle(tangle_event.service_id, tangle_event.job_id, call_id)
+            });
 
             #job_processor_call
-            #job_processor_call_return
+
+            let output = #job_processor_call_return;
+            if let (Some(key), Ok((_, result))) = (&call_key, &output) {
+                CTX.get().unwrap().idempotency.record_result(key, result);
+            }
+            output
         }
     })
 }
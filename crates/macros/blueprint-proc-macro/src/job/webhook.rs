@@ -0,0 +1,41 @@
+use crate::shared::get_return_type_wrapper;
+use indexmap::IndexMap;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, Type};
+
+pub(crate) fn get_webhook_job_processor_wrapper(
+    params: &[Ident],
+    param_types: &IndexMap<Ident, Type>,
+    ordered_inputs: &[TokenStream],
+    fn_name_ident: &Ident,
+    asyncness: &TokenStream,
+    return_type: &Type,
+) -> syn::Result<TokenStream> {
+    // Each job param is decoded from the field of the same name in the JSON body
+    let params_tokens = params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let ident = format_ident!("param{i}");
+            let ty = param_types.get(param).ok_or_else(|| {
+                syn::Error::new_spanned(param, "parameter not declared in the function")
+            })?;
+            let name = param.to_string();
+            Ok(quote! {
+                let #ident = __params.take::<#ty>(#name).map_err(|e| ::blueprint_sdk::macros::ext::event_listeners::core::Error::BadArgumentDecoding(e.to_string()))?;
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let job_processor_call_return = get_return_type_wrapper(return_type, None);
+
+    Ok(quote! {
+        move |request: ::blueprint_sdk::macros::ext::event_listeners::webhook::WebhookRequest| async move {
+            let mut __params = request.params().map_err(|e| ::blueprint_sdk::macros::ext::event_listeners::core::Error::BadArgumentDecoding(e.to_string()))?;
+            #(#params_tokens)*
+            let res = #fn_name_ident (#(#ordered_inputs),*) #asyncness;
+            #job_processor_call_return
+        }
+    })
+}
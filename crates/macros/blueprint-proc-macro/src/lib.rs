@@ -18,6 +18,8 @@ use syn::parse_macro_input;
 mod abi;
 /// Benchmarking proc-macro
 mod benchmark;
+/// `BlueprintField` derive macro
+mod field;
 /// Blueprint Hooks proc-macro
 mod hooks;
 /// Blueprint Job proc-macro
@@ -34,12 +36,49 @@ mod sdk_main;
 /// Addon to the generated code, the `job` macro also generates an Event Handler struct that
 /// implements the `EventHandler` trait for you.
 ///
+/// For Tangle jobs, each result is recorded by its service, job and call ID in the gadget data directory,
+/// and for EVM jobs by the chain ID, transaction hash and log index of the log that triggered the call.
+/// A call that is delivered again reuses the recorded result instead of re-running the job, and a result
+/// is only submitted (or passed to the EVM `post_processor`) if it wasn't already.
+///
+/// The return type of a Tangle or EVM job must therefore implement `Serialize` and `DeserializeOwned`,
+/// which jobs written before results were recorded may need to derive. A result that fails to encode is
+/// still submitted, but isn't recorded. Recorded results that were never submitted are forgotten after
+/// 30 days.
+///
+/// A job that returns a tuple is submitted as a single tuple field by default. With `split_result`, each
+/// element of the tuple (or each type declared in `result`) is submitted as a separate field instead.
+///
+/// Jobs with `event_listener(listener = WebhookEventListener)` are triggered by authenticated HTTP requests
+/// (requires the `webhook` feature). The context must implement `WebhookDefinition`, and the request body is
+/// a JSON object with a field for each job parameter. A `pre_processor` receives and returns the
+/// `WebhookRequest`, and the `post_processor` receives the job result.
+///
 /// # Parameters
 /// - `id`: The unique identifier for the job (must be in the range of 0..[`u8::MAX`])
 /// - `params`: The parameters of the job function, must be a tuple of identifiers in the function signature.
 /// - `result`: The result of the job function, must be a type that this job returns.
 ///    also, it can be omitted if the return type is simple to infer, like `u32` or `Vec<u8>` just use `_`.
 /// - `skip_codegen`: A flag to skip the code generation for the job, useful for manual event handling.
+/// - `max_concurrency`: The maximum number of job calls processed at once, defaults to `1`.
+///    Once the limit is reached, no further events are pulled from the event listener until a call finishes.
+/// - `ordering_key`: A function from the pre-processed event to a `u64`. Job calls with the same key are
+///    processed in the order they were received, even when `max_concurrency` is greater than `1`.
+/// - `max_retries`: The number of times a failed job call is retried, with exponential back-off. Once a
+///    call has failed every attempt it is logged and skipped, rather than stopping the event listener.
+/// - `dead_letters`: Keep calls that failed every retry in a dead-letter queue under the gadget's
+///    `DATA_DIR`, so they can be inspected and replayed through the `BlueprintRunner`'s `dead_letters`,
+///    under the job's function name. Requires `max_retries`. Tangle jobs store the job call itself, while
+///    other jobs need a pre-processed event that implements `Serialize` and `Deserialize`.
+/// - `timeout_secs`: The number of seconds after which a job call is cancelled, applied to each attempt.
+/// - `layers`: Middleware implementing `JobLayer` that wraps each stage of the job's calls, outermost first,
+///    for example `layers(TraceLayer, MetricsLayer)`. Layers added to the `BlueprintRunner` run outside these.
+/// - `stream`: Publish the heartbeats, progress and partial results the job emits through
+///    `UpdateSender::current()` while it runs, before its final result is submitted. Updates are logged by
+///    default, or published with the given `UpdatePublisher`, as in `stream(MyPublisher::new())`.
+///    Only supported for Tangle jobs.
+/// - `split_result`: Submit each element of the job's tuple result as a separate field, rather than as one
+///    tuple field.
 #[proc_macro_attribute]
 pub fn job(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as job::JobArgs);
@@ -51,6 +90,41 @@ pub fn job(args: TokenStream, input: TokenStream) -> TokenStream {
     }
 }
 
+/// Derives `BlueprintField` for a struct or enum, describing the `FieldType` it is encoded as.
+///
+/// Types used as job parameters or results must implement `BlueprintField`, so that the job definition
+/// in `blueprint.json` matches what `gadget_blueprint_serde::from_field` decodes. Fields may be any type
+/// with a known `FieldType`, including other types deriving `BlueprintField`. Enums may have unit,
+/// newtype, tuple and struct variants. Enums with only unit variants are encoded as the variant's name
+/// and described as a `FieldType::String`. Other enums are described as a `FieldType::Enum`, which the
+/// Tangle runtime can't represent, so blueprints that use them are rejected when deployed.
+///
+/// Types that have no single `FieldType` are rejected at compile time, including generic types,
+/// `usize`/`isize`, maps, and serde attributes that change the encoding (such as `flatten`, `skip`
+/// or `untagged`). `#[serde(rename = "...")]` and `#[serde(transparent)]` are supported.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use blueprint_sdk::macros::BlueprintField;
+///
+/// #[derive(BlueprintField, serde::Serialize, serde::Deserialize)]
+/// pub struct KeygenArgs {
+///     pub n: u16,
+///     pub t: u16,
+///     pub participants: Vec<AccountId32>,
+/// }
+/// ```
+#[proc_macro_derive(BlueprintField, attributes(serde))]
+pub fn derive_blueprint_field(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    match field::derive_blueprint_field(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 /// Creates a misbehavior report handler for the given function.
 ///
 /// This macro generates the necessary code to handle events and process reports within the
@@ -66,6 +140,30 @@ pub fn job(args: TokenStream, input: TokenStream) -> TokenStream {
 /// - `result`: The result of the report function, must be a type that this report returns.
 ///    It can be omitted if the return type is simple to infer, like `u32` or `Vec<u8>` by using `_`.
 /// - `skip_codegen`: A flag to skip the code generation for the report, useful for manual event handling.
+///
+/// # `QoS` reports
+///
+/// Reports with `report_type = "qos"` take no `event_listener`. Instead, a `<name>_qos` function is
+/// generated, taking the arguments of the report that aren't `params`, and returning a `QosReport` to
+/// run with a `QosReportService`. Every `interval` seconds, the recorded metrics are checked against
+/// `metric_thresholds`, and if any is violated, the report is called with the metrics named in
+/// `params` and its result is submitted. A threshold is written `metric <= value` or
+/// `metric >= value`, and `metric = value` is the same as `<=`. `QoS` report functions must return a
+/// `Result`.
+///
+/// ```rust,ignore
+/// #[report(
+///     params(uptime, error_rate),
+///     report_type = "qos",
+///     interval = 3600,
+///     metric_thresholds(uptime >= 99, error_rate <= 5)
+/// )]
+/// fn report_service_health(context: MyContext, uptime: u64, error_rate: u64) -> Result<u64, Error> {
+///     Ok(error_rate)
+/// }
+///
+/// let qos = QosReportService::new(env, submitter).report(report_service_health_qos(context));
+/// ```
 #[proc_macro_attribute]
 pub fn report(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as report::ReportArgs);
@@ -101,6 +199,18 @@ pub fn request_hook(_args: TokenStream, input: TokenStream) -> TokenStream {
 
 /// A procedural macro that annotates a function as a benchmark hook, mainly used
 /// during the benchmarking phase.
+///
+/// The function body is run `iterations` times (10 by default) on a runtime with `cores` threads, and
+/// `<name>_benchmark()` returns a summary of the run. Benchmarks defined in a blueprint's library are
+/// run by `cargo tangle blueprint bench`.
+///
+/// ```rust,ignore
+/// #[benchmark(job_id = 0, cores = 2, iterations = 20)]
+/// fn keygen_2_of_3() {
+///     let result = keygen(2, 3).await;
+///     assert!(result.is_ok());
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn benchmark(args: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::ItemFn);
@@ -128,7 +238,8 @@ pub fn load_abi(input: TokenStream) -> TokenStream {
 /// ```
 ///
 /// # Parameters
-/// - `env`: Sets up the environment for the main function.
+/// - `env`: Sets up the environment for the main function, from the command line, the environment and
+///    the configuration file passed with `--config`.
 /// - `skip_logging`: A flag to skip the logging setup for the main function.
 /// - ...: are passes as-is to the `#[tokio::main]` attribute.
 #[proc_macro_attribute]
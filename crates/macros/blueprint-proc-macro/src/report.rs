@@ -3,9 +3,10 @@ use crate::job::{
     declared_params_to_field_types, generate_autogen_struct, generate_specialized_logic,
     get_job_id_field_name, get_return_type, ParameterType, ResultsKind,
 };
-use crate::shared::{pascal_case, MacroExt};
+use crate::shared::MacroExt;
 use gadget_blueprint_proc_macro_core::{
-    FieldType, ReportDefinition, ReportMetadata, ReportResultVerifier, ReportType,
+    MetricThreshold, ReportDefinition, ReportMetadata, ReportResultVerifier, ReportType,
+    ThresholdBound,
 };
 use indexmap::IndexMap;
 use proc_macro::TokenStream;
@@ -87,11 +88,15 @@ pub(crate) fn report_impl(args: &ReportArgs, input: &ItemFn) -> syn::Result<Toke
         metric_thresholds: args.metric_thresholds.as_ref().map(|thresholds| {
             thresholds
                 .iter()
-                .filter_map(|(ident, lit_int)| {
+                .filter_map(|(ident, bound, lit_int)| {
                     lit_int
                         .base10_parse::<u64>()
                         .ok()
-                        .map(|value| (ident.to_string(), value))
+                        .map(|value| MetricThreshold {
+                            metric: ident.to_string(),
+                            bound: *bound,
+                            value,
+                        })
                 })
                 .collect()
         }),
@@ -108,10 +113,22 @@ pub(crate) fn report_impl(args: &ReportArgs, input: &ItemFn) -> syn::Result<Toke
         )
     })?;
 
-    let suffix = match args.report_type {
-        ReportType::Job => "JobReportEventHandler",
-        ReportType::QoS => "QoSReportEventHandler",
-    };
+    if args.report_type == ReportType::QoS {
+        let qos_report = generate_qos_report(args, input, &report_def_name)?;
+        return Ok(quote! {
+            #[doc = "Report definition for the function "]
+            #[doc = #fn_name_string]
+            pub const #report_def_name: &str = #report_def_str;
+
+            #[allow(unused_variables)]
+            #input
+
+            #qos_report
+        }
+        .into());
+    }
+
+    let suffix = "JobReportEventHandler";
 
     let event_listener_gen;
     let event_listener_calls;
@@ -126,6 +143,9 @@ pub(crate) fn report_impl(args: &ReportArgs, input: &ItemFn) -> syn::Result<Toke
                 &args.event_listeners,
                 &param_types,
                 &args.params,
+                &proc_macro2::TokenStream::new(),
+                &crate::job::ResultOptions::default(),
+                false,
             )?;
     }
 
@@ -205,9 +225,9 @@ pub(crate) struct ReportArgs {
     /// Optional: Interval for the report.
     /// `#[report(interval = 10)]`
     interval: Option<LitInt>,
-    /// Optional: Metric thresholds for the report.
-    /// `#[report(metric_thresholds(a = 10, b = 20))]`
-    metric_thresholds: Option<Vec<(Ident, LitInt)>>,
+    /// Optional: Metric thresholds for the report, `=` is the same as `<=`.
+    /// `#[report(metric_thresholds(a >= 10, b <= 20))]`
+    metric_thresholds: Option<Vec<(Ident, ThresholdBound, LitInt)>>,
     /// Optional: Verifier for the report result, currently only supports EVM verifier.
     /// `#[report(verifier(evm = "MyVerifierContract"))]`
     verifier: Verifier,
@@ -276,9 +296,21 @@ impl Parse for ReportArgs {
                 let thresholds = content.parse_terminated(
                     |input| {
                         let name = input.parse::<Ident>()?;
-                        let _ = input.parse::<Token![=]>()?;
+                        let lookahead = input.lookahead1();
+                        let bound = if lookahead.peek(Token![>=]) {
+                            let _ = input.parse::<Token![>=]>()?;
+                            ThresholdBound::Min
+                        } else if lookahead.peek(Token![<=]) {
+                            let _ = input.parse::<Token![<=]>()?;
+                            ThresholdBound::Max
+                        } else if lookahead.peek(Token![=]) {
+                            let _ = input.parse::<Token![=]>()?;
+                            ThresholdBound::Max
+                        } else {
+                            return Err(lookahead.error());
+                        };
                         let value = input.parse::<LitInt>()?;
-                        Ok((name, value))
+                        Ok((name, bound, value))
                     },
                     Token![,],
                 )?;
@@ -321,10 +353,15 @@ impl Parse for ReportArgs {
                 if job_id.is_some() {
                     return Err(input.error("Unexpected `job_id` for QoS report"));
                 }
+                if !event_listener.listeners.is_empty() {
+                    return Err(input.error(
+                        "QoS reports run on their `interval`, remove the `event_listener`",
+                    ));
+                }
             }
         }
 
-        if event_listener.listeners.is_empty() {
+        if report_type == ReportType::Job && event_listener.listeners.is_empty() {
             return Err(input.error("Missing `event_listener` for report"));
         }
 
@@ -352,118 +389,80 @@ impl MacroExt for ReportArgs {
     }
 }
 
-/// Generates an event handler for `QoS` reports.
+/// Generates the function that creates the runtime of a `QoS` report.
 ///
-/// This function creates a struct that periodically collects `QoS` metrics
-/// and triggers the report function at specified intervals.
-///
-/// # Arguments
-///
-/// * `args` - The parsed arguments from the `report` attribute macro.
-/// * `input` - The function item that the `report` attribute is attached to.
-/// * `param_types` - A map of parameter names to their types.
-/// * `params` - The list of parameter field types.
-/// * `result` - The list of result field types.
-///
-/// # Returns
-///
-/// Returns a `TokenStream` containing the generated event handler code.
-#[allow(dead_code)]
-fn generate_qos_report_event_handler(
+/// The generated `<name>_qos` function takes the arguments of the report that aren't metrics, and
+/// returns a `QosReport` that calls the report with the metrics named in `params`.
+fn generate_qos_report(
     args: &ReportArgs,
     input: &ItemFn,
-    _params: &[FieldType],
-    _result: &[FieldType],
+    report_def_name: &Ident,
 ) -> syn::Result<proc_macro2::TokenStream> {
     let fn_name = &input.sig.ident;
     let fn_name_string = fn_name.to_string();
-    const SUFFIX: &str = "QoSReportEventHandler";
-
-    let struct_name = format_ident!("{}{SUFFIX}", pascal_case(&fn_name_string));
-    let job_id = quote! { 0 }; // We don't care about job ID's for QOS
-    let param_types = crate::shared::param_types(&input.sig)
-        .expect("Failed to generate param types for job report");
-    // TODO: Allow passing all events, use a dummy value here that satisfies the trait bounds. For now QOS will
-    // trigger only once a singular JobCalled event is received.
-    let event_type = quote! { gadget_sdk::tangle_subxt::tangle_testnet_runtime::api::services::events::JobResultSubmitted };
-
-    let event_listener_gen;
-    let event_listener_calls;
-    if args.skip_codegen {
-        event_listener_gen = Vec::new();
-        event_listener_calls = Vec::new();
-    } else {
-        (event_listener_gen, event_listener_calls) =
-            crate::job::generate_event_workflow_tokenstream(
-                input,
-                SUFFIX,
-                &args.event_listeners,
-                &param_types,
-                &args.params,
-            )?;
+    let builder_name = format_ident!("{}_qos", fn_name);
+    let qos = quote! { ::blueprint_sdk::runners::tangle::qos };
+
+    let mut context_args = Vec::new();
+    let mut metrics = Vec::new();
+    let mut call_args = Vec::new();
+    for arg in &input.sig.inputs {
+        let syn::FnArg::Typed(arg) = arg else {
+            return Err(syn::Error::new_spanned(
+                arg,
+                "QoS reports can't take `self`",
+            ));
+        };
+        let syn::Pat::Ident(pat_ident) = &*arg.pat else {
+            return Err(syn::Error::new_spanned(
+                &arg.pat,
+                "QoS report arguments must be identifiers",
+            ));
+        };
+
+        let ident = &pat_ident.ident;
+        let ty = &*arg.ty;
+        if args.params.contains(ident) {
+            let name = ident.to_string();
+            metrics.push(quote! { let #ident = metrics.get::<#ty>(#name); });
+        } else {
+            context_args.push(quote! { #ident: #ty });
+        }
+        call_args.push(ident.clone());
     }
 
-    let interval = args
-        .interval
-        .as_ref()
-        .ok_or_else(|| syn::Error::new_spanned(input, "Missing field `interval` for QoS report"))?;
-
-    let combined_event_listener =
-        crate::job::generate_combined_event_listener_selector(&struct_name);
+    let clone_context = input.sig.inputs.iter().filter_map(|arg| match arg {
+        syn::FnArg::Typed(arg) => match &*arg.pat {
+            syn::Pat::Ident(pat_ident) if !args.params.contains(&pat_ident.ident) => {
+                let ident = &pat_ident.ident;
+                Some(quote! { let #ident = ::core::clone::Clone::clone(&#ident); })
+            }
+            _ => None,
+        },
+        syn::FnArg::Receiver(_) => None,
+    });
+    let check_metrics = args
+        .params
+        .iter()
+        .map(|ident| quote! { let #ident = #ident?; });
+    let call_await = input.sig.asyncness.map(|_| quote! { .await });
 
     Ok(quote! {
-        #(#event_listener_gen)*
-
+        #[doc = "Create the runtime of the `QoS` report [`"]
+        #[doc = #fn_name_string]
+        #[doc = "`], to run with a `QosReportService`"]
         #[automatically_derived]
-        #[gadget_sdk::async_trait::async_trait]
-        impl gadget_sdk::event_utils::substrate::EventHandler<gadget_sdk::clients::tangle::client::TangleConfig, #event_type> for #struct_name {
-            async fn handle(&self, event: &#event_type) -> Result<Vec<gadget_sdk::tangle_subxt::tangle_testnet_runtime::api::runtime_types::tangle_primitives::services::field::Field<gadget_sdk::subxt_core::utils::AccountId32>>, gadget_sdk::event_utils::Error> {
-                use std::time::Duration;
-                use gadget_sdk::slashing::reports::{QoSReporter, DefaultQoSReporter};
-
-
-                let mut reporter = DefaultQoSReporter { service_id: self.service_id };
-                let interval = Duration::from_secs(#interval);
-                let mut next_check = std::time::Instant::now();
-
-                loop {
-                    if std::time::Instant::now() >= next_check {
-                        let metrics = reporter.collect_metrics().await
-                            .map_err(|e| gadget_sdk::event_utils::Error::Handler(e.into()))?;
-
-                        let report_result = reporter.report(&metrics).await
-                            .map_err(|e| gadget_sdk::event_utils::Error::Handler(e.into()))?;
-
-                        next_check = std::time::Instant::now() + interval;
-                    }
-                    gadget_sdk::tokio::time::sleep(Duration::from_millis(100)).await;
+        pub fn #builder_name(#(#context_args),*) -> #qos::QosReport {
+            #qos::QosReport::from_definition(#report_def_name, move |metrics: &#qos::Metrics| {
+                #(#clone_context)*
+                #(#metrics)*
+                async move {
+                    #(#check_metrics)*
+                    #qos::IntoReport::into_report(#fn_name(#(#call_args),*) #call_await)
                 }
-            }
-
-            /// Returns the job ID
-            fn job_id(&self) -> u8 {
-                #job_id
-            }
-
-            /// Returns the service ID
-            fn service_id(&self) -> u64 {
-                self.service_id
-            }
-
-            fn signer(&self) -> &gadget_sdk::keystore::TanglePairSigner<gadget_sdk::ext::sp_core::sr25519::Pair> {
-                &self.signer
-            }
+            })
+            .expect("report definitions are generated by `#[report]`")
         }
-
-        #[gadget_sdk::async_trait::async_trait]
-        impl gadget_sdk::event_utils::InitializableEventHandler for #struct_name {
-            async fn init_event_handler(&self) -> Option<gadget_sdk::tokio::sync::oneshot::Receiver<Result<(), gadget_sdk::Error>>> {
-                #(#event_listener_calls)*
-                #combined_event_listener
-            }
-        }
-
-        impl gadget_sdk::event_listener::markers::IsTangle for #struct_name {}
     })
 }
 
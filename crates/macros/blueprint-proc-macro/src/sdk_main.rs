@@ -56,7 +56,7 @@ pub(crate) fn sdk_main_impl(args: &SdkMainArgs, input: &ItemFn) -> syn::Result<T
     let standard_setup = if args.env {
         quote! {
             // Load the environment and create the gadget runner
-            let config: ::blueprint_sdk::macros::ext::config::ContextConfig = ::blueprint_sdk::macros::ext::clap::Parser::parse();
+            let config = ::blueprint_sdk::macros::ext::config::ContextConfig::parse_layered();
             let env = ::blueprint_sdk::macros::ext::config::load(config.clone()).expect("Failed to load environment");
         }
     } else {
@@ -2,7 +2,7 @@ use crate::job::ParameterType;
 use crate::job::{IsResultType, ResultsKind};
 use gadget_blueprint_proc_macro_core::FieldType;
 use indexmap::IndexMap;
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
 use syn::{Ident, Signature, Type};
 
@@ -36,11 +36,32 @@ pub fn ident_to_field_type(ident: &Ident) -> syn::Result<FieldType> {
         "bool" => Ok(FieldType::Bool),
         "String" => Ok(FieldType::String),
         "Bytes" => Ok(FieldType::Bytes),
-        "AccountId" => Ok(FieldType::AccountId),
+        "AccountId" | "AccountId32" => Ok(FieldType::AccountId),
         _ => Err(syn::Error::new_spanned(ident, "unsupported field type")),
     }
 }
 
+/// Rejects types that can't be described by a single [`FieldType`], which would otherwise be mistaken
+/// for a user-defined struct
+pub fn reject_unsupported_type(ident: &Ident) -> syn::Result<()> {
+    let message = match ident.to_string().as_str() {
+        "usize" | "isize" => format!(
+            "`{ident}` is ambiguous, as its size depends on the target. Use a fixed-size integer instead"
+        ),
+        "f32" => String::from("`f32` is unsupported, use `f64` instead"),
+        "char" | "str" => format!("`{ident}` is unsupported, use `String` instead"),
+        "Box" | "Rc" | "Arc" | "Cow" => {
+            format!("`{ident}` is unsupported, use the type it contains instead")
+        }
+        "HashMap" | "BTreeMap" | "HashSet" | "BTreeSet" => format!(
+            "`{ident}` is unsupported, use a `Vec` of entries or a struct deriving `BlueprintField` instead"
+        ),
+        _ => return Ok(()),
+    };
+
+    Err(syn::Error::new_spanned(ident, message))
+}
+
 pub fn type_to_field_type(ty: &Type) -> syn::Result<ParameterType> {
     let field_type = match ty {
         Type::Array(arr) => {
@@ -88,6 +109,7 @@ pub fn path_to_field_type(path: &syn::Path) -> syn::Result<FieldType> {
         .last()
         .ok_or_else(|| syn::Error::new_spanned(path, "path must have at least one segment"))?;
     let ident = &seg.ident;
+    reject_unsupported_type(ident)?;
 
     let args = &seg.arguments;
     match args {
@@ -170,6 +192,63 @@ pub fn path_to_field_type(path: &syn::Path) -> syn::Result<FieldType> {
     }
 }
 
+/// Returns the user-defined types within `ty`, which are only named in its [`FieldType`] and must
+/// implement `BlueprintField`
+pub fn custom_field_types(ty: &Type) -> Vec<Type> {
+    match ty {
+        Type::Array(arr) => custom_field_types(&arr.elem),
+        Type::Reference(type_reference) => custom_field_types(&type_reference.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().flat_map(custom_field_types).collect(),
+        Type::Path(inner) => {
+            let Some(seg) = inner.path.segments.last() else {
+                return Vec::new();
+            };
+            match &seg.arguments {
+                syn::PathArguments::None if ident_to_field_type(&seg.ident).is_err() => {
+                    vec![ty.clone()]
+                }
+                syn::PathArguments::AngleBracketed(args)
+                    if ["Vec", "Option", "Result"].iter().any(|i| seg.ident == i) =>
+                {
+                    // Only the first argument of `Result` is part of the field type
+                    match args.args.first() {
+                        Some(syn::GenericArgument::Type(inner_ty)) => custom_field_types(inner_ty),
+                        _ => Vec::new(),
+                    }
+                }
+                syn::PathArguments::AngleBracketed(_) => vec![ty.clone()],
+                _ => Vec::new(),
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Generates assertions that each of `types` implements `BlueprintField`, so that types with no
+/// [`FieldType`] fail to compile rather than to decode at runtime
+pub fn generate_field_type_assertions(types: &[Type]) -> proc_macro2::TokenStream {
+    if types.is_empty() {
+        return proc_macro2::TokenStream::new();
+    }
+
+    let assertions = types.iter().map(|ty| {
+        quote_spanned! {ty.span()=>
+            assert_blueprint_field::<#ty>();
+        }
+    });
+
+    quote! {
+        const _: () = {
+            fn assert_blueprint_field<T: ::blueprint_sdk::macros::core::BlueprintField>() {}
+
+            #[allow(dead_code)]
+            fn assert_field_types() {
+                #(#assertions)*
+            }
+        };
+    }
+}
+
 /// Returns the set of arguments which are not job-related arguments. These typically go into the
 /// autogenerated job struct
 pub fn get_non_job_arguments(
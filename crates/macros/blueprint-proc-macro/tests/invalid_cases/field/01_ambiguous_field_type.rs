@@ -0,0 +1,8 @@
+use blueprint_sdk::macros::BlueprintField;
+
+#[derive(BlueprintField)]
+pub struct KeygenArgs {
+    n: usize,
+}
+
+fn main() {}
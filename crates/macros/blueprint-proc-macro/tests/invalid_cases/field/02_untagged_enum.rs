@@ -0,0 +1,10 @@
+use blueprint_sdk::macros::BlueprintField;
+
+#[derive(BlueprintField)]
+#[serde(untagged)]
+pub enum Curve {
+    Secp256k1(u8),
+    Ed25519(String),
+}
+
+fn main() {}
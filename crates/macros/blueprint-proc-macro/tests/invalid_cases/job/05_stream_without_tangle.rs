@@ -0,0 +1,12 @@
+use blueprint_sdk::event_listeners::core::testing::PendingEventListener;
+use blueprint_sdk::macros::job;
+
+#[derive(Clone)]
+struct EmptyContext;
+
+#[job(id = 0, params(n), event_listener(listener = PendingEventListener<u16, EmptyContext>), stream)]
+fn keygen(ctx: EmptyContext, n: u16) -> Vec<u8> {
+    Vec::new()
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use blueprint_sdk::event_listeners::core::testing::PendingEventListener;
+use blueprint_sdk::macros::report;
+
+#[derive(Clone)]
+struct EmptyContext;
+
+#[report(params(uptime), report_type = "qos", interval = 60, event_listener(listener = PendingEventListener<u64, EmptyContext>))]
+fn report_uptime(ctx: EmptyContext, uptime: u64) -> Result<u64, String> {
+    Ok(uptime)
+}
+
+fn main() {}
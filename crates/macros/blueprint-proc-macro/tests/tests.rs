@@ -15,4 +15,16 @@ mod tests {
         let t = TestCases::new();
         t.compile_fail("tests/invalid_cases/job/*.rs");
     }
+
+    #[test]
+    fn test_reports_invalid_cases() {
+        let t = TestCases::new();
+        t.compile_fail("tests/invalid_cases/report/*.rs");
+    }
+
+    #[test]
+    fn test_blueprint_field_invalid_cases() {
+        let t = TestCases::new();
+        t.compile_fail("tests/invalid_cases/field/*.rs");
+    }
 }
@@ -0,0 +1,40 @@
+use crate::EmptyContext;
+use blueprint_sdk::event_listeners::core::testing::PendingEventListener;
+use blueprint_sdk::macros::job;
+use blueprint_sdk::macros::BlueprintField;
+
+#[derive(BlueprintField)]
+pub struct Participant {
+    pub id: u16,
+    pub weight: Option<u64>,
+}
+
+#[derive(BlueprintField)]
+pub enum Curve {
+    Secp256k1,
+    Ed25519,
+    Custom { name: String, order: Vec<u8> },
+}
+
+#[derive(BlueprintField)]
+pub struct KeygenArgs {
+    pub participants: Vec<Participant>,
+    pub threshold: u16,
+    pub seed: [u8; 32],
+    pub curve: Curve,
+}
+
+#[derive(BlueprintField)]
+pub struct PublicKey(pub Vec<u8>);
+
+#[job(
+    id = 0,
+    params(args),
+    event_listener(listener = PendingEventListener<KeygenArgs, EmptyContext>),
+    result(_),
+    skip_codegen
+)]
+fn keygen(args: KeygenArgs) -> PublicKey {
+    let _ = args;
+    PublicKey(Vec::new())
+}
@@ -0,0 +1,17 @@
+use crate::EmptyContext;
+use blueprint_sdk::event_listeners::core::testing::PendingEventListener;
+use blueprint_sdk::macros::job;
+use blueprint_sdk::std::convert::Infallible;
+
+/// Up to 4 calls at once, with calls for the same `n` handled in the order they were received
+#[job(
+    id = 0,
+    event_listener(listener = PendingEventListener<u16, EmptyContext>),
+    max_concurrency = 4,
+    ordering_key = |n: &u16| u64::from(*n),
+    result(Vec<u8>)
+)]
+fn keygen(ctx: EmptyContext, n: u16) -> Result<Vec<u8>, Infallible> {
+    let _ = n;
+    Ok(Vec::new())
+}
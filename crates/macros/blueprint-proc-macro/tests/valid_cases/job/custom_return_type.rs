@@ -1,7 +1,9 @@
 use crate::EmptyContext;
 use blueprint_sdk::event_listeners::core::testing::PendingEventListener;
 use blueprint_sdk::macros::job;
+use blueprint_sdk::macros::BlueprintField;
 
+#[derive(BlueprintField)]
 pub struct TestReturnType;
 
 #[job(id = 0, event_listener(listener = PendingEventListener<u16, EmptyContext>), result(_))]
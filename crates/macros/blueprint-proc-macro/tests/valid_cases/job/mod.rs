@@ -1,5 +1,6 @@
 mod array_return_type;
 mod complex_types;
+mod concurrency;
 mod custom_return_type;
 mod function_with_explicit_empty_return_type;
 mod function_without_return_type;
@@ -8,4 +9,6 @@ mod mixed_types;
 mod multiple_params;
 mod multiple_results;
 mod non_result_return_type;
+mod retries;
 mod simple;
+mod webhook;
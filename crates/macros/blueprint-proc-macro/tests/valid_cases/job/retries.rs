@@ -0,0 +1,18 @@
+use crate::EmptyContext;
+use blueprint_sdk::event_listeners::core::testing::PendingEventListener;
+use blueprint_sdk::macros::job;
+use blueprint_sdk::std::convert::Infallible;
+
+/// Retried up to 3 times, then kept as a dead letter, with each attempt cancelled after 30 seconds
+#[job(
+    id = 0,
+    event_listener(listener = PendingEventListener<u16, EmptyContext>),
+    max_retries = 3,
+    dead_letters,
+    timeout_secs = 30,
+    result(Vec<u8>)
+)]
+fn keygen(ctx: EmptyContext, n: u16) -> Result<Vec<u8>, Infallible> {
+    let _ = n;
+    Ok(Vec::new())
+}
@@ -0,0 +1,19 @@
+use crate::EmptyContext;
+use blueprint_sdk::event_listeners::webhook::{
+    WebhookAuth, WebhookConfig, WebhookDefinition, WebhookEventListener,
+};
+use blueprint_sdk::macros::job;
+
+impl WebhookDefinition for EmptyContext {
+    fn webhook(&self) -> WebhookConfig {
+        let auth = WebhookAuth::Hmac {
+            secret: b"secret".to_vec(),
+        };
+        WebhookConfig::new(([127, 0, 0, 1], 8080).into(), auth).with_path("/square")
+    }
+}
+
+#[job(id = 0, params(x, label), event_listener(listener = WebhookEventListener), result(_))]
+fn square(ctx: EmptyContext, x: u64, label: String) -> String {
+    format!("{label}: {}", x * x)
+}
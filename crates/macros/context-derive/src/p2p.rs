@@ -26,13 +26,11 @@ pub fn generate_context_impl(
             fn p2p_client(
                 &self,
                 name: ::blueprint_sdk::macros::ext::std::string::String,
-                target_port: u16,
                 my_ecdsa_key: ::blueprint_sdk::macros::ext::contexts::p2p::GossipMsgKeyPair,
             ) -> ::blueprint_sdk::macros::ext::contexts::p2p::P2PClient {
                 ::blueprint_sdk::macros::ext::contexts::p2p::P2PClient::new(
                     name,
                     #field_access.clone(),
-                    target_port,
                     my_ecdsa_key.clone()
                 )
             }
@@ -52,8 +52,7 @@ fn main() {
             .unwrap();
         let pub_key = keystore.generate::<SpEcdsa>(None).unwrap();
         let pair = keystore.get_secret::<SpEcdsa>(&pub_key).unwrap();
-        let p2p_client =
-            ctx.p2p_client(String::from("Foo"), 1337, GossipMsgKeyPair(pair.0.clone()));
+        let p2p_client = ctx.p2p_client(String::from("Foo"), GossipMsgKeyPair(pair.0.clone()));
 
         // Test MPC context utility functions
         let _config = p2p_client.config();
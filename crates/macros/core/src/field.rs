@@ -0,0 +1,118 @@
+use crate::FieldType;
+
+/// A type that can be passed to, or returned from, a job
+///
+/// Describes the [`FieldType`] that the type is encoded as by `gadget_blueprint_serde`. Implement it for
+/// your own structs with `#[derive(BlueprintField)]`, which rejects fields that can't be encoded.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be used as a job parameter or result",
+    label = "no `FieldType` for this type",
+    note = "derive `BlueprintField` for `{Self}`, or use a type with a known `FieldType`"
+)]
+pub trait BlueprintField {
+    /// The [`FieldType`] this type is encoded as
+    fn field_type() -> FieldType;
+}
+
+/// The [`FieldType`] of a user-defined type, emitted by `#[derive(BlueprintField)]`.
+///
+/// Job definitions only name the user-defined types they use, as `FieldType::Struct(name, [])`, which are
+/// then resolved against these definitions when generating `blueprint.json`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FieldTypeDefinition {
+    /// The name of the type
+    pub name: String,
+    /// The type's [`FieldType`], in which other user-defined types are only named
+    pub field_type: FieldType,
+}
+
+macro_rules! impl_blueprint_field {
+    ($($ty:ty => $field_type:ident),* $(,)?) => {
+        $(
+            impl BlueprintField for $ty {
+                fn field_type() -> FieldType {
+                    FieldType::$field_type
+                }
+            }
+        )*
+    };
+}
+
+impl_blueprint_field! {
+    () => Void,
+    bool => Bool,
+    u8 => Uint8,
+    i8 => Int8,
+    u16 => Uint16,
+    i16 => Int16,
+    u32 => Uint32,
+    i32 => Int32,
+    u64 => Uint64,
+    i64 => Int64,
+    u128 => Uint128,
+    i128 => Int128,
+    f64 => Float64,
+    String => String,
+}
+
+impl<T: BlueprintField> BlueprintField for Option<T> {
+    fn field_type() -> FieldType {
+        FieldType::Optional(Box::new(T::field_type()))
+    }
+}
+
+impl<T: BlueprintField> BlueprintField for Vec<T> {
+    fn field_type() -> FieldType {
+        FieldType::List(Box::new(T::field_type()))
+    }
+}
+
+impl<T: BlueprintField, const N: usize> BlueprintField for [T; N] {
+    fn field_type() -> FieldType {
+        FieldType::Array(N as u64, Box::new(T::field_type()))
+    }
+}
+
+macro_rules! impl_blueprint_field_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: BlueprintField),+> BlueprintField for ($($name,)+) {
+            fn field_type() -> FieldType {
+                FieldType::Tuple(vec![$($name::field_type()),+])
+            }
+        }
+    };
+}
+
+impl_blueprint_field_for_tuple!(A);
+impl_blueprint_field_for_tuple!(A, B);
+impl_blueprint_field_for_tuple!(A, B, C);
+impl_blueprint_field_for_tuple!(A, B, C, D);
+impl_blueprint_field_for_tuple!(A, B, C, D, E);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F, G);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F, G, H);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_blueprint_field_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn std_types_have_field_types() {
+        assert_eq!(
+            <Vec<Option<u8>>>::field_type(),
+            FieldType::List(Box::new(FieldType::Optional(Box::new(FieldType::Uint8))))
+        );
+        assert_eq!(
+            <([u64; 4], String)>::field_type(),
+            FieldType::Tuple(vec![
+                FieldType::Array(4, Box::new(FieldType::Uint64)),
+                FieldType::String
+            ])
+        );
+        assert_eq!(<()>::field_type(), FieldType::Void);
+    }
+}
@@ -1,5 +1,8 @@
 use gadget_std::borrow::Cow;
 
+mod field;
+pub use field::{BlueprintField, FieldTypeDefinition};
+
 pub type BlueprintString<'a> = std::borrow::Cow<'a, str>;
 /// A type that represents an EVM Address.
 pub type Address = ethereum_types::H160;
@@ -49,6 +52,17 @@ pub enum FieldType {
     Struct(String, Vec<(String, Box<FieldType>)>),
     /// Tuple
     Tuple(Vec<FieldType>),
+    /// An enum with at least one variant holding data, with the [`FieldType`] each variant is encoded as.
+    ///
+    /// Unit variants are encoded as their name, a [`FieldType::String`]. The data of other variants is
+    /// wrapped in a struct named `Enum::Variant`, and described by the type of that data: the type it
+    /// contains for newtype variants, a [`FieldType::Struct`] with `field_0..` fields for tuple variants,
+    /// and a [`FieldType::Struct`] for struct variants. Enums with only unit variants are described as
+    /// a [`FieldType::String`] instead.
+    ///
+    /// This only exists in blueprint metadata. The Tangle runtime's `FieldType` has no counterpart, so
+    /// blueprints whose jobs use it are rejected when deployed.
+    Enum(String, Vec<(String, Box<FieldType>)>),
     // NOTE: Special types starts from 100
     /// A special type for `AccountId`
     AccountId,
@@ -100,6 +114,7 @@ impl FieldType {
             FieldType::Array(size, ty) => Cow::Owned(format!("[{}; {size}]", ty.as_rust_type())),
             FieldType::List(ty) => Cow::Owned(format!("Vec<{}>", ty.as_rust_type())),
             FieldType::Struct(..) => unimplemented!("FieldType::Struct encoding"),
+            FieldType::Enum(name, _) => Cow::Borrowed(name.as_str()),
             FieldType::Tuple(tys) => {
                 let mut s = String::from("(");
                 for ty in tys {
@@ -224,7 +239,7 @@ pub struct ReportDefinition<'a> {
 
     /// Optional metric thresholds for `QoS` reports.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub metric_thresholds: Option<Vec<(String, u64)>>,
+    pub metric_thresholds: Option<Vec<MetricThreshold>>,
 
     /// The verifier to use for this report's results.
     pub verifier: ReportResultVerifier,
@@ -241,6 +256,39 @@ pub enum ReportType {
     QoS,
 }
 
+/// A bound a metric must stay within, checked by `QoS` reports.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MetricThreshold {
+    /// The name of the metric.
+    pub metric: String,
+    /// Whether `value` is the highest or lowest value allowed.
+    pub bound: ThresholdBound,
+    /// The threshold value.
+    pub value: u64,
+}
+
+impl MetricThreshold {
+    /// Whether `value` is outside of the bound.
+    #[must_use]
+    pub fn is_violated_by(&self, value: u64) -> bool {
+        match self.bound {
+            ThresholdBound::Max => value > self.value,
+            ThresholdBound::Min => value < self.value,
+        }
+    }
+}
+
+/// Enum representing which side of a [`MetricThreshold`] is allowed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThresholdBound {
+    /// The metric must not exceed the threshold.
+    #[default]
+    Max,
+    /// The metric must not fall below the threshold.
+    Min,
+}
+
 /// Enum representing the type of verifier for the report result.
 #[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -124,10 +124,9 @@ fn report_keygen(
 
 #[report(
     params(uptime, response_time, error_rate),
-    event_listener(listener = TangleEventListener<MyContext, JobResultSubmitted>, pre_processor = services_pre_processor,),
     report_type = "qos",
     interval = 3600,
-    metric_thresholds(uptime = 99, response_time = 1000, error_rate = 5)
+    metric_thresholds(uptime >= 99, response_time <= 1000, error_rate <= 5)
 )]
 fn report_service_health(
     context: MyContext,
@@ -7,11 +7,18 @@ compile_error!("evm feature is currently only supported on std");
 pub mod ext {
     pub use async_trait;
     pub use futures;
-    #[cfg(any(feature = "tangle", feature = "evm", feature = "std"))]
+    #[cfg(any(
+        feature = "tangle",
+        feature = "evm",
+        feature = "webhook",
+        feature = "std"
+    ))]
     pub use tokio;
 
     #[cfg(feature = "std")]
     pub use clap;
+    #[cfg(feature = "std")]
+    pub use gadget_benchmarking as benchmarking;
     #[cfg(feature = "tangle")]
     pub use gadget_blueprint_serde as blueprint_serde;
     #[cfg(any(feature = "tangle", feature = "evm"))]
@@ -20,10 +27,10 @@ pub mod ext {
     pub use gadget_contexts as contexts;
     #[cfg(feature = "tangle")]
     pub use gadget_crypto as crypto;
-    #[cfg(any(feature = "tangle", feature = "evm"))]
+    #[cfg(any(feature = "tangle", feature = "evm", feature = "webhook"))]
     pub use gadget_event_listeners as event_listeners;
     pub use gadget_keystore as keystore;
-    #[cfg(any(feature = "tangle", feature = "evm"))]
+    #[cfg(any(feature = "tangle", feature = "evm", feature = "webhook"))]
     pub use gadget_logging as logging;
     pub use gadget_std as std;
 
@@ -43,4 +50,5 @@ pub mod ext {
 
 pub use gadget_blueprint_proc_macro::*;
 pub use gadget_blueprint_proc_macro_core as core;
+pub use gadget_blueprint_proc_macro_core::BlueprintField;
 pub use gadget_context_derive as contexts;
@@ -1,2 +1,6 @@
+pub mod registry;
+
+pub use metrics;
+
 #[cfg(feature = "rpc-calls")]
 pub use gadget_rpc_calls as rpc_calls;
@@ -0,0 +1,146 @@
+//! An in-process registry of the latest value of every metric
+//!
+//! Once [`MetricsRegistry::global`] is called, everything recorded through the [`metrics`] macros is
+//! kept in the registry, so it can be read back with [`MetricsRegistry::snapshot`]. This is what
+//! `QoS` reports are evaluated against.
+
+use metrics::atomics::AtomicU64;
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+static GLOBAL: OnceLock<MetricsRegistry> = OnceLock::new();
+
+/// The latest value of every metric, by name
+pub type Snapshot = BTreeMap<String, u64>;
+
+/// Records counters and gauges, see the [module docs](self)
+///
+/// Metrics are stored by name, so the same metric with different labels shares a single value.
+/// Histograms aren't recorded.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    counters: RwLock<BTreeMap<String, Arc<AtomicU64>>>,
+    gauges: RwLock<BTreeMap<String, Arc<AtomicU64>>>,
+}
+
+impl MetricsRegistry {
+    /// The registry installed as the global [`metrics`] recorder
+    ///
+    /// The registry is installed on the first call. If another recorder was installed before, it is
+    /// kept, and the registry only holds the metrics recorded through it directly.
+    #[must_use]
+    pub fn global() -> &'static MetricsRegistry {
+        let mut created = false;
+        let registry = GLOBAL.get_or_init(|| {
+            created = true;
+            MetricsRegistry::default()
+        });
+
+        if created && metrics::set_global_recorder(registry).is_err() {
+            gadget_logging::warn!(
+                "A metrics recorder is already installed, QoS metrics won't be collected"
+            );
+        }
+
+        registry
+    }
+
+    /// Set the gauge `name` to `value`
+    pub fn set_gauge(&self, name: &str, value: f64) {
+        entry(&self.gauges, name).store(value.to_bits(), Ordering::Release);
+    }
+
+    /// Increment the counter `name` by `value`
+    pub fn increment_counter(&self, name: &str, value: u64) {
+        let _ = entry(&self.counters, name).fetch_add(value, Ordering::AcqRel);
+    }
+
+    /// The latest value of every metric
+    ///
+    /// Gauges are rounded to the nearest integer, and negative gauges are read as `0`.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot {
+        let mut snapshot = Snapshot::new();
+        for (name, value) in self
+            .counters
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+        {
+            snapshot.insert(name.clone(), value.load(Ordering::Acquire));
+        }
+        for (name, value) in self
+            .gauges
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+        {
+            let value = f64::from_bits(value.load(Ordering::Acquire));
+            // Saturates at the bounds of `u64`
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            snapshot.insert(name.clone(), value.round() as u64);
+        }
+        snapshot
+    }
+}
+
+fn entry(metrics: &RwLock<BTreeMap<String, Arc<AtomicU64>>>, name: &str) -> Arc<AtomicU64> {
+    if let Some(value) = metrics
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+    {
+        return value.clone();
+    }
+
+    metrics
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(name.to_string())
+        .or_default()
+        .clone()
+}
+
+impl Recorder for MetricsRegistry {
+    fn describe_counter(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn describe_gauge(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn describe_histogram(&self, _key: KeyName, _unit: Option<Unit>, _description: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        Counter::from_arc(entry(&self.counters, key.name()))
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(entry(&self.gauges, key.name()))
+    }
+
+    fn register_histogram(&self, _key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_counters_and_gauges() {
+        let registry = MetricsRegistry::default();
+        metrics::with_local_recorder(&registry, || {
+            metrics::counter!("errors").increment(2);
+            metrics::counter!("errors", "method" => "call").increment(1);
+            metrics::gauge!("uptime").set(99.6);
+            metrics::gauge!("temperature").set(-3.0);
+        });
+        registry.increment_counter("errors", 1);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot["errors"], 4);
+        assert_eq!(snapshot["uptime"], 100);
+        assert_eq!(snapshot["temperature"], 0);
+    }
+}
@@ -6,6 +6,9 @@
 )]
 
 use crate::key_types::{GossipMsgKeyPair, GossipMsgPublicKey, GossipSignedMsgSignature};
+use crate::peer_manager::{InboundPath, PeerEvent, PeerManager, PenaltyReason, RateLimitOutcome};
+use crate::peer_store::{KnownPeer, PeerStore};
+use crate::rpc::{RpcRegistry, RpcRequest};
 use crate::Error;
 use async_trait::async_trait;
 use gadget_crypto::hashing::blake3_256;
@@ -13,15 +16,17 @@ use gadget_std::collections::BTreeMap;
 use gadget_std::string::ToString;
 use gadget_std::sync::atomic::AtomicUsize;
 use gadget_std::sync::Arc;
+use gadget_std::time::Duration;
 use libp2p::gossipsub::IdentTopic;
 use libp2p::kad::store::MemoryStore;
 use libp2p::{
     gossipsub, mdns, request_response, swarm::NetworkBehaviour, swarm::SwarmEvent, PeerId,
 };
 use lru_mem::LruCache;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{oneshot, Mutex, RwLock};
 
 use crate::networking::{Network, ParticipantInfo, ProtocolMessage};
 use gadget_std as std;
@@ -43,15 +48,26 @@ pub struct MyBehaviour {
     pub relay: libp2p::relay::Behaviour,
     pub relay_client: libp2p::relay::client::Behaviour,
     pub ping: libp2p::ping::Behaviour,
+    pub blocked_peers: libp2p::allow_block_list::Behaviour<libp2p::allow_block_list::BlockedPeers>,
 }
 
 pub type InboundMapping = (IdentTopic, UnboundedSender<Vec<u8>>, Arc<AtomicUsize>);
 
+/// A response to an inbound RPC request, produced by a handler off the network worker.
+pub type RpcResponse = (
+    request_response::ResponseChannel<MyBehaviourResponse>,
+    MyBehaviourResponse,
+);
+
 pub struct NetworkServiceWithoutSwarm<'a> {
     pub inbound_mapping: &'a [InboundMapping],
     pub public_key_to_libp2p_id: Arc<RwLock<BTreeMap<GossipMsgPublicKey, PeerId>>>,
     pub secret_key: &'a GossipMsgKeyPair,
     pub connected_peers: Arc<AtomicUsize>,
+    pub peer_manager: Arc<PeerManager>,
+    pub peer_store: Arc<PeerStore>,
+    pub rpc: Arc<RpcRegistry>,
+    pub tx_rpc_response: UnboundedSender<RpcResponse>,
     pub span: tracing::Span,
     pub my_id: PeerId,
 }
@@ -67,6 +83,10 @@ impl<'a> NetworkServiceWithoutSwarm<'a> {
             public_key_to_libp2p_id: &self.public_key_to_libp2p_id,
             secret_key: self.secret_key,
             connected_peers: self.connected_peers.clone(),
+            peer_manager: &self.peer_manager,
+            peer_store: &self.peer_store,
+            rpc: &self.rpc,
+            tx_rpc_response: &self.tx_rpc_response,
             span: &self.span,
             my_id: self.my_id,
         }
@@ -79,11 +99,71 @@ pub struct NetworkService<'a> {
     pub public_key_to_libp2p_id: &'a Arc<RwLock<BTreeMap<GossipMsgPublicKey, PeerId>>>,
     pub connected_peers: Arc<AtomicUsize>,
     pub secret_key: &'a GossipMsgKeyPair,
+    pub peer_manager: &'a Arc<PeerManager>,
+    pub peer_store: &'a Arc<PeerStore>,
+    pub rpc: &'a Arc<RpcRegistry>,
+    pub tx_rpc_response: &'a UnboundedSender<RpcResponse>,
     pub span: &'a tracing::Span,
     pub my_id: PeerId,
 }
 
 impl NetworkService<'_> {
+    /// Ban a peer, blocking all current and future connections to it.
+    pub(crate) fn ban_peer(&mut self, peer_id: PeerId, reason: PenaltyReason) {
+        self.peer_manager.ban(&peer_id, reason);
+        self.peer_store.remove(&peer_id);
+        self.block_peer(peer_id);
+    }
+
+    /// Enforce an existing ban against the swarm.
+    pub(crate) fn block_peer(&mut self, peer_id: PeerId) {
+        let behaviour = self.swarm.behaviour_mut();
+        behaviour.gossipsub.blacklist_peer(&peer_id);
+        behaviour.gossipsub.remove_explicit_peer(&peer_id);
+        behaviour.blocked_peers.block_peer(peer_id);
+    }
+
+    /// Record a penalty against a peer, banning it once it has accrued too many violations.
+    pub(crate) fn penalise_peer(&mut self, peer_id: PeerId, reason: PenaltyReason) {
+        if self.peer_manager.penalise(&peer_id, reason.clone()) {
+            self.ban_peer(peer_id, reason);
+        }
+    }
+
+    /// Check an inbound message against the rate limit for `path`, banning the peer if it has
+    /// exceeded the limit too often.
+    ///
+    /// Returns `true` if the message should be processed.
+    pub(crate) fn enforce_rate_limit(&mut self, peer_id: PeerId, path: InboundPath) -> bool {
+        match self.peer_manager.check_rate_limit(&peer_id, path) {
+            RateLimitOutcome::Allowed => true,
+            RateLimitOutcome::Limited => {
+                gadget_logging::debug!(
+                    "Dropping {path:?} message from rate limited peer: {peer_id}"
+                );
+                false
+            }
+            RateLimitOutcome::Ban => {
+                self.ban_peer(
+                    peer_id,
+                    PenaltyReason::RateLimited(path.as_str().to_string()),
+                );
+                false
+            }
+        }
+    }
+
+    /// Lift any bans that have expired, and forget stale rate limit and violation state.
+    pub(crate) fn prune_expired_bans(&mut self) {
+        self.peer_manager.prune();
+        for peer_id in self.peer_manager.take_expired_bans() {
+            gadget_logging::info!("Ban on peer {peer_id} has expired");
+            let behaviour = self.swarm.behaviour_mut();
+            behaviour.gossipsub.remove_blacklisted_peer(&peer_id);
+            behaviour.blocked_peers.unblock_peer(peer_id);
+        }
+    }
+
     /// Handle local requests that are meant to be sent to the network.
     pub(crate) fn handle_intra_node_payload(&mut self, msg: IntraNodePayload) {
         let _enter = self.span.enter();
@@ -104,7 +184,10 @@ impl NetworkService<'_> {
                 // Send the outer payload in order to attach the topic to it
                 // "Requests are sent using Behaviour::send_request and the responses
                 // received as Message::Response via Event::Message."
-                self.swarm.behaviour_mut().p2p.send_request(&peer_id, req);
+                let request_id = self.swarm.behaviour_mut().p2p.send_request(&peer_id, req);
+                if let Some(response_tx) = msg.response_tx {
+                    self.rpc.insert_pending(request_id, response_tx);
+                }
             }
             (MessageType::Broadcast, GossipOrRequestResponse::Request(_)) => {
                 gadget_logging::error!("Broadcasting a request is not supported");
@@ -121,6 +204,20 @@ impl NetworkService<'_> {
         }
     }
 
+    /// Send the response to an inbound RPC request once its handler has completed
+    pub(crate) fn handle_rpc_response(&mut self, (channel, response): RpcResponse) {
+        let _enter = self.span.enter();
+        if self
+            .swarm
+            .behaviour_mut()
+            .p2p
+            .send_response(channel, response)
+            .is_err()
+        {
+            gadget_logging::warn!("Failed to send RPC response, the connection was closed");
+        }
+    }
+
     /// Handle inbound events from the networking layer
     #[allow(clippy::too_many_lines)]
     pub(crate) async fn handle_swarm_event(&mut self, event: SwarmEvent<MyBehaviourEvent>) {
@@ -172,9 +269,10 @@ impl NetworkService<'_> {
             ConnectionEstablished {
                 peer_id,
                 num_established,
+                endpoint,
                 ..
             } => {
-                self.handle_connection_established(peer_id, num_established.get())
+                self.handle_connection_established(peer_id, num_established.get(), endpoint)
                     .await;
             }
             ConnectionClosed {
@@ -270,6 +368,9 @@ pub struct GossipHandle {
     pub connected_peers: Arc<AtomicUsize>,
     pub public_key_to_libp2p_id: Arc<RwLock<BTreeMap<GossipMsgPublicKey, PeerId>>>,
     pub recent_messages: parking_lot::Mutex<LruCache<[u8; 32], ()>>,
+    pub peer_manager: Arc<PeerManager>,
+    pub peer_store: Arc<PeerStore>,
+    pub rpc: Arc<RpcRegistry>,
     pub my_id: GossipMsgPublicKey,
 }
 
@@ -294,12 +395,119 @@ impl GossipHandle {
             .copied()
             .collect()
     }
+
+    /// Subscribe to events emitted whenever a peer is penalised, banned or unbanned.
+    #[must_use]
+    pub fn peer_events(&self) -> tokio::sync::broadcast::Receiver<PeerEvent> {
+        self.peer_manager.subscribe()
+    }
+
+    /// Returns the peers this node has previously connected to, most recently seen first.
+    #[must_use]
+    pub fn known_peers(&self) -> Vec<KnownPeer> {
+        self.peer_store.known_peers()
+    }
+
+    /// Returns the libp2p IDs of all currently banned peers.
+    #[must_use]
+    pub fn banned_peers(&self) -> Vec<PeerId> {
+        self.peer_manager.banned_peers()
+    }
+
+    /// Send `request` to the peer with the operator key `peer` and wait for its response.
+    ///
+    /// The peer must have registered a handler for `Req` on this topic with
+    /// [`GossipHandle::register_handler`]. Requests also fail once the network's
+    /// [`NetworkConfig::request_timeout`](crate::setup::NetworkConfig::request_timeout) elapses,
+    /// whichever `timeout` is given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no handshake has been completed with the peer, the request could
+    /// not be delivered, the peer's handler failed, or no response arrived within `timeout`.
+    pub async fn request<Req, Resp>(
+        &self,
+        peer: &GossipMsgPublicKey,
+        request: &Req,
+        timeout: Duration,
+    ) -> Result<Resp, Error>
+    where
+        Req: RpcRequest,
+        Resp: DeserializeOwned,
+    {
+        let peer_id = self
+            .public_key_to_libp2p_id
+            .read()
+            .await
+            .get(peer)
+            .copied()
+            .ok_or_else(|| {
+                Error::NetworkError(format!(
+                    "No libp2p ID found for crypto public key: {peer:?}. No handshake happened?"
+                ))
+            })?;
+
+        let payload =
+            bincode::serialize(request).map_err(|err| Error::MessagingError(err.to_string()))?;
+        let (response_tx, response_rx) = oneshot::channel();
+        let payload = IntraNodePayload {
+            topic: self.topic.clone(),
+            payload: GossipOrRequestResponse::Request(MyBehaviourRequest::Rpc {
+                topic: self.topic.to_string(),
+                method: Req::METHOD.to_string(),
+                payload,
+            }),
+            message_type: MessageType::P2P(peer_id),
+            response_tx: Some(response_tx),
+        };
+        self.tx_to_outbound
+            .send(payload)
+            .map_err(|e| Error::NetworkError(format!("Failed to send intra-node payload: {e}")))?;
+
+        let response = tokio::time::timeout(timeout, response_rx)
+            .await
+            .map_err(|_| {
+                Error::NetworkError(format!(
+                    "{} request to {peer_id} timed out after {timeout:?}",
+                    Req::METHOD
+                ))
+            })?
+            .map_err(|_| Error::ChannelError("Network worker dropped the request".to_string()))??;
+
+        bincode::deserialize(&response).map_err(|err| Error::MessagingError(err.to_string()))
+    }
+
+    /// Register `handler` to serve requests of type `Req` sent to this topic, replacing any
+    /// previously registered handler.
+    ///
+    /// The handler is called with the operator key of the requesting peer. Returning an error
+    /// sends it back to the peer as the response.
+    pub fn register_handler<Req, Resp, E, F, Fut>(&self, handler: F)
+    where
+        Req: RpcRequest,
+        Resp: Serialize,
+        E: gadget_std::fmt::Display,
+        F: Fn(GossipMsgPublicKey, Req) -> Fut + Send + Sync + 'static,
+        Fut: gadget_std::future::Future<Output = Result<Resp, E>> + Send + 'static,
+    {
+        self.rpc
+            .register::<Req, Resp, E, F, Fut>(self.topic.to_string(), handler);
+    }
+
+    /// Stop serving requests of type `Req` on this topic.
+    ///
+    /// Returns `true` if a handler was registered.
+    pub fn unregister_handler<Req: RpcRequest>(&self) -> bool {
+        self.rpc.unregister::<Req>(self.topic.to_string())
+    }
 }
 
 pub struct IntraNodePayload {
     topic: IdentTopic,
     payload: GossipOrRequestResponse,
     message_type: MessageType,
+    /// Where to deliver the response to an RPC request
+    response_tx: Option<oneshot::Sender<Result<Vec<u8>, Error>>>,
 }
 
 impl gadget_std::fmt::Debug for IntraNodePayload {
@@ -335,6 +543,11 @@ pub enum MyBehaviourRequest {
         topic: String,
         raw_payload: Vec<u8>,
     },
+    Rpc {
+        topic: String,
+        method: String,
+        payload: Vec<u8>,
+    },
 }
 
 #[non_exhaustive]
@@ -345,6 +558,9 @@ pub enum MyBehaviourResponse {
         signature: GossipSignedMsgSignature,
     },
     MessageHandled,
+    Rpc {
+        result: Result<Vec<u8>, String>,
+    },
 }
 
 enum MessageType {
@@ -423,6 +639,7 @@ impl Network for GossipHandle {
             topic: self.topic.clone(),
             payload: payload_inner,
             message_type,
+            response_tx: None,
         };
 
         self.tx_to_outbound
@@ -5,6 +5,7 @@ use crate::key_types::Curve;
 use gadget_crypto::KeyType;
 use gadget_std as std;
 use itertools::Itertools;
+use libp2p::core::ConnectedPoint;
 use libp2p::PeerId;
 
 impl NetworkService<'_> {
@@ -13,8 +14,25 @@ impl NetworkService<'_> {
         &mut self,
         peer_id: PeerId,
         _num_established: u32,
+        endpoint: ConnectedPoint,
     ) {
         gadget_logging::debug!("Connection established");
+        if self.peer_manager.is_banned(&peer_id) {
+            gadget_logging::warn!("Disconnecting banned peer: {peer_id}");
+            self.block_peer(peer_id);
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+            return;
+        }
+
+        // Only dialled addresses are worth remembering, since the remote address of an inbound
+        // connection is usually an ephemeral port. Listen addresses are learned through identify.
+        if endpoint.is_dialer() {
+            self.peer_store
+                .record_seen(peer_id, [endpoint.get_remote_address().clone()]);
+        } else {
+            self.peer_store.record_seen(peer_id, []);
+        }
+
         if !self
             .public_key_to_libp2p_id
             .read()
@@ -56,6 +74,7 @@ impl NetworkService<'_> {
     ) {
         gadget_logging::trace!("Connection closed");
         if num_established == 0 {
+            self.peer_manager.peer_disconnected(&peer_id);
             self.swarm
                 .behaviour_mut()
                 .gossipsub
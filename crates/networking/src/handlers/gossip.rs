@@ -1,10 +1,11 @@
 #![allow(unused_results)]
 
 use crate::gossip::{GossipMessage, NetworkService};
+use crate::peer_manager::{InboundPath, PenaltyReason};
 use gadget_std::string::ToString;
 use gadget_std::sync::atomic::AtomicUsize;
 use gadget_std::sync::Arc;
-use libp2p::gossipsub::TopicHash;
+use libp2p::gossipsub::{MessageAcceptance, TopicHash};
 use libp2p::{gossipsub, PeerId};
 
 impl NetworkService<'_> {
@@ -68,30 +69,54 @@ impl NetworkService<'_> {
     #[tracing::instrument(
         skip(self, message),
         fields(
-            %_message_id,
-            %_propagation_source,
+            %message_id,
+            %propagation_source,
             source = ?message.source
         )
     )]
     async fn handle_gossip_message(
         &mut self,
-        _propagation_source: PeerId,
-        _message_id: gossipsub::MessageId,
+        propagation_source: PeerId,
+        message_id: gossipsub::MessageId,
         message: gossipsub::Message,
     ) {
         let Some(origin) = message.source else {
             gadget_logging::error!("Got message from unknown peer");
+            self.report_gossip_validation(
+                &message_id,
+                &propagation_source,
+                MessageAcceptance::Reject,
+            );
             return;
         };
 
         // Reject messages from self
         if origin == self.my_id {
+            self.report_gossip_validation(
+                &message_id,
+                &propagation_source,
+                MessageAcceptance::Ignore,
+            );
+            return;
+        }
+
+        if !self.enforce_rate_limit(propagation_source, InboundPath::Gossip) {
+            self.report_gossip_validation(
+                &message_id,
+                &propagation_source,
+                MessageAcceptance::Ignore,
+            );
             return;
         }
 
         gadget_logging::trace!("Got message from peer: {origin}");
         match bincode::deserialize::<GossipMessage>(&message.data) {
             Ok(GossipMessage { topic, raw_payload }) => {
+                self.report_gossip_validation(
+                    &message_id,
+                    &propagation_source,
+                    MessageAcceptance::Accept,
+                );
                 if let Some((_, tx, _)) = self
                     .inbound_mapping
                     .iter()
@@ -106,7 +131,31 @@ impl NetworkService<'_> {
             }
             Err(e) => {
                 gadget_logging::error!("Failed to deserialize message (handlers/gossip): {e}");
+                // Rejecting applies the gossipsub invalid message penalty to the propagation source
+                self.report_gossip_validation(
+                    &message_id,
+                    &propagation_source,
+                    MessageAcceptance::Reject,
+                );
+                self.penalise_peer(propagation_source, PenaltyReason::MalformedMessage);
             }
         }
     }
+
+    /// Report the validation result of a gossip message, since messages are only forwarded once validated.
+    fn report_gossip_validation(
+        &mut self,
+        message_id: &gossipsub::MessageId,
+        propagation_source: &PeerId,
+        acceptance: MessageAcceptance,
+    ) {
+        if let Err(e) = self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .report_message_validation_result(message_id, propagation_source, acceptance)
+        {
+            gadget_logging::warn!("Failed to report message validation result: {e}");
+        }
+    }
 }
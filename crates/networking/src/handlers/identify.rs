@@ -18,6 +18,7 @@ impl NetworkService<'_> {
                     "Received identify event from peer: {peer_id} with info: {info_lines}"
                 );
                 self.swarm.add_external_address(info.observed_addr);
+                self.peer_store.record_seen(peer_id, info.listen_addrs);
             }
             Sent { peer_id, .. } => {
                 gadget_logging::trace!("Sent identify event to peer: {peer_id}");
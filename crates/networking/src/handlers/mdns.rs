@@ -8,6 +8,10 @@ impl NetworkService<'_> {
         match event {
             Discovered(list) => {
                 for (peer_id, multiaddr) in list {
+                    if self.peer_manager.is_banned(&peer_id) {
+                        gadget_logging::trace!("Ignoring discovered banned peer: {peer_id}");
+                        continue;
+                    }
                     gadget_logging::trace!("discovered a new peer: {peer_id} on {multiaddr}");
                     self.swarm
                         .behaviour_mut()
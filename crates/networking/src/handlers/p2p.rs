@@ -2,6 +2,7 @@
 
 use crate::gossip::{MyBehaviourRequest, MyBehaviourResponse, NetworkService};
 use crate::key_types::Curve;
+use crate::peer_manager::{InboundPath, PenaltyReason};
 use gadget_crypto::KeyType;
 use gadget_std::string::ToString;
 use gadget_std::sync::atomic::Ordering;
@@ -26,6 +27,10 @@ impl NetworkService<'_> {
                 error,
             } => {
                 gadget_logging::error!("Failed to send message to peer: {peer} with request_id: {request_id} and error: {error}");
+                self.rpc.complete(
+                    request_id,
+                    Err(crate::Error::NetworkError(error.to_string())),
+                );
             }
             InboundFailure {
                 peer,
@@ -81,7 +86,12 @@ impl NetworkService<'_> {
         req: MyBehaviourRequest,
         channel: request_response::ResponseChannel<MyBehaviourResponse>,
     ) {
-        use crate::gossip::MyBehaviourRequest::{Handshake, Message};
+        use crate::gossip::MyBehaviourRequest::{Handshake, Message, Rpc};
+        if !self.enforce_rate_limit(peer, InboundPath::Request) {
+            // Dropping the response channel notifies the peer of the failure
+            return;
+        }
+
         let result = match req {
             Handshake {
                 public_key,
@@ -93,9 +103,11 @@ impl NetworkService<'_> {
                 let valid = <Curve as KeyType>::verify(&public_key, &msg, &signature);
                 if !valid {
                     gadget_logging::warn!("Invalid initial handshake signature from peer: {peer}");
+                    self.penalise_peer(peer, PenaltyReason::InvalidHandshake);
                     let _ = self.swarm.disconnect_peer_id(peer);
                     return;
                 }
+                self.peer_store.record_public_key(peer, public_key);
                 if self
                     .public_key_to_libp2p_id
                     .write()
@@ -145,6 +157,64 @@ impl NetworkService<'_> {
                     .p2p
                     .send_response(channel, MyBehaviourResponse::MessageHandled)
             }
+            Rpc {
+                topic,
+                method,
+                payload,
+            } => {
+                // Only serve peers whose operator key we know
+                let public_key = self
+                    .public_key_to_libp2p_id
+                    .read()
+                    .await
+                    .iter()
+                    .find_map(|(public_key, id)| (*id == peer).then_some(*public_key));
+                let Some(public_key) = public_key else {
+                    gadget_logging::debug!(
+                        "Rejecting {method} request from peer without handshake: {peer}"
+                    );
+                    let response = MyBehaviourResponse::Rpc {
+                        result: Err("Handshake not completed".to_string()),
+                    };
+                    if self
+                        .swarm
+                        .behaviour_mut()
+                        .p2p
+                        .send_response(channel, response)
+                        .is_err()
+                    {
+                        gadget_logging::error!("Failed to send response for {request_id}");
+                    }
+                    return;
+                };
+
+                match self
+                    .rpc
+                    .dispatch(topic, method.clone(), public_key, payload)
+                {
+                    Ok(handler) => {
+                        // Run the handler off the network worker, the response is sent once it completes
+                        let tx_rpc_response = self.tx_rpc_response.clone();
+                        tokio::spawn(async move {
+                            let result = handler.await;
+                            let _ = tx_rpc_response
+                                .send((channel, MyBehaviourResponse::Rpc { result }));
+                        });
+                        return;
+                    }
+                    Err(reason) => {
+                        gadget_logging::debug!(
+                            "Rejecting {method} request from peer {peer}: {reason}"
+                        );
+                        self.swarm.behaviour_mut().p2p.send_response(
+                            channel,
+                            MyBehaviourResponse::Rpc {
+                                result: Err(reason),
+                            },
+                        )
+                    }
+                }
+            }
         };
         if result.is_err() {
             gadget_logging::error!("Failed to send response for {request_id}");
@@ -158,7 +228,7 @@ impl NetworkService<'_> {
         request_id: request_response::OutboundRequestId,
         message: MyBehaviourResponse,
     ) {
-        use crate::gossip::MyBehaviourResponse::{Handshaked, MessageHandled};
+        use crate::gossip::MyBehaviourResponse::{Handshaked, MessageHandled, Rpc};
         match message {
             Handshaked {
                 public_key,
@@ -171,7 +241,7 @@ impl NetworkService<'_> {
                     gadget_logging::warn!(
                         "Invalid handshake-acknowledgement signature from peer: {peer}"
                     );
-                    // TODO: report this peer.
+                    self.penalise_peer(peer, PenaltyReason::InvalidHandshake);
                     self.public_key_to_libp2p_id
                         .write()
                         .await
@@ -179,6 +249,7 @@ impl NetworkService<'_> {
                     let _ = self.swarm.disconnect_peer_id(peer);
                     return;
                 }
+                self.peer_store.record_public_key(peer, public_key);
                 if self
                     .public_key_to_libp2p_id
                     .write()
@@ -190,6 +261,10 @@ impl NetworkService<'_> {
                 }
             }
             MessageHandled => {}
+            Rpc { result } => {
+                self.rpc
+                    .complete(request_id, result.map_err(crate::Error::ProtocolError));
+            }
         }
     }
 }
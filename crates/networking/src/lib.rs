@@ -4,12 +4,17 @@ pub mod gossip;
 pub mod handlers;
 pub mod messaging;
 pub mod networking;
+pub mod peer_manager;
+pub mod peer_store;
 #[cfg(feature = "round-based-compat")]
 pub mod round_based_compat;
+pub mod rpc;
 #[cfg(feature = "round-based-compat")]
 pub use round_based;
 
 pub mod setup;
+pub mod simulated;
+mod storage;
 
 use gadget_std::string::String;
 
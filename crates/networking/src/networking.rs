@@ -377,6 +377,12 @@ impl NetworkMultiplexer {
                             while let Some(Reverse(PendingMessage { seq, message: _ })) =
                                 pending.peek()
                             {
+                                if *seq < *expected_seq {
+                                    // Already delivered, this is a duplicate
+                                    let _ = pending.pop();
+                                    continue;
+                                }
+
                                 if *seq != *expected_seq {
                                     break;
                                 }
@@ -404,6 +410,12 @@ impl NetworkMultiplexer {
                             while let Some(Reverse(PendingMessage { seq, message: _ })) =
                                 pending.peek()
                             {
+                                if *seq < *expected_seq {
+                                    // Already delivered, this is a duplicate
+                                    let _ = pending.pop();
+                                    continue;
+                                }
+
                                 if *seq != *expected_seq {
                                     break;
                                 }
@@ -0,0 +1,727 @@
+//! Peer scoring, rate limiting and ban list management for the gossip network.
+
+use crate::storage::{load_json_list, store_json_list};
+use gadget_std::collections::HashMap;
+use gadget_std::hash::Hash;
+use gadget_std::path::{Path, PathBuf};
+use gadget_std::string::{String, ToString};
+use gadget_std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use gadget_std::vec::Vec;
+use libp2p::gossipsub::{PeerScoreParams, PeerScoreThresholds};
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// The name of the file (relative to the network data directory) holding the persisted ban list.
+pub const BAN_LIST_FILE_NAME: &str = "banned_peers.json";
+
+/// Capacity of the [`PeerEvent`] broadcast channel.
+const PEER_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// The maximum number of rate limit windows, and of peers with violations, tracked at once. Once
+/// reached, stale entries are pruned, and then the oldest entry is dropped to make room for a new one.
+pub const MAX_TRACKED_PEERS: usize = 4096;
+
+/// A per-peer inbound rate limit, expressed as a maximum number of messages per window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The maximum number of messages accepted from a single peer within `window`.
+    pub max_messages: u32,
+    /// The length of the rate limiting window.
+    pub window: Duration,
+}
+
+impl RateLimit {
+    #[must_use]
+    pub const fn new(max_messages: u32, window: Duration) -> Self {
+        Self {
+            max_messages,
+            window,
+        }
+    }
+}
+
+/// Configuration for the [`PeerManager`].
+///
+/// The defaults enable gossipsub peer scoring with the `libp2p` default parameters, allow
+/// 1000 gossip messages and 500 direct requests per peer every 10 seconds, forgive one violation
+/// every 10 minutes, and ban a peer for one hour after 5 violations.
+#[derive(Debug, Clone)]
+pub struct PeerManagerConfig {
+    /// Gossipsub peer scoring parameters. Scoring is disabled if `None`.
+    pub score_params: Option<PeerScoreParams>,
+    /// Gossipsub peer scoring thresholds, used when `score_params` is set.
+    pub score_thresholds: PeerScoreThresholds,
+    /// Inbound rate limit applied to gossip messages, keyed by the propagating peer.
+    pub gossip_rate_limit: Option<RateLimit>,
+    /// Inbound rate limit applied to request-response messages.
+    pub request_rate_limit: Option<RateLimit>,
+    /// The number of penalties a peer may accrue before it is banned.
+    pub max_violations: u32,
+    /// How often one of a peer's violations is forgiven. Violations never decay if `None`.
+    pub violation_decay: Option<Duration>,
+    /// How long a ban lasts. Bans are permanent if `None`.
+    pub ban_duration: Option<Duration>,
+}
+
+impl PeerManagerConfig {
+    fn rate_limit(&self, path: InboundPath) -> Option<RateLimit> {
+        match path {
+            InboundPath::Gossip => self.gossip_rate_limit,
+            InboundPath::Request => self.request_rate_limit,
+        }
+    }
+}
+
+impl Default for PeerManagerConfig {
+    fn default() -> Self {
+        Self {
+            score_params: Some(PeerScoreParams::default()),
+            score_thresholds: PeerScoreThresholds::default(),
+            gossip_rate_limit: Some(RateLimit::new(1000, Duration::from_secs(10))),
+            request_rate_limit: Some(RateLimit::new(500, Duration::from_secs(10))),
+            max_violations: 5,
+            violation_decay: Some(Duration::from_secs(10 * 60)),
+            ban_duration: Some(Duration::from_secs(60 * 60)),
+        }
+    }
+}
+
+/// The inbound path a message arrived on, used to select the applicable [`RateLimit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InboundPath {
+    Gossip,
+    Request,
+}
+
+impl InboundPath {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InboundPath::Gossip => "gossip",
+            InboundPath::Request => "request",
+        }
+    }
+}
+
+/// The reason a peer was penalised or banned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PenaltyReason {
+    /// The peer exceeded the inbound rate limit on the given path.
+    RateLimited(String),
+    /// The peer sent a message that failed to decode.
+    MalformedMessage,
+    /// The peer sent a handshake with an invalid signature.
+    InvalidHandshake,
+    /// The peer was banned by the operator.
+    Manual,
+}
+
+impl PenaltyReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PenaltyReason::RateLimited(_) => "rate_limited",
+            PenaltyReason::MalformedMessage => "malformed_message",
+            PenaltyReason::InvalidHandshake => "invalid_handshake",
+            PenaltyReason::Manual => "manual",
+        }
+    }
+}
+
+impl gadget_std::fmt::Display for PenaltyReason {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        match self {
+            PenaltyReason::RateLimited(path) => write!(f, "rate limit exceeded on {path} path"),
+            PenaltyReason::MalformedMessage => write!(f, "malformed message"),
+            PenaltyReason::InvalidHandshake => write!(f, "invalid handshake signature"),
+            PenaltyReason::Manual => write!(f, "banned manually"),
+        }
+    }
+}
+
+/// Events emitted by the [`PeerManager`] whenever a peer is penalised, banned or unbanned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerEvent {
+    Penalised {
+        peer_id: PeerId,
+        reason: PenaltyReason,
+        violations: u32,
+    },
+    Banned {
+        peer_id: PeerId,
+        reason: PenaltyReason,
+        until: Option<SystemTime>,
+    },
+    Unbanned {
+        peer_id: PeerId,
+    },
+}
+
+/// The outcome of a rate limit check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitOutcome {
+    /// The message is within the limit.
+    Allowed,
+    /// The message exceeds the limit and should be dropped.
+    Limited,
+    /// The message exceeds the limit and the peer has now accrued enough violations to be banned.
+    Ban,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BanEntry {
+    peer_id: String,
+    reason: PenaltyReason,
+    /// Seconds since the UNIX epoch at which the ban expires, or `None` for a permanent ban.
+    until: Option<u64>,
+}
+
+impl BanEntry {
+    fn expiry(&self) -> Option<SystemTime> {
+        self.until
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expiry().is_some_and(|until| until <= now)
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateWindow {
+    started: Option<Instant>,
+    count: u32,
+}
+
+#[derive(Debug)]
+struct Violations {
+    count: u32,
+    /// When the count last decayed, or was first incremented
+    decayed_at: Instant,
+}
+
+impl Violations {
+    /// Forgive one violation for every full `interval` elapsed since the count last decayed
+    fn decay(&mut self, interval: Duration, now: Instant) {
+        if interval.is_zero() {
+            self.count = 0;
+            self.decayed_at = now;
+            return;
+        }
+
+        let elapsed = now.duration_since(self.decayed_at);
+        let periods = u32::try_from(elapsed.as_nanos() / interval.as_nanos()).unwrap_or(u32::MAX);
+        if periods == 0 {
+            return;
+        }
+        self.count = self.count.saturating_sub(periods);
+        self.decayed_at = if self.count == 0 {
+            now
+        } else {
+            self.decayed_at + interval * periods
+        };
+    }
+}
+
+#[derive(Debug, Default)]
+struct PeerManagerState {
+    windows: HashMap<(PeerId, InboundPath), RateWindow>,
+    violations: HashMap<PeerId, Violations>,
+    banned: HashMap<PeerId, BanEntry>,
+}
+
+impl PeerManagerState {
+    /// Forget rate limit windows that have ended, and violations that have decayed to zero
+    fn prune(&mut self, config: &PeerManagerConfig, now: Instant) {
+        self.windows.retain(
+            |(_, path), window| match (config.rate_limit(*path), window.started) {
+                (Some(limit), Some(started)) => now.duration_since(started) < limit.window,
+                _ => false,
+            },
+        );
+        if let Some(interval) = config.violation_decay {
+            self.violations.retain(|_, violations| {
+                violations.decay(interval, now);
+                violations.count > 0
+            });
+        }
+    }
+
+    /// Make room for a new entry in `map` once it holds [`MAX_TRACKED_PEERS`], pruning stale entries
+    /// first and then dropping the entry that sorts first by `age`
+    fn make_room<K, V, O>(
+        &mut self,
+        config: &PeerManagerConfig,
+        now: Instant,
+        map: fn(&mut Self) -> &mut HashMap<K, V>,
+        age: impl Fn(&V) -> O,
+    ) where
+        K: Copy + Eq + Hash,
+        O: Ord,
+    {
+        if map(self).len() < MAX_TRACKED_PEERS {
+            return;
+        }
+        self.prune(config, now);
+
+        let entries = map(self);
+        if entries.len() < MAX_TRACKED_PEERS {
+            return;
+        }
+        let oldest = entries
+            .iter()
+            .min_by_key(|(_, value)| age(value))
+            .map(|(key, _)| *key);
+        if let Some(oldest) = oldest {
+            let _ = entries.remove(&oldest);
+        }
+    }
+}
+
+/// Tracks per-peer rate limits, penalties and the (optionally persisted) ban list.
+///
+/// The manager only records decisions; enforcing them against the swarm (blocking connections,
+/// blacklisting in gossipsub) is done by the network service.
+#[derive(Debug)]
+pub struct PeerManager {
+    config: PeerManagerConfig,
+    ban_list_path: Option<PathBuf>,
+    state: Mutex<PeerManagerState>,
+    events: broadcast::Sender<PeerEvent>,
+}
+
+impl PeerManager {
+    /// Create a new `PeerManager`, loading any persisted ban list from `data_dir`.
+    ///
+    /// Expired bans are dropped while loading.
+    #[must_use]
+    pub fn new(config: PeerManagerConfig, data_dir: Option<&Path>) -> Self {
+        let ban_list_path = data_dir.map(|dir| dir.join(BAN_LIST_FILE_NAME));
+        let banned = ban_list_path
+            .as_deref()
+            .map(load_ban_list)
+            .unwrap_or_default();
+
+        let (events, _) = broadcast::channel(PEER_EVENT_CHANNEL_CAPACITY);
+        Self {
+            config,
+            ban_list_path,
+            state: Mutex::new(PeerManagerState {
+                banned,
+                ..Default::default()
+            }),
+            events,
+        }
+    }
+
+    #[must_use]
+    pub fn config(&self) -> &PeerManagerConfig {
+        &self.config
+    }
+
+    /// Subscribe to [`PeerEvent`]s.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<PeerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Returns `true` if the peer is currently banned.
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.state
+            .lock()
+            .banned
+            .get(peer_id)
+            .is_some_and(|entry| !entry.is_expired(SystemTime::now()))
+    }
+
+    /// Forget the rate limit windows of a peer that is no longer connected.
+    ///
+    /// Its violations are kept until they decay, so that reconnecting doesn't clear them.
+    pub fn peer_disconnected(&self, peer_id: &PeerId) {
+        self.state
+            .lock()
+            .windows
+            .retain(|(peer, _), _| peer != peer_id);
+    }
+
+    /// Forget rate limit windows that have ended, and violations that have fully decayed according to
+    /// [`PeerManagerConfig::violation_decay`].
+    pub fn prune(&self) {
+        self.state.lock().prune(&self.config, Instant::now());
+    }
+
+    /// Remove all bans that have expired, returning the peers that are no longer banned.
+    pub fn take_expired_bans(&self) -> Vec<PeerId> {
+        let now = SystemTime::now();
+        let expired = {
+            let mut state = self.state.lock();
+            let expired = state
+                .banned
+                .iter()
+                .filter(|(_, entry)| entry.is_expired(now))
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<_>>();
+            for peer_id in &expired {
+                let _ = state.banned.remove(peer_id);
+            }
+            expired
+        };
+
+        if !expired.is_empty() {
+            self.persist();
+            for peer_id in &expired {
+                let _ = self.events.send(PeerEvent::Unbanned { peer_id: *peer_id });
+            }
+        }
+
+        expired
+    }
+
+    /// Returns all peers that are currently banned.
+    pub fn banned_peers(&self) -> Vec<PeerId> {
+        let now = SystemTime::now();
+        self.state
+            .lock()
+            .banned
+            .iter()
+            .filter(|(_, entry)| !entry.is_expired(now))
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
+    /// Record an inbound message from `peer_id` on `path` and check it against the configured limit.
+    pub fn check_rate_limit(&self, peer_id: &PeerId, path: InboundPath) -> RateLimitOutcome {
+        let Some(limit) = self.config.rate_limit(path) else {
+            return RateLimitOutcome::Allowed;
+        };
+
+        let now = Instant::now();
+        let exceeded = {
+            let mut state = self.state.lock();
+            if !state.windows.contains_key(&(*peer_id, path)) {
+                state.make_room(
+                    &self.config,
+                    now,
+                    |state| &mut state.windows,
+                    |window| window.started,
+                );
+            }
+            let window = state.windows.entry((*peer_id, path)).or_default();
+            match window.started {
+                Some(started) if now.duration_since(started) < limit.window => {
+                    window.count = window.count.saturating_add(1);
+                }
+                _ => {
+                    window.started = Some(now);
+                    window.count = 1;
+                }
+            }
+            window.count > limit.max_messages
+        };
+
+        if !exceeded {
+            return RateLimitOutcome::Allowed;
+        }
+
+        metrics::counter!("gadget_p2p_rate_limited_messages_total", "path" => path.as_str())
+            .increment(1);
+        if self.penalise(
+            peer_id,
+            PenaltyReason::RateLimited(path.as_str().to_string()),
+        ) {
+            RateLimitOutcome::Ban
+        } else {
+            RateLimitOutcome::Limited
+        }
+    }
+
+    /// Record a penalty against `peer_id`, after forgiving the violations that have decayed
+    /// according to [`PeerManagerConfig::violation_decay`].
+    ///
+    /// Returns `true` if the peer has reached [`PeerManagerConfig::max_violations`] and should be banned.
+    pub fn penalise(&self, peer_id: &PeerId, reason: PenaltyReason) -> bool {
+        let now = Instant::now();
+        let violations = {
+            let mut state = self.state.lock();
+            if !state.violations.contains_key(peer_id) {
+                // Forgive the peers with the fewest violations first
+                state.make_room(
+                    &self.config,
+                    now,
+                    |state| &mut state.violations,
+                    |violations| (violations.count, violations.decayed_at),
+                );
+            }
+            let violations = state.violations.entry(*peer_id).or_insert(Violations {
+                count: 0,
+                decayed_at: now,
+            });
+            if let Some(interval) = self.config.violation_decay {
+                violations.decay(interval, now);
+            }
+            violations.count = violations.count.saturating_add(1);
+            violations.count
+        };
+
+        gadget_logging::warn!("Penalised peer {peer_id} ({reason}), violations: {violations}");
+        metrics::counter!("gadget_p2p_peer_penalties_total", "reason" => reason.as_str())
+            .increment(1);
+        let _ = self.events.send(PeerEvent::Penalised {
+            peer_id: *peer_id,
+            reason,
+            violations,
+        });
+
+        violations >= self.config.max_violations
+    }
+
+    /// Ban `peer_id`, persisting the ban list if a data directory was configured.
+    pub fn ban(&self, peer_id: &PeerId, reason: PenaltyReason) {
+        let until = self
+            .config
+            .ban_duration
+            .map(|duration| SystemTime::now() + duration);
+        let entry = BanEntry {
+            peer_id: peer_id.to_base58(),
+            reason: reason.clone(),
+            until: until.map(|until| {
+                until
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            }),
+        };
+
+        {
+            let mut state = self.state.lock();
+            let _ = state.banned.insert(*peer_id, entry);
+            let _ = state.violations.remove(peer_id);
+            state.windows.retain(|(peer, _), _| peer != peer_id);
+        }
+        self.persist();
+
+        gadget_logging::warn!("Banned peer {peer_id}: {reason}");
+        metrics::counter!("gadget_p2p_peer_bans_total", "reason" => reason.as_str()).increment(1);
+        let _ = self.events.send(PeerEvent::Banned {
+            peer_id: *peer_id,
+            reason,
+            until,
+        });
+    }
+
+    /// Lift the ban on `peer_id`, if any.
+    ///
+    /// Returns `true` if the peer was banned.
+    pub fn unban(&self, peer_id: &PeerId) -> bool {
+        let removed = self.state.lock().banned.remove(peer_id).is_some();
+        if removed {
+            self.persist();
+            let _ = self.events.send(PeerEvent::Unbanned { peer_id: *peer_id });
+        }
+        removed
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.ban_list_path else {
+            return;
+        };
+
+        let entries = self
+            .state
+            .lock()
+            .banned
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        if let Err(e) = store_json_list(path, &entries) {
+            gadget_logging::error!("Failed to persist ban list to {}: {e}", path.display());
+        }
+    }
+}
+
+fn load_ban_list(path: &Path) -> HashMap<PeerId, BanEntry> {
+    let entries = load_json_list::<BanEntry>(path, "ban list");
+
+    let now = SystemTime::now();
+    entries
+        .into_iter()
+        .filter(|entry| !entry.is_expired(now))
+        .filter_map(|entry| match entry.peer_id.parse::<PeerId>() {
+            Ok(peer_id) => Some((peer_id, entry)),
+            Err(e) => {
+                gadget_logging::warn!("Skipping invalid peer id in ban list: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_messages: u32, max_violations: u32) -> PeerManagerConfig {
+        PeerManagerConfig {
+            gossip_rate_limit: Some(RateLimit::new(max_messages, Duration::from_secs(60))),
+            max_violations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rate_limit_escalates_to_ban() {
+        let manager = PeerManager::new(config(2, 2), None);
+        let peer = PeerId::random();
+        let mut events = manager.subscribe();
+
+        assert_eq!(
+            manager.check_rate_limit(&peer, InboundPath::Gossip),
+            RateLimitOutcome::Allowed
+        );
+        assert_eq!(
+            manager.check_rate_limit(&peer, InboundPath::Gossip),
+            RateLimitOutcome::Allowed
+        );
+        assert_eq!(
+            manager.check_rate_limit(&peer, InboundPath::Gossip),
+            RateLimitOutcome::Limited
+        );
+        assert_eq!(
+            manager.check_rate_limit(&peer, InboundPath::Gossip),
+            RateLimitOutcome::Ban
+        );
+        assert!(matches!(
+            events.try_recv(),
+            Ok(PeerEvent::Penalised { violations: 1, .. })
+        ));
+
+        // Other peers and paths are tracked independently
+        assert_eq!(
+            manager.check_rate_limit(&PeerId::random(), InboundPath::Gossip),
+            RateLimitOutcome::Allowed
+        );
+        assert_eq!(
+            manager.check_rate_limit(&peer, InboundPath::Request),
+            RateLimitOutcome::Allowed
+        );
+    }
+
+    #[test]
+    fn violations_decay() {
+        let peer = PeerId::random();
+        let manager = PeerManager::new(
+            PeerManagerConfig {
+                violation_decay: Some(Duration::ZERO),
+                ..config(1, 2)
+            },
+            None,
+        );
+        assert!(!manager.penalise(&peer, PenaltyReason::MalformedMessage));
+        assert!(!manager.penalise(&peer, PenaltyReason::MalformedMessage));
+
+        let manager = PeerManager::new(
+            PeerManagerConfig {
+                violation_decay: None,
+                ..config(1, 2)
+            },
+            None,
+        );
+        assert!(!manager.penalise(&peer, PenaltyReason::MalformedMessage));
+        assert!(manager.penalise(&peer, PenaltyReason::MalformedMessage));
+
+        let start = Instant::now();
+        let mut violations = Violations {
+            count: 3,
+            decayed_at: start,
+        };
+        let interval = Duration::from_secs(60);
+        violations.decay(interval, start + Duration::from_secs(90));
+        assert_eq!(violations.count, 2);
+        assert_eq!(violations.decayed_at, start + interval);
+        violations.decay(interval, start + Duration::from_secs(600));
+        assert_eq!(violations.count, 0);
+    }
+
+    #[test]
+    fn stale_state_is_pruned() {
+        let manager = PeerManager::new(
+            PeerManagerConfig {
+                violation_decay: Some(Duration::ZERO),
+                ..config(10, 10)
+            },
+            None,
+        );
+        let (connected, disconnected) = (PeerId::random(), PeerId::random());
+        for peer in [&connected, &disconnected] {
+            let _ = manager.check_rate_limit(peer, InboundPath::Gossip);
+            let _ = manager.penalise(peer, PenaltyReason::MalformedMessage);
+        }
+
+        manager.peer_disconnected(&disconnected);
+        manager.prune();
+        let state = manager.state.lock();
+        assert!(state
+            .windows
+            .contains_key(&(connected, InboundPath::Gossip)));
+        assert!(!state
+            .windows
+            .contains_key(&(disconnected, InboundPath::Gossip)));
+        assert!(state.violations.is_empty());
+    }
+
+    #[test]
+    fn tracked_peers_are_capped() {
+        let manager = PeerManager::new(
+            PeerManagerConfig {
+                violation_decay: None,
+                ..config(10, 10)
+            },
+            None,
+        );
+        for _ in 0..=MAX_TRACKED_PEERS {
+            let peer = PeerId::random();
+            let _ = manager.check_rate_limit(&peer, InboundPath::Gossip);
+            let _ = manager.penalise(&peer, PenaltyReason::MalformedMessage);
+        }
+
+        let state = manager.state.lock();
+        assert_eq!(state.windows.len(), MAX_TRACKED_PEERS);
+        assert_eq!(state.violations.len(), MAX_TRACKED_PEERS);
+    }
+
+    #[test]
+    fn ban_list_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let banned = PeerId::random();
+        let unbanned = PeerId::random();
+
+        let manager = PeerManager::new(PeerManagerConfig::default(), Some(dir.path()));
+        manager.ban(&banned, PenaltyReason::Manual);
+        manager.ban(&unbanned, PenaltyReason::InvalidHandshake);
+        assert!(manager.unban(&unbanned));
+        drop(manager);
+
+        let manager = PeerManager::new(PeerManagerConfig::default(), Some(dir.path()));
+        assert!(manager.is_banned(&banned));
+        assert!(!manager.is_banned(&unbanned));
+        assert_eq!(manager.banned_peers(), vec![banned]);
+    }
+
+    #[test]
+    fn expired_bans_are_lifted() {
+        let manager = PeerManager::new(
+            PeerManagerConfig {
+                ban_duration: Some(Duration::ZERO),
+                ..Default::default()
+            },
+            None,
+        );
+        let peer = PeerId::random();
+        manager.ban(&peer, PenaltyReason::Manual);
+        assert!(!manager.is_banned(&peer));
+        assert!(manager.banned_peers().is_empty());
+        assert_eq!(manager.take_expired_bans(), vec![peer]);
+        assert!(manager.take_expired_bans().is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+//! Persistent store of known peers, used to reconnect to previously seen peers on restart.
+
+use crate::key_types::GossipMsgPublicKey;
+use crate::storage::{load_json_list, store_json_list};
+use gadget_std::collections::HashMap;
+use gadget_std::path::{Path, PathBuf};
+use gadget_std::string::{String, ToString};
+use gadget_std::time::{Duration, SystemTime, UNIX_EPOCH};
+use gadget_std::vec::Vec;
+use libp2p::{Multiaddr, PeerId};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// The name of the file (relative to the network data directory) holding the known peers.
+pub const PEER_STORE_FILE_NAME: &str = "known_peers.json";
+
+/// Peers that have not been seen for longer than this are dropped from the store.
+pub const DEFAULT_PEER_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The maximum number of peers remembered. Once reached, the least recently seen peer is forgotten to
+/// make room for a new one.
+pub const MAX_KNOWN_PEERS: usize = 1024;
+
+/// The maximum number of addresses remembered for a single peer. Once reached, the least recently
+/// seen address is forgotten to make room for a new one.
+const MAX_ADDRESSES_PER_PEER: usize = 16;
+
+/// A peer that this node has previously connected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownPeer {
+    pub peer_id: PeerId,
+    /// Addresses the peer can be dialled on, from least to most recently seen
+    pub addresses: Vec<Multiaddr>,
+    /// When the peer was last connected
+    pub last_seen: SystemTime,
+    /// The operator key the peer proved ownership of during the handshake, if any
+    pub public_key: Option<GossipMsgPublicKey>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KnownPeerEntry {
+    peer_id: String,
+    addresses: Vec<String>,
+    /// Seconds since the UNIX epoch
+    last_seen: u64,
+    public_key: Option<GossipMsgPublicKey>,
+}
+
+impl From<&KnownPeer> for KnownPeerEntry {
+    fn from(peer: &KnownPeer) -> Self {
+        Self {
+            peer_id: peer.peer_id.to_base58(),
+            addresses: peer.addresses.iter().map(ToString::to_string).collect(),
+            last_seen: peer
+                .last_seen
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            public_key: peer.public_key,
+        }
+    }
+}
+
+impl TryFrom<KnownPeerEntry> for KnownPeer {
+    type Error = String;
+
+    fn try_from(entry: KnownPeerEntry) -> Result<Self, Self::Error> {
+        let peer_id = entry.peer_id.parse::<PeerId>().map_err(|e| e.to_string())?;
+        let mut addresses = Vec::new();
+        for address in entry
+            .addresses
+            .iter()
+            .filter_map(|addr| addr.parse::<Multiaddr>().ok())
+        {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+        if let Some(excess) = addresses.len().checked_sub(MAX_ADDRESSES_PER_PEER) {
+            let _ = addresses.drain(..excess);
+        }
+        Ok(Self {
+            peer_id,
+            addresses,
+            last_seen: UNIX_EPOCH + Duration::from_secs(entry.last_seen),
+            public_key: entry.public_key,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct PeerStoreState {
+    peers: HashMap<PeerId, KnownPeer>,
+    dirty: bool,
+}
+
+impl PeerStoreState {
+    /// Returns the entry for `peer_id`, adding it if it's new, and evicting the least recently seen
+    /// peer if the store is full
+    fn peer_mut(&mut self, peer_id: PeerId) -> &mut KnownPeer {
+        if !self.peers.contains_key(&peer_id) && self.peers.len() >= MAX_KNOWN_PEERS {
+            let oldest = self
+                .peers
+                .values()
+                .min_by_key(|peer| peer.last_seen)
+                .map(|peer| peer.peer_id);
+            if let Some(oldest) = oldest {
+                let _ = self.peers.remove(&oldest);
+            }
+        }
+
+        self.peers.entry(peer_id).or_insert_with(|| KnownPeer {
+            peer_id,
+            addresses: Vec::new(),
+            last_seen: SystemTime::now(),
+            public_key: None,
+        })
+    }
+}
+
+/// Tracks the peers this node has connected to, persisting them to the gadget data directory.
+///
+/// Changes are buffered in memory and written out by [`PeerStore::flush`], and when the store is dropped.
+#[derive(Debug)]
+pub struct PeerStore {
+    path: Option<PathBuf>,
+    state: Mutex<PeerStoreState>,
+}
+
+impl PeerStore {
+    /// Create a new `PeerStore`, loading any known peers from `data_dir`.
+    ///
+    /// Peers that have not been seen within `max_age` are discarded.
+    #[must_use]
+    pub fn new(data_dir: Option<&Path>, max_age: Duration) -> Self {
+        let path = data_dir.map(|dir| dir.join(PEER_STORE_FILE_NAME));
+        let peers = path
+            .as_deref()
+            .map(|path| load_peers(path, max_age))
+            .unwrap_or_default();
+
+        Self {
+            path,
+            state: Mutex::new(PeerStoreState {
+                peers,
+                dirty: false,
+            }),
+        }
+    }
+
+    /// Returns all known peers, most recently seen first.
+    pub fn known_peers(&self) -> Vec<KnownPeer> {
+        let mut peers = self
+            .state
+            .lock()
+            .peers
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        peers.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+        peers
+    }
+
+    /// Returns the known peer with the given ID, if any.
+    pub fn get(&self, peer_id: &PeerId) -> Option<KnownPeer> {
+        self.state.lock().peers.get(peer_id).cloned()
+    }
+
+    /// Record that the peer is connected and reachable on `addresses`.
+    ///
+    /// If the peer is new and the store already holds [`MAX_KNOWN_PEERS`], the least recently seen peer
+    /// is forgotten.
+    pub fn record_seen(&self, peer_id: PeerId, addresses: impl IntoIterator<Item = Multiaddr>) {
+        let mut state = self.state.lock();
+        let peer = state.peer_mut(peer_id);
+        peer.last_seen = SystemTime::now();
+        for address in addresses {
+            peer.addresses.retain(|known| *known != address);
+            if peer.addresses.len() >= MAX_ADDRESSES_PER_PEER {
+                let _ = peer.addresses.remove(0);
+            }
+            peer.addresses.push(address);
+        }
+        state.dirty = true;
+    }
+
+    /// Associate the operator key proven during the handshake with the peer.
+    pub fn record_public_key(&self, peer_id: PeerId, public_key: GossipMsgPublicKey) {
+        let mut state = self.state.lock();
+        let peer = state.peer_mut(peer_id);
+        peer.public_key = Some(public_key);
+        state.dirty = true;
+    }
+
+    /// Forget a peer entirely, for example after it has been banned.
+    pub fn remove(&self, peer_id: &PeerId) {
+        let mut state = self.state.lock();
+        if state.peers.remove(peer_id).is_some() {
+            state.dirty = true;
+        }
+    }
+
+    /// Write the store to disk if it has changed since the last flush.
+    pub fn flush(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let entries = {
+            let mut state = self.state.lock();
+            if !state.dirty {
+                return;
+            }
+            state.dirty = false;
+            state
+                .peers
+                .values()
+                .map(KnownPeerEntry::from)
+                .collect::<Vec<_>>()
+        };
+
+        if let Err(e) = store_json_list(path, &entries) {
+            gadget_logging::error!("Failed to persist known peers to {}: {e}", path.display());
+            self.state.lock().dirty = true;
+        }
+    }
+}
+
+impl Drop for PeerStore {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+fn load_peers(path: &Path, max_age: Duration) -> HashMap<PeerId, KnownPeer> {
+    let entries = load_json_list::<KnownPeerEntry>(path, "known peers");
+
+    let cutoff = SystemTime::now().checked_sub(max_age).unwrap_or(UNIX_EPOCH);
+    let mut peers = entries
+        .into_iter()
+        .filter_map(|entry| match KnownPeer::try_from(entry) {
+            Ok(peer) => Some(peer),
+            Err(e) => {
+                gadget_logging::warn!("Skipping invalid known peer entry: {e}");
+                None
+            }
+        })
+        .filter(|peer| peer.last_seen >= cutoff)
+        .collect::<Vec<_>>();
+
+    // Keep the most recently seen peers if the file holds more than the store does
+    peers.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    peers.truncate(MAX_KNOWN_PEERS);
+    peers.into_iter().map(|peer| (peer.peer_id, peer)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_types::Curve;
+    use gadget_crypto::KeyType;
+
+    #[test]
+    fn known_peers_are_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let peer_id = PeerId::random();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/30333".parse().unwrap();
+        let public_key = Curve::public_from_secret(&Curve::generate_with_seed(None).unwrap());
+
+        let store = PeerStore::new(Some(dir.path()), DEFAULT_PEER_MAX_AGE);
+        store.record_seen(peer_id, [address.clone()]);
+        store.record_public_key(peer_id, public_key);
+        store.flush();
+
+        let store = PeerStore::new(Some(dir.path()), DEFAULT_PEER_MAX_AGE);
+        let peer = store.get(&peer_id).expect("peer should be persisted");
+        assert_eq!(peer.addresses, vec![address]);
+        assert_eq!(peer.public_key, Some(public_key));
+    }
+
+    #[test]
+    fn oldest_addresses_are_evicted() {
+        let store = PeerStore::new(None, DEFAULT_PEER_MAX_AGE);
+        let peer_id = PeerId::random();
+        let addresses = (0..=MAX_ADDRESSES_PER_PEER)
+            .map(|port| {
+                format!("/ip4/127.0.0.1/tcp/{port}")
+                    .parse::<Multiaddr>()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        store.record_seen(peer_id, addresses.clone());
+        let peer = store.get(&peer_id).unwrap();
+        assert_eq!(peer.addresses, addresses[1..]);
+
+        // Seeing a known address again makes it the most recent
+        store.record_seen(peer_id, [addresses[1].clone()]);
+        let peer = store.get(&peer_id).unwrap();
+        assert_eq!(peer.addresses.last(), Some(&addresses[1]));
+        assert_eq!(peer.addresses.len(), MAX_ADDRESSES_PER_PEER);
+    }
+
+    #[test]
+    fn least_recently_seen_peers_are_evicted() {
+        let store = PeerStore::new(None, DEFAULT_PEER_MAX_AGE);
+        let oldest = PeerId::random();
+        store.record_seen(oldest, []);
+        for _ in 1..MAX_KNOWN_PEERS {
+            store.record_seen(PeerId::random(), []);
+        }
+
+        // Seeing the oldest peer again keeps it, so another peer is evicted instead
+        store.record_seen(oldest, []);
+        let before = store.known_peers();
+        let newest = PeerId::random();
+        store.record_seen(newest, []);
+
+        let after = store.known_peers();
+        assert_eq!(after.len(), MAX_KNOWN_PEERS);
+        assert!(store.get(&oldest).is_some());
+        assert!(store.get(&newest).is_some());
+        let evicted = before
+            .iter()
+            .find(|peer| store.get(&peer.peer_id).is_none())
+            .unwrap();
+        assert!(before
+            .iter()
+            .all(|peer| peer.last_seen >= evicted.last_seen));
+    }
+
+    #[test]
+    fn dropping_the_store_flushes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let peer_id = PeerId::random();
+
+        let store = PeerStore::new(Some(dir.path()), DEFAULT_PEER_MAX_AGE);
+        store.record_seen(peer_id, []);
+        drop(store);
+
+        let store = PeerStore::new(Some(dir.path()), DEFAULT_PEER_MAX_AGE);
+        assert!(store.get(&peer_id).is_some());
+    }
+
+    #[test]
+    fn stale_peers_are_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PeerStore::new(Some(dir.path()), DEFAULT_PEER_MAX_AGE);
+        store.record_seen(PeerId::random(), []);
+        store.flush();
+
+        let store = PeerStore::new(Some(dir.path()), Duration::ZERO);
+        assert!(store.known_peers().is_empty());
+    }
+}
@@ -9,12 +9,28 @@ use futures::prelude::*;
 use gadget_std::collections::{BTreeMap, HashMap, VecDeque};
 use gadget_std::string::ToString;
 use gadget_std::sync::Arc;
+use gadget_std::vec::Vec;
 use round_based::{Delivery, Incoming, MessageType, Outgoing};
 use round_based::{MessageDestination, MsgId, PartyIndex};
 use stream::{SplitSink, SplitStream};
 
 use crate::networking::ParticipantInfo;
 
+/// The number of rounds a [`NetworkDeliveryWrapper`] is created with when none is specified.
+pub const DEFAULT_ROUNDS: u16 = 10;
+
+/// Derive the task hash of a sub-protocol nested within the protocol identified by `task_hash`.
+///
+/// Sub-protocols get their own task hash so that their rounds never collide with the
+/// rounds of the parent protocol on the multiplexer.
+#[must_use]
+pub fn derive_task_hash(task_hash: &[u8; 32], sub_protocol: &[u8]) -> [u8; 32] {
+    let mut input = Vec::with_capacity(task_hash.len() + sub_protocol.len());
+    input.extend_from_slice(task_hash);
+    input.extend_from_slice(sub_protocol);
+    gadget_crypto::hashing::blake3_256(&input)
+}
+
 pub struct NetworkDeliveryWrapper<M> {
     /// The wrapped network implementation.
     network: NetworkWrapper<M>,
@@ -26,33 +42,149 @@ where
     M: serde::Serialize + serde::de::DeserializeOwned,
 {
     /// Create a new `NetworkDeliveryWrapper` over a network implementation with the given party index.
+    ///
+    /// The wrapper supports [`DEFAULT_ROUNDS`] rounds. Use [`NetworkDeliveryWrapper::builder`]
+    /// for protocols with more rounds.
     #[must_use]
     pub fn new(
         mux: Arc<NetworkMultiplexer>,
         i: PartyIndex,
         task_hash: [u8; 32],
-        parties: BTreeMap<PartyIndex, GossipMsgPublicKey>,
+        parties: impl IntoIterator<Item = (PartyIndex, GossipMsgPublicKey)>,
+    ) -> Self {
+        Self::builder(mux, i, task_hash).parties(parties).build()
+    }
+
+    /// Start building a `NetworkDeliveryWrapper` for party `i` of the protocol identified by `task_hash`.
+    #[must_use]
+    pub fn builder(
+        mux: Arc<NetworkMultiplexer>,
+        i: PartyIndex,
+        task_hash: [u8; 32],
+    ) -> NetworkDeliveryWrapperBuilder {
+        NetworkDeliveryWrapperBuilder {
+            mux,
+            me: i,
+            task_hash,
+            parties: BTreeMap::new(),
+            rounds: DEFAULT_ROUNDS,
+        }
+    }
+
+    /// The task hash the substreams of this wrapper are keyed by.
+    #[must_use]
+    pub fn task_hash(&self) -> [u8; 32] {
+        self.network.task_hash
+    }
+
+    /// The number of rounds this wrapper can carry.
+    #[must_use]
+    pub fn rounds(&self) -> u16 {
+        self.network.rounds
+    }
+
+    /// Create a wrapper for a sub-protocol running among the same parties.
+    ///
+    /// The sub-protocol's task hash is derived from this wrapper's task hash and `sub_protocol`
+    /// (see [`derive_task_hash`]), so both can run concurrently over the same multiplexer.
+    #[must_use]
+    pub fn nested<N>(&self, sub_protocol: &[u8], rounds: u16) -> NetworkDeliveryWrapper<N>
+    where
+        N: Clone + Send + Unpin + 'static,
+        N: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        NetworkDeliveryWrapper::builder(
+            self.network.mux.clone(),
+            self.network.me,
+            self.network.task_hash,
+        )
+        .parties(self.network.participants.clone())
+        .rounds(rounds)
+        .sub_protocol(sub_protocol)
+        .build()
+    }
+}
+
+/// Builder for a [`NetworkDeliveryWrapper`].
+pub struct NetworkDeliveryWrapperBuilder {
+    mux: Arc<NetworkMultiplexer>,
+    me: PartyIndex,
+    task_hash: [u8; 32],
+    parties: BTreeMap<PartyIndex, GossipMsgPublicKey>,
+    rounds: u16,
+}
+
+impl NetworkDeliveryWrapperBuilder {
+    /// Set the participants of the protocol along with their public keys.
+    #[must_use]
+    pub fn parties(
+        mut self,
+        parties: impl IntoIterator<Item = (PartyIndex, GossipMsgPublicKey)>,
     ) -> Self {
+        self.parties.extend(parties);
+        self
+    }
+
+    /// Add a single participant of the protocol.
+    #[must_use]
+    pub fn party(mut self, i: PartyIndex, public_key: GossipMsgPublicKey) -> Self {
+        let _ = self.parties.insert(i, public_key);
+        self
+    }
+
+    /// Set the number of rounds of the protocol.
+    ///
+    /// One substream is created per round. Sending a message for a round outside of
+    /// `0..rounds` results in an [`Error::RoundBasedError`](crate::Error::RoundBasedError).
+    #[must_use]
+    pub fn rounds(mut self, rounds: u16) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Run the protocol as a sub-protocol, with a task hash derived from the current one.
+    ///
+    /// May be called multiple times to nest sub-protocols further.
+    #[must_use]
+    pub fn sub_protocol(mut self, sub_protocol: &[u8]) -> Self {
+        self.task_hash = derive_task_hash(&self.task_hash, sub_protocol);
+        self
+    }
+
+    /// Build the `NetworkDeliveryWrapper`, creating a substream for each round.
+    #[must_use]
+    pub fn build<M>(self) -> NetworkDeliveryWrapper<M>
+    where
+        M: Clone + Send + Unpin + 'static,
+        M: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let Self {
+            mux,
+            me,
+            task_hash,
+            parties,
+            rounds,
+        } = self;
+
         let (tx_forward, rx) = tokio::sync::mpsc::unbounded_channel();
-        // By default, we create 10 substreams for each party.
-        let mut sub_streams = HashMap::new();
-        for x in 0..10 {
+        let mut sub_streams = HashMap::with_capacity(usize::from(rounds));
+        for round_id in 0..rounds {
             let key = StreamKey {
                 task_hash,
-                round_id: x,
+                round_id: i32::from(round_id),
             };
             // Creates a multiplexed subnetwork, and also forwards all messages to the given channel
             let _ = sub_streams.insert(key, mux.multiplex_with_forwarding(key, tx_forward.clone()));
         }
 
         let network = NetworkWrapper {
-            me: i,
+            me,
             mux,
             incoming_queue: VecDeque::new(),
             sub_streams,
             participants: parties,
             task_hash,
-            tx_forward,
+            rounds,
             rx,
             next_msg_id: Arc::new(NextMessageId::default()),
         };
@@ -77,9 +209,10 @@ pub struct NetworkWrapper<M> {
     /// Note: This is a `BTreeMap` to ensure that the participants are sorted by their party index.
     participants: BTreeMap<PartyIndex, GossipMsgPublicKey>,
     next_msg_id: Arc<NextMessageId>,
-    tx_forward: tokio::sync::mpsc::UnboundedSender<ProtocolMessage>,
     rx: tokio::sync::mpsc::UnboundedReceiver<ProtocolMessage>,
     task_hash: [u8; 32],
+    /// The number of rounds, each of which has a substream in `sub_streams`.
+    rounds: u16,
 }
 
 impl<M> Delivery<M> for NetworkDeliveryWrapper<M>
@@ -150,9 +283,15 @@ where
 
     fn start_send(self: Pin<&mut Self>, out: Outgoing<M>) -> Result<(), Self::Error> {
         let this = self.get_mut();
-        let id = this.next_msg_id.next();
-
         let round_id = out.msg.round();
+        if round_id >= this.rounds {
+            return Err(crate::Error::RoundBasedError(format!(
+                "Round {round_id} is out of range, the protocol was configured with {} rounds",
+                this.rounds
+            )));
+        }
+
+        let id = this.next_msg_id.next();
 
         gadget_logging::info!(
             "Round {}: Sending message from {} to {:?} (id: {})",
@@ -167,10 +306,10 @@ where
             task_hash: this.task_hash,
             round_id: i32::from(round_id),
         };
-        let substream = this.sub_streams.entry(key).or_insert_with(|| {
-            this.mux
-                .multiplex_with_forwarding(key, this.tx_forward.clone())
-        });
+        let substream = this
+            .sub_streams
+            .get(&key)
+            .expect("A substream is created for every round");
 
         let identifier_info = IdentifierInfo {
             message_id: id,
@@ -225,3 +364,96 @@ impl NextMessageId {
             .fetch_add(1, gadget_std::sync::atomic::Ordering::Relaxed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulated::SimulatedNetwork;
+    use core::time::Duration;
+    use round_based::Delivery;
+
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct RoundMsg(u16);
+
+    impl round_based::ProtocolMessage for RoundMsg {
+        fn round(&self) -> u16 {
+            self.0
+        }
+    }
+
+    fn wrappers(rounds: u16) -> Vec<NetworkDeliveryWrapper<RoundMsg>> {
+        let (network, parties) = SimulatedNetwork::builder(2).seed(7).build();
+        let keys = network.public_keys();
+        parties
+            .into_iter()
+            .enumerate()
+            .map(|(i, party)| {
+                let mux = Arc::new(NetworkMultiplexer::new(party));
+                let i = u16::try_from(i).unwrap();
+                NetworkDeliveryWrapper::builder(mux, i, [1u8; 32])
+                    .parties(
+                        keys.iter()
+                            .copied()
+                            .enumerate()
+                            .map(|(j, key)| (u16::try_from(j).unwrap(), key)),
+                    )
+                    .rounds(rounds)
+                    .build()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rounds_beyond_default_are_delivered() {
+        let mut wrappers = wrappers(DEFAULT_ROUNDS + 2);
+        let (mut incoming, _) = wrappers.pop().unwrap().split();
+        let (_, mut outgoing) = wrappers.pop().unwrap().split();
+
+        let msg = RoundMsg(DEFAULT_ROUNDS + 1);
+        outgoing
+            .send(Outgoing {
+                recipient: MessageDestination::AllParties,
+                msg: msg.clone(),
+            })
+            .await
+            .unwrap();
+
+        let received = tokio::time::timeout(Duration::from_secs(5), incoming.next())
+            .await
+            .expect("message should be delivered")
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.msg, msg);
+        assert_eq!(received.sender, 0);
+    }
+
+    #[tokio::test]
+    async fn out_of_range_round_is_rejected() {
+        let mut wrappers = wrappers(3);
+        let (_, mut outgoing) = wrappers.remove(0).split();
+
+        let err = outgoing
+            .send(Outgoing {
+                recipient: MessageDestination::AllParties,
+                msg: RoundMsg(3),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::RoundBasedError(_)));
+    }
+
+    #[tokio::test]
+    async fn nested_protocols_get_distinct_task_hashes() {
+        let wrapper = wrappers(1).remove(0);
+        let first = wrapper.nested::<RoundMsg>(b"first", 4);
+        let second = wrapper.nested::<RoundMsg>(b"second", 4);
+
+        assert_eq!(first.rounds(), 4);
+        assert_eq!(
+            first.task_hash(),
+            derive_task_hash(&wrapper.task_hash(), b"first")
+        );
+        assert_ne!(first.task_hash(), wrapper.task_hash());
+        assert_ne!(first.task_hash(), second.task_hash());
+    }
+}
@@ -0,0 +1,288 @@
+//! Typed request/response RPC between peers, built on the `request_response` behaviour.
+//!
+//! A request type implements [`RpcRequest`] to give it a method name. Peers register a handler
+//! for it with [`GossipHandle::register_handler`](crate::gossip::GossipHandle::register_handler),
+//! and other peers call it with [`GossipHandle::request`](crate::gossip::GossipHandle::request).
+//! Requests are only served to peers that have completed the handshake.
+
+use crate::key_types::GossipMsgPublicKey;
+use crate::Error;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use gadget_std::collections::{BTreeMap, HashMap};
+use gadget_std::fmt::Display;
+use gadget_std::format;
+use gadget_std::future::Future;
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::Arc;
+use gadget_std::vec::Vec;
+use libp2p::request_response::OutboundRequestId;
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+
+/// The maximum number of inbound requests handled at once, across all peers
+pub const MAX_INBOUND_REQUESTS: usize = 256;
+
+/// The maximum number of inbound requests from a single peer handled at once
+pub const MAX_INBOUND_REQUESTS_PER_PEER: usize = 16;
+
+/// A request that can be sent to a peer with [`GossipHandle::request`](crate::gossip::GossipHandle::request).
+pub trait RpcRequest: Serialize + DeserializeOwned + Send + 'static {
+    /// The name of the method, unique within a network topic.
+    const METHOD: &'static str;
+}
+
+/// The result of an RPC call, as sent over the wire.
+pub(crate) type RpcResult = Result<Vec<u8>, String>;
+
+type BoxedHandler =
+    Arc<dyn Fn(GossipMsgPublicKey, Vec<u8>) -> BoxFuture<'static, RpcResult> + Send + Sync>;
+
+/// Registered RPC handlers and in-flight outbound requests, shared between the
+/// [`GossipHandle`](crate::gossip::GossipHandle)s and the network worker.
+pub struct RpcRegistry {
+    /// Handlers keyed by `(topic, method)`
+    handlers: RwLock<HashMap<(String, String), BoxedHandler>>,
+    /// Outbound requests awaiting a response
+    pending: Mutex<HashMap<OutboundRequestId, oneshot::Sender<Result<Vec<u8>, Error>>>>,
+    /// Bounds the inbound requests being handled, see [`MAX_INBOUND_REQUESTS`]
+    inbound: Arc<Semaphore>,
+    /// Inbound requests being handled for each peer, see [`MAX_INBOUND_REQUESTS_PER_PEER`]
+    inbound_by_peer: Arc<Mutex<BTreeMap<GossipMsgPublicKey, usize>>>,
+}
+
+impl Default for RpcRegistry {
+    fn default() -> Self {
+        Self {
+            handlers: RwLock::default(),
+            pending: Mutex::default(),
+            inbound: Arc::new(Semaphore::new(MAX_INBOUND_REQUESTS)),
+            inbound_by_peer: Arc::default(),
+        }
+    }
+}
+
+/// Held while an inbound request is handled
+struct InboundPermit {
+    _permit: OwnedSemaphorePermit,
+    inbound_by_peer: Arc<Mutex<BTreeMap<GossipMsgPublicKey, usize>>>,
+    peer: GossipMsgPublicKey,
+}
+
+impl Drop for InboundPermit {
+    fn drop(&mut self) {
+        let mut inbound_by_peer = self.inbound_by_peer.lock();
+        if let Some(count) = inbound_by_peer.get_mut(&self.peer) {
+            *count -= 1;
+            if *count == 0 {
+                let _ = inbound_by_peer.remove(&self.peer);
+            }
+        }
+    }
+}
+
+impl gadget_std::fmt::Debug for RpcRegistry {
+    fn fmt(&self, f: &mut gadget_std::fmt::Formatter<'_>) -> gadget_std::fmt::Result {
+        f.debug_struct("RpcRegistry")
+            .field("handlers", &self.handlers.read().keys().collect::<Vec<_>>())
+            .field("pending", &self.pending.lock().len())
+            .field(
+                "inbound",
+                &(MAX_INBOUND_REQUESTS - self.inbound.available_permits()),
+            )
+            .finish()
+    }
+}
+
+impl RpcRegistry {
+    /// Register `handler` for requests of type `Req` on `topic`, replacing any existing handler.
+    pub(crate) fn register<Req, Resp, E, F, Fut>(&self, topic: String, handler: F)
+    where
+        Req: RpcRequest,
+        Resp: Serialize,
+        E: Display,
+        F: Fn(GossipMsgPublicKey, Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Resp, E>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let boxed: BoxedHandler = Arc::new(move |peer, payload| {
+            let handler = handler.clone();
+            async move {
+                let request = bincode::deserialize::<Req>(&payload)
+                    .map_err(|e| format!("Failed to decode {} request: {e}", Req::METHOD))?;
+                let response = handler(peer, request).await.map_err(|e| e.to_string())?;
+                bincode::serialize(&response)
+                    .map_err(|e| format!("Failed to encode {} response: {e}", Req::METHOD))
+            }
+            .boxed()
+        });
+
+        let _ = self
+            .handlers
+            .write()
+            .insert((topic, Req::METHOD.to_string()), boxed);
+    }
+
+    /// Remove the handler for requests of type `Req` on `topic`.
+    ///
+    /// Returns `true` if a handler was registered.
+    pub(crate) fn unregister<Req: RpcRequest>(&self, topic: String) -> bool {
+        self.handlers
+            .write()
+            .remove(&(topic, Req::METHOD.to_string()))
+            .is_some()
+    }
+
+    /// Start handling a request for `method` on `topic` from `peer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the reason to send back to the peer if no handler is registered, or if too many
+    /// requests are already being handled, in total or for `peer`.
+    pub(crate) fn dispatch(
+        &self,
+        topic: String,
+        method: String,
+        peer: GossipMsgPublicKey,
+        payload: Vec<u8>,
+    ) -> Result<BoxFuture<'static, RpcResult>, String> {
+        let handler = self
+            .handlers
+            .read()
+            .get(&(topic, method.clone()))
+            .cloned()
+            .ok_or_else(|| format!("No handler registered for {method}"))?;
+        let permit = self.acquire_inbound(peer)?;
+        let response = handler(peer, payload);
+        Ok(async move {
+            let result = response.await;
+            drop(permit);
+            result
+        }
+        .boxed())
+    }
+
+    fn acquire_inbound(&self, peer: GossipMsgPublicKey) -> Result<InboundPermit, String> {
+        let mut inbound_by_peer = self.inbound_by_peer.lock();
+        let count = inbound_by_peer.entry(peer).or_default();
+        if *count >= MAX_INBOUND_REQUESTS_PER_PEER {
+            return Err(String::from("Too many requests in flight from this peer"));
+        }
+        let Ok(permit) = self.inbound.clone().try_acquire_owned() else {
+            if *count == 0 {
+                let _ = inbound_by_peer.remove(&peer);
+            }
+            return Err(String::from("Too many requests in flight"));
+        };
+        *count += 1;
+
+        Ok(InboundPermit {
+            _permit: permit,
+            inbound_by_peer: self.inbound_by_peer.clone(),
+            peer,
+        })
+    }
+
+    /// Track an outbound request so its response can be routed back to the caller.
+    pub(crate) fn insert_pending(
+        &self,
+        request_id: OutboundRequestId,
+        tx: oneshot::Sender<Result<Vec<u8>, Error>>,
+    ) {
+        let _ = self.pending.lock().insert(request_id, tx);
+    }
+
+    /// Complete an outbound request. Responses to requests the caller stopped waiting on are dropped.
+    pub(crate) fn complete(&self, request_id: OutboundRequestId, result: Result<Vec<u8>, Error>) {
+        if let Some(tx) = self.pending.lock().remove(&request_id) {
+            let _ = tx.send(result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key_types::Curve;
+    use gadget_crypto::KeyType;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct Echo(u64);
+
+    impl RpcRequest for Echo {
+        const METHOD: &'static str = "echo";
+    }
+
+    fn peer() -> GossipMsgPublicKey {
+        Curve::public_from_secret(&Curve::generate_with_seed(None).unwrap())
+    }
+
+    #[tokio::test]
+    async fn dispatches_to_registered_handler() {
+        let registry = RpcRegistry::default();
+        registry.register::<Echo, u64, String, _, _>("topic".into(), |_, Echo(n)| async move {
+            if n == 0 {
+                Err("zero".to_string())
+            } else {
+                Ok(n * 2)
+            }
+        });
+
+        let payload = bincode::serialize(&Echo(21)).unwrap();
+        let response = registry
+            .dispatch("topic".into(), "echo".into(), peer(), payload)
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(bincode::deserialize::<u64>(&response).unwrap(), 42);
+
+        let payload = bincode::serialize(&Echo(0)).unwrap();
+        let err = registry
+            .dispatch("topic".into(), "echo".into(), peer(), payload)
+            .unwrap()
+            .await
+            .unwrap_err();
+        assert_eq!(err, "zero");
+    }
+
+    #[tokio::test]
+    async fn handlers_are_scoped_to_their_topic() {
+        let registry = RpcRegistry::default();
+        registry
+            .register::<Echo, u64, String, _, _>("topic".into(), |_, Echo(n)| async move { Ok(n) });
+
+        assert!(registry
+            .dispatch("other".into(), "echo".into(), peer(), Vec::new())
+            .is_err());
+        assert!(registry.unregister::<Echo>("topic".into()));
+        assert!(registry
+            .dispatch("topic".into(), "echo".into(), peer(), Vec::new())
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn limits_inbound_requests_per_peer() {
+        let registry = RpcRegistry::default();
+        registry
+            .register::<Echo, u64, String, _, _>("topic".into(), |_, Echo(n)| async move { Ok(n) });
+
+        let (busy, other) = (peer(), peer());
+        let payload = bincode::serialize(&Echo(1)).unwrap();
+        let dispatch =
+            |peer| registry.dispatch("topic".into(), "echo".into(), peer, payload.clone());
+
+        let in_flight = (0..MAX_INBOUND_REQUESTS_PER_PEER)
+            .map(|_| dispatch(busy).unwrap())
+            .collect::<Vec<_>>();
+        assert!(dispatch(busy).is_err());
+        assert!(dispatch(other).is_ok());
+
+        // Finishing a request frees its slot
+        let mut in_flight = in_flight.into_iter();
+        in_flight.next().unwrap().await.unwrap();
+        assert!(dispatch(busy).is_ok());
+    }
+}
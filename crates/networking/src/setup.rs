@@ -4,6 +4,9 @@ use crate::gossip::{
     GossipHandle, IntraNodePayload, MyBehaviour, NetworkServiceWithoutSwarm, MAX_MESSAGE_SIZE,
 };
 pub use crate::key_types::GossipMsgKeyPair;
+use crate::peer_manager::{PeerManager, PeerManagerConfig};
+use crate::peer_store::{PeerStore, DEFAULT_PEER_MAX_AGE};
+use crate::rpc::RpcRegistry;
 use futures::StreamExt;
 use gadget_std as std;
 use gadget_std::boxed::Box;
@@ -12,6 +15,7 @@ use gadget_std::error::Error;
 use gadget_std::format;
 use gadget_std::io;
 use gadget_std::net::IpAddr;
+use gadget_std::path::{Path, PathBuf};
 use gadget_std::str::FromStr;
 use gadget_std::string::String;
 use gadget_std::sync::atomic::AtomicUsize;
@@ -34,6 +38,16 @@ pub const AGENT_VERSION: &str = "tangle/gadget-sdk/1.0.0";
 /// The version of the client
 pub const CLIENT_VERSION: &str = "1.0.0";
 
+/// The directory (relative to [`NetworkConfig::data_dir`]) holding the state of each network
+pub const NETWORKS_DIR_NAME: &str = "networks";
+
+/// The default for [`NetworkConfig::request_timeout`]
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// How often expired peer bans are lifted
+const BAN_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the known peers are written to disk
+const PEER_STORE_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
 /// The base network configuration for a blueprint's `libp2p` network.
 ///
 /// This configuration is used to setup the `libp2p` network for a blueprint.
@@ -44,7 +58,24 @@ pub struct NetworkConfig {
     pub secret_key: GossipMsgKeyPair,
     pub bootnodes: Vec<Multiaddr>,
     pub bind_port: u16,
+    /// The addresses to listen on
+    ///
+    /// If empty, the network listens on all interfaces on `bind_port`, over both TCP and QUIC.
+    pub listen_addrs: Vec<Multiaddr>,
+    /// The publicly reachable addresses to announce to peers, such as the address of a NAT
+    pub external_addrs: Vec<Multiaddr>,
     pub topics: Vec<String>,
+    /// Peer scoring, rate limiting and ban settings
+    pub peer_manager: PeerManagerConfig,
+    /// Directory for persisted network state, such as the ban list and known peers
+    ///
+    /// Each network keeps its state in its own subdirectory, see [`network_data_dir`]. Nothing is
+    /// persisted if this is `None`.
+    pub data_dir: Option<PathBuf>,
+    /// How long a direct request may wait for its response, including RPC calls
+    ///
+    /// This caps the timeout passed to [`GossipHandle::request`].
+    pub request_timeout: Duration,
 }
 
 impl gadget_std::fmt::Debug for NetworkConfig {
@@ -53,7 +84,12 @@ impl gadget_std::fmt::Debug for NetworkConfig {
             .field("identity", &self.identity)
             .field("bootnodes", &self.bootnodes)
             .field("bind_port", &self.bind_port)
+            .field("listen_addrs", &self.listen_addrs)
+            .field("external_addrs", &self.external_addrs)
             .field("topics", &self.topics)
+            .field("peer_manager", &self.peer_manager)
+            .field("data_dir", &self.data_dir)
+            .field("request_timeout", &self.request_timeout)
             .finish_non_exhaustive()
     }
 }
@@ -74,10 +110,50 @@ impl NetworkConfig {
             secret_key,
             bootnodes,
             bind_port,
+            listen_addrs: Vec::new(),
+            external_addrs: Vec::new(),
             topics,
+            peer_manager: PeerManagerConfig::default(),
+            data_dir: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
         }
     }
 
+    /// Set the peer scoring, rate limiting and ban settings.
+    #[must_use]
+    pub fn with_peer_manager_config(mut self, peer_manager: PeerManagerConfig) -> Self {
+        self.peer_manager = peer_manager;
+        self
+    }
+
+    /// Listen on `listen_addrs` instead of all interfaces on `bind_port`.
+    #[must_use]
+    pub fn with_listen_addrs(mut self, listen_addrs: Vec<Multiaddr>) -> Self {
+        self.listen_addrs = listen_addrs;
+        self
+    }
+
+    /// Announce `external_addrs` to peers as addresses this node is reachable at.
+    #[must_use]
+    pub fn with_external_addrs(mut self, external_addrs: Vec<Multiaddr>) -> Self {
+        self.external_addrs = external_addrs;
+        self
+    }
+
+    /// Set the directory used to persist network state across restarts.
+    #[must_use]
+    pub fn with_data_dir(mut self, data_dir: impl Into<PathBuf>) -> Self {
+        self.data_dir = Some(data_dir.into());
+        self
+    }
+
+    /// Set how long a direct request may wait for its response.
+    #[must_use]
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
     /// When constructing a network for a single service, the service name is used as the network name.
     /// Each service within a blueprint must have a unique network name.
     pub fn new_service_network<T: Into<String>>(
@@ -97,6 +173,28 @@ impl NetworkConfig {
     }
 }
 
+/// The directory under `data_dir` that the network with the given `topics` persists its state to
+///
+/// Networks that share a data directory, such as those of different services, don't share ban lists or
+/// known peers.
+#[must_use]
+pub fn network_data_dir(data_dir: &Path, topics: &[String]) -> PathBuf {
+    let mut topics = topics.to_vec();
+    topics.sort();
+    let name = topics
+        .join("+")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '+') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    data_dir.join(NETWORKS_DIR_NAME).join(name)
+}
+
 /// Start a P2P network with the given configuration.
 ///
 /// Each service will only have one network. It is necessary that each service calling this function
@@ -144,10 +242,19 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
         identity,
         bootnodes,
         bind_port,
+        listen_addrs,
+        external_addrs,
         topics,
         secret_key,
+        peer_manager,
+        data_dir,
+        request_timeout,
     } = config;
 
+    let data_dir = data_dir.map(|dir| network_data_dir(&dir, &topics));
+    let peer_manager = Arc::new(PeerManager::new(peer_manager, data_dir.as_deref()));
+    let peer_store = Arc::new(PeerStore::new(data_dir.as_deref(), DEFAULT_PEER_MAX_AGE));
+
     // Ensure all topics are unique
     let topics_unique = topics
         .iter()
@@ -189,17 +296,25 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
                 .map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))?; // Temporary hack because `build` does not return a proper `std::error::Error`.
 
             // Setup gossipsub network behaviour for broadcasting
-            let gossipsub = gossipsub::Behaviour::new(
+            let mut gossipsub = gossipsub::Behaviour::new(
                 gossipsub::MessageAuthenticity::Signed(key.clone()),
                 gossipsub_config,
             )?;
 
+            // Enable peer scoring so that misbehaving peers are pruned from the mesh
+            let peer_manager_config = peer_manager.config();
+            if let Some(score_params) = peer_manager_config.score_params.clone() {
+                gossipsub
+                    .with_peer_score(score_params, peer_manager_config.score_thresholds.clone())?;
+            }
+
             // Setup mDNS for peer discovery
             let mdns =
                 mdns::tokio::Behaviour::new(mdns::Config::default(), key.public().to_peer_id())?;
 
             // Setup request-response for direct messaging
-            let p2p_config = request_response::Config::default();
+            let p2p_config =
+                request_response::Config::default().with_request_timeout(request_timeout);
             // StreamProtocols MUST begin with a forward slash
             let protocols = networks
                 .iter()
@@ -236,6 +351,9 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
             // Setup ping for liveness checks between connections
             let ping = libp2p::ping::Behaviour::new(libp2p::ping::Config::default());
 
+            // Setup the block list used to enforce peer bans
+            let blocked_peers = libp2p::allow_block_list::Behaviour::default();
+
             Ok(MyBehaviour {
                 gossipsub,
                 mdns,
@@ -246,11 +364,18 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
                 relay,
                 relay_client,
                 ping,
+                blocked_peers,
             })
         })?
         .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(60)))
         .build();
 
+    // Enforce any bans persisted from a previous run
+    for peer_id in peer_manager.banned_peers() {
+        swarm.behaviour_mut().gossipsub.blacklist_peer(&peer_id);
+        swarm.behaviour_mut().blocked_peers.block_peer(peer_id);
+    }
+
     // Subscribe to all networks
     let mut inbound_mapping = Vec::new();
     let (tx_to_outbound, mut rx_to_outbound) =
@@ -258,6 +383,8 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
     let public_key_to_libp2p_id = Arc::new(RwLock::new(BTreeMap::new()));
     let mut handles_ret = BTreeMap::new();
     let connected_peers = Arc::new(AtomicUsize::new(0));
+    let rpc = Arc::new(RpcRegistry::default());
+    let (tx_rpc_response, mut rx_rpc_response) = tokio::sync::mpsc::unbounded_channel();
     for network in networks {
         let topic = IdentTopic::new(network.clone());
         swarm.behaviour_mut().gossipsub.subscribe(&topic)?;
@@ -274,22 +401,36 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
                 public_key_to_libp2p_id: public_key_to_libp2p_id.clone(),
                 // Each key is 32 bytes, therefore 512 messages hashes can be stored in the set
                 recent_messages: LruCache::new(16 * 1024).into(),
+                peer_manager: peer_manager.clone(),
+                peer_store: peer_store.clone(),
+                rpc: rpc.clone(),
                 my_id: my_pk,
             },
         );
     }
 
-    let ips_to_bind_to = [
-        IpAddr::from_str("::").unwrap(),      // IN_ADDR_ANY_V6
-        IpAddr::from_str("0.0.0.0").unwrap(), // IN_ADDR_ANY_V4
-    ];
-
-    for addr in ips_to_bind_to {
-        let ip_label = if addr.is_ipv4() { "ip4" } else { "ip6" };
-        // Bind to both UDP and TCP to increase probability of successful NAT traversal.
-        // Use QUIC over UDP to have reliable ordered transport like TCP.
-        swarm.listen_on(format!("/{ip_label}/{addr}/udp/{bind_port}/quic-v1").parse()?)?;
-        swarm.listen_on(format!("/{ip_label}/{addr}/tcp/{bind_port}").parse()?)?;
+    if listen_addrs.is_empty() {
+        let ips_to_bind_to = [
+            IpAddr::from_str("::").unwrap(),      // IN_ADDR_ANY_V6
+            IpAddr::from_str("0.0.0.0").unwrap(), // IN_ADDR_ANY_V4
+        ];
+
+        for addr in ips_to_bind_to {
+            let ip_label = if addr.is_ipv4() { "ip4" } else { "ip6" };
+            // Bind to both UDP and TCP to increase probability of successful NAT traversal.
+            // Use QUIC over UDP to have reliable ordered transport like TCP.
+            swarm.listen_on(format!("/{ip_label}/{addr}/udp/{bind_port}/quic-v1").parse()?)?;
+            swarm.listen_on(format!("/{ip_label}/{addr}/tcp/{bind_port}").parse()?)?;
+        }
+    } else {
+        for addr in listen_addrs {
+            swarm.listen_on(addr)?;
+        }
+    }
+
+    // Peers learn our observed addresses through identify, but those are wrong behind a NAT
+    for addr in external_addrs {
+        swarm.add_external_address(addr);
     }
 
     // Dial all bootnodes
@@ -301,6 +442,31 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
         )?;
     }
 
+    // Redial the peers we knew about before the last shutdown, so that we can rejoin the network
+    // even if the bootnodes are unreachable
+    for known_peer in peer_store.known_peers() {
+        if known_peer.peer_id == my_id
+            || known_peer.addresses.is_empty()
+            || peer_manager.is_banned(&known_peer.peer_id)
+        {
+            continue;
+        }
+
+        for address in &known_peer.addresses {
+            swarm
+                .behaviour_mut()
+                .kadmelia
+                .add_address(&known_peer.peer_id, address.clone());
+        }
+
+        let dial_opts = DialOpts::peer_id(known_peer.peer_id)
+            .addresses(known_peer.addresses.into_iter().collect())
+            .build();
+        if let Err(e) = swarm.dial(dial_opts) {
+            gadget_logging::warn!("Failed to dial known peer {}: {e}", known_peer.peer_id);
+        }
+    }
+
     let worker = async move {
         let span = tracing::debug_span!("network_worker");
         let _enter = span.enter();
@@ -309,16 +475,31 @@ pub fn multiplexed_libp2p_network(config: NetworkConfig) -> NetworkResult {
             connected_peers,
             public_key_to_libp2p_id,
             secret_key: &secret_key,
+            peer_manager,
+            peer_store,
+            rpc,
+            tx_rpc_response,
             span: tracing::debug_span!(parent: &span, "network_service"),
             my_id,
         };
 
+        let mut ban_expiry_interval = tokio::time::interval(BAN_EXPIRY_CHECK_INTERVAL);
+        let mut peer_store_flush_interval = tokio::time::interval(PEER_STORE_FLUSH_INTERVAL);
         loop {
             select! {
                 // Setup outbound channel
                 Some(msg) = rx_to_outbound.recv() => {
                     service.with_swarm(&mut swarm).handle_intra_node_payload(msg);
                 }
+                Some(response) = rx_rpc_response.recv() => {
+                    service.with_swarm(&mut swarm).handle_rpc_response(response);
+                }
+                _ = ban_expiry_interval.tick() => {
+                    service.with_swarm(&mut swarm).prune_expired_bans();
+                }
+                _ = peer_store_flush_interval.tick() => {
+                    service.peer_store.flush();
+                }
                 event = swarm.select_next_some() => {
                     service.with_swarm(&mut swarm).handle_swarm_event(event).await;
                 }
@@ -0,0 +1,538 @@
+//! An in-process [`Network`] implementation for testing round-based protocols.
+//!
+//! A [`SimulatedNetwork`] connects `N` parties over in-memory channels, with configurable
+//! latency, message loss, reordering, duplication and partitions. All randomness is derived
+//! from a single seed, so protocol tests are reproducible.
+//!
+//! ```no_run
+//! use gadget_networking::networking::NetworkMultiplexer;
+//! use gadget_networking::simulated::SimulatedNetwork;
+//! use gadget_std::time::Duration;
+//!
+//! # async fn example() {
+//! let (network, parties) = SimulatedNetwork::builder(3)
+//!     .seed(42)
+//!     .latency(Duration::from_millis(5), Duration::from_millis(20))
+//!     .drop_probability(0.01)
+//!     .build();
+//!
+//! let multiplexers = parties
+//!     .into_iter()
+//!     .map(NetworkMultiplexer::new)
+//!     .collect::<Vec<_>>();
+//!
+//! // Split party 0 from parties 1 and 2
+//! network.partition(&[&[0], &[1, 2]]);
+//! # }
+//! ```
+
+use crate::key_types::{Curve, GossipMsgPublicKey};
+use crate::networking::{Network, ProtocolMessage};
+use crate::Error;
+use async_trait::async_trait;
+use gadget_crypto::KeyType;
+use gadget_std::boxed::Box;
+use gadget_std::collections::HashMap;
+use gadget_std::format;
+use gadget_std::rand::rngs::StdRng;
+use gadget_std::rand::{Rng, SeedableRng};
+use gadget_std::sync::atomic::{AtomicU64, Ordering};
+use gadget_std::sync::Arc;
+use gadget_std::time::Duration;
+use gadget_std::vec::Vec;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// The conditions applied to every link of a [`SimulatedNetwork`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConditions {
+    /// The minimum delay before a message is delivered
+    pub min_latency: Duration,
+    /// The maximum delay before a message is delivered
+    pub max_latency: Duration,
+    /// The probability, in `[0, 1]`, that a message is lost
+    pub drop_probability: f64,
+    /// The probability, in `[0, 1]`, that a message is delivered twice
+    pub duplicate_probability: f64,
+    /// The probability, in `[0, 1]`, that a message is held back by an extra `reorder_delay`,
+    /// letting later messages overtake it
+    pub reorder_probability: f64,
+    /// The extra delay applied to reordered messages
+    pub reorder_delay: Duration,
+}
+
+impl Default for LinkConditions {
+    fn default() -> Self {
+        Self {
+            min_latency: Duration::ZERO,
+            max_latency: Duration::ZERO,
+            drop_probability: 0.0,
+            duplicate_probability: 0.0,
+            reorder_probability: 0.0,
+            reorder_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+impl LinkConditions {
+    /// Returns these conditions with every probability in `[0, 1]`, and `max_latency` no lower
+    /// than `min_latency`, so they can never make the network panic.
+    #[must_use]
+    pub fn sanitized(mut self) -> Self {
+        self.max_latency = self.max_latency.max(self.min_latency);
+        self.drop_probability = sanitize_probability(self.drop_probability);
+        self.duplicate_probability = sanitize_probability(self.duplicate_probability);
+        self.reorder_probability = sanitize_probability(self.reorder_probability);
+        self
+    }
+}
+
+/// Clamp `probability` to `[0, 1]`, treating NaN as `0`
+fn sanitize_probability(probability: f64) -> f64 {
+    if probability.is_nan() {
+        0.0
+    } else {
+        probability.clamp(0.0, 1.0)
+    }
+}
+
+/// Counters describing what the simulated network did with the messages it was given.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulationStats {
+    pub sent: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub duplicated: u64,
+    pub partitioned: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    sent: AtomicU64,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+    duplicated: AtomicU64,
+    partitioned: AtomicU64,
+}
+
+#[derive(Debug)]
+struct SimulationState {
+    seed: u64,
+    conditions: RwLock<LinkConditions>,
+    /// The partition group of each party. Parties can only talk to parties in the same group.
+    partitions: RwLock<Vec<usize>>,
+    public_keys: Vec<GossipMsgPublicKey>,
+    inboxes: Vec<UnboundedSender<ProtocolMessage>>,
+    /// One RNG per (sender, receiver) link, so that the fate of a message only depends on the
+    /// messages previously sent over the same link, not on how tasks are scheduled
+    link_rngs: Mutex<HashMap<(usize, usize), StdRng>>,
+    counters: Counters,
+}
+
+impl SimulationState {
+    fn party_index(&self, public_key: &GossipMsgPublicKey) -> Option<usize> {
+        self.public_keys.iter().position(|key| key == public_key)
+    }
+
+    fn route(&self, from: usize, to: usize, message: &ProtocolMessage) {
+        let partitioned = {
+            let partitions = self.partitions.read();
+            partitions[from] != partitions[to]
+        };
+        if partitioned {
+            let _ = self.counters.partitioned.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let conditions = *self.conditions.read();
+        let (dropped, copies) = {
+            let mut rngs = self.link_rngs.lock();
+            let rng = rngs
+                .entry((from, to))
+                .or_insert_with(|| StdRng::seed_from_u64(link_seed(self.seed, from, to)));
+
+            let dropped = rng.gen_bool(conditions.drop_probability);
+            let duplicated = rng.gen_bool(conditions.duplicate_probability);
+            let copies = (0..1 + usize::from(duplicated))
+                .map(|_| {
+                    let mut delay = if conditions.max_latency > conditions.min_latency {
+                        rng.gen_range(conditions.min_latency..=conditions.max_latency)
+                    } else {
+                        conditions.min_latency
+                    };
+                    if rng.gen_bool(conditions.reorder_probability) {
+                        delay += conditions.reorder_delay;
+                    }
+                    delay
+                })
+                .collect::<Vec<_>>();
+            (dropped, copies)
+        };
+
+        if dropped {
+            let _ = self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        if copies.len() > 1 {
+            let _ = self.counters.duplicated.fetch_add(1, Ordering::Relaxed);
+        }
+
+        for delay in copies {
+            let inbox = self.inboxes[to].clone();
+            let message = message.clone();
+            let _ = self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+            if delay.is_zero() {
+                let _ = inbox.send(message);
+            } else {
+                drop(tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = inbox.send(message);
+                }));
+            }
+        }
+    }
+}
+
+fn link_seed(seed: u64, from: usize, to: usize) -> u64 {
+    let mut hasher_input = [0u8; 24];
+    hasher_input[..8].copy_from_slice(&seed.to_le_bytes());
+    hasher_input[8..16].copy_from_slice(&(from as u64).to_le_bytes());
+    hasher_input[16..].copy_from_slice(&(to as u64).to_le_bytes());
+    let hash = gadget_crypto::hashing::blake3_256(&hasher_input);
+    u64::from_le_bytes(hash[..8].try_into().expect("8 bytes"))
+}
+
+/// Builder for a [`SimulatedNetwork`].
+#[derive(Debug, Clone)]
+pub struct SimulatedNetworkBuilder {
+    parties: usize,
+    seed: u64,
+    conditions: LinkConditions,
+}
+
+impl SimulatedNetworkBuilder {
+    /// Seed used for all randomness, including the party keys.
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Deliver each message after a delay chosen uniformly from `min..=max`.
+    #[must_use]
+    pub fn latency(mut self, min: Duration, max: Duration) -> Self {
+        self.conditions.min_latency = min;
+        self.conditions.max_latency = max.max(min);
+        self
+    }
+
+    /// Lose each message with the given probability.
+    #[must_use]
+    pub fn drop_probability(mut self, probability: f64) -> Self {
+        self.conditions.drop_probability = sanitize_probability(probability);
+        self
+    }
+
+    /// Deliver each message twice with the given probability.
+    #[must_use]
+    pub fn duplicate_probability(mut self, probability: f64) -> Self {
+        self.conditions.duplicate_probability = sanitize_probability(probability);
+        self
+    }
+
+    /// Hold back each message by `delay` with the given probability, so that later messages overtake it.
+    #[must_use]
+    pub fn reorder(mut self, probability: f64, delay: Duration) -> Self {
+        self.conditions.reorder_probability = sanitize_probability(probability);
+        self.conditions.reorder_delay = delay;
+        self
+    }
+
+    /// Use the given link conditions wholesale, after [sanitizing](LinkConditions::sanitized) them.
+    #[must_use]
+    pub fn conditions(mut self, conditions: LinkConditions) -> Self {
+        self.conditions = conditions.sanitized();
+        self
+    }
+
+    /// Build the network, returning a controller and one [`SimulatedParty`] per party, in index order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a party key cannot be generated from the seed.
+    #[must_use]
+    pub fn build(self) -> (SimulatedNetwork, Vec<SimulatedParty>) {
+        let mut public_keys = Vec::with_capacity(self.parties);
+        let mut inboxes = Vec::with_capacity(self.parties);
+        let mut receivers = Vec::with_capacity(self.parties);
+        for i in 0..self.parties {
+            let key_seed = gadget_crypto::hashing::blake3_256(
+                format!("simulated-party-{}-{i}", self.seed).as_bytes(),
+            );
+            let secret = Curve::generate_with_seed(Some(&key_seed))
+                .expect("Should be able to generate a key from a 32 byte seed");
+            public_keys.push(Curve::public_from_secret(&secret));
+
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            inboxes.push(tx);
+            receivers.push(rx);
+        }
+
+        let state = Arc::new(SimulationState {
+            seed: self.seed,
+            conditions: RwLock::new(self.conditions),
+            partitions: RwLock::new(vec![0; self.parties]),
+            public_keys,
+            inboxes,
+            link_rngs: Mutex::new(HashMap::new()),
+            counters: Counters::default(),
+        });
+
+        let parties = receivers
+            .into_iter()
+            .enumerate()
+            .map(|(index, rx)| SimulatedParty {
+                index,
+                state: state.clone(),
+                rx: tokio::sync::Mutex::new(rx),
+            })
+            .collect();
+
+        (SimulatedNetwork { state }, parties)
+    }
+}
+
+/// Controller for a set of [`SimulatedParty`]s, used to change network conditions mid-test.
+#[derive(Debug, Clone)]
+pub struct SimulatedNetwork {
+    state: Arc<SimulationState>,
+}
+
+impl SimulatedNetwork {
+    /// Start building a network of `parties` parties with perfect links.
+    #[must_use]
+    pub fn builder(parties: usize) -> SimulatedNetworkBuilder {
+        SimulatedNetworkBuilder {
+            parties,
+            seed: 0,
+            conditions: LinkConditions::default(),
+        }
+    }
+
+    /// Returns the public keys of all parties, in index order.
+    #[must_use]
+    pub fn public_keys(&self) -> &[GossipMsgPublicKey] {
+        &self.state.public_keys
+    }
+
+    /// Returns the current link conditions.
+    #[must_use]
+    pub fn conditions(&self) -> LinkConditions {
+        *self.state.conditions.read()
+    }
+
+    /// Replace the link conditions for all subsequently sent messages, after
+    /// [sanitizing](LinkConditions::sanitized) them.
+    pub fn set_conditions(&self, conditions: LinkConditions) {
+        *self.state.conditions.write() = conditions.sanitized();
+    }
+
+    /// Split the network into the given groups of party indices.
+    ///
+    /// Messages between parties in different groups are silently dropped. Parties that are not
+    /// listed are isolated in a group of their own.
+    pub fn partition(&self, groups: &[&[usize]]) {
+        let mut partitions = self.state.partitions.write();
+        let isolated = groups.len();
+        for (index, group) in partitions.iter_mut().enumerate() {
+            *group = isolated + index;
+        }
+        for (group_id, group) in groups.iter().enumerate() {
+            for &index in *group {
+                if let Some(group) = partitions.get_mut(index) {
+                    *group = group_id;
+                }
+            }
+        }
+    }
+
+    /// Remove all partitions.
+    pub fn heal(&self) {
+        self.state.partitions.write().fill(0);
+    }
+
+    /// Returns counters describing the messages seen so far.
+    #[must_use]
+    pub fn stats(&self) -> SimulationStats {
+        let counters = &self.state.counters;
+        SimulationStats {
+            sent: counters.sent.load(Ordering::Relaxed),
+            delivered: counters.delivered.load(Ordering::Relaxed),
+            dropped: counters.dropped.load(Ordering::Relaxed),
+            duplicated: counters.duplicated.load(Ordering::Relaxed),
+            partitioned: counters.partitioned.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A single party of a [`SimulatedNetwork`].
+///
+/// Like [`GossipHandle`](crate::gossip::GossipHandle), messages with a recipient public key are
+/// delivered only to that party, and all others are broadcast to every other party.
+#[derive(Debug)]
+pub struct SimulatedParty {
+    index: usize,
+    state: Arc<SimulationState>,
+    rx: tokio::sync::Mutex<UnboundedReceiver<ProtocolMessage>>,
+}
+
+impl SimulatedParty {
+    /// The index of this party in the network.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+#[async_trait]
+impl Network for SimulatedParty {
+    async fn next_message(&self) -> Option<ProtocolMessage> {
+        self.rx.lock().await.recv().await
+    }
+
+    async fn send_message(&self, mut message: ProtocolMessage) -> Result<(), Error> {
+        message.sender.public_key = Some(self.public_id());
+        let _ = self.state.counters.sent.fetch_add(1, Ordering::Relaxed);
+
+        match message.recipient.and_then(|recipient| recipient.public_key) {
+            Some(to) => {
+                let to = self.state.party_index(&to).ok_or_else(|| {
+                    Error::NetworkError(format!("No simulated party with public key: {to:?}"))
+                })?;
+                self.state.route(self.index, to, &message);
+            }
+            None => {
+                for to in (0..self.state.inboxes.len()).filter(|&to| to != self.index) {
+                    self.state.route(self.index, to, &message);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn public_id(&self) -> GossipMsgPublicKey {
+        self.state.public_keys[self.index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networking::{IdentifierInfo, NetworkMultiplexer, StreamKey};
+
+    const STREAM: StreamKey = StreamKey {
+        task_hash: [7u8; 32],
+        round_id: 0,
+    };
+
+    async fn send_and_collect(
+        builder: SimulatedNetworkBuilder,
+        count: u16,
+    ) -> (SimulationStats, Vec<u16>) {
+        let (network, mut parties) = builder.build();
+        let receiver = NetworkMultiplexer::new(parties.pop().unwrap()).multiplex(STREAM);
+        let sender = NetworkMultiplexer::new(parties.pop().unwrap()).multiplex(STREAM);
+        let to = network.public_keys()[1];
+
+        for i in 0..count {
+            let msg =
+                sender.build_protocol_message(IdentifierInfo::default(), 0, Some(1), &i, Some(to));
+            sender.send(msg).unwrap();
+        }
+
+        let mut received = Vec::new();
+        while let Ok(Some(msg)) =
+            tokio::time::timeout(Duration::from_secs(1), receiver.recv()).await
+        {
+            received.push(bincode::deserialize(&msg.payload).unwrap());
+            if received.len() == usize::from(count) {
+                break;
+            }
+        }
+
+        (network.stats(), received)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn multiplexer_restores_order_and_removes_duplicates() {
+        let builder = SimulatedNetwork::builder(2)
+            .seed(1)
+            .latency(Duration::from_millis(1), Duration::from_millis(30))
+            .reorder(0.3, Duration::from_millis(100))
+            .duplicate_probability(0.3);
+
+        let (stats, received) = send_and_collect(builder, 100).await;
+        assert!(stats.duplicated > 0);
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn same_seed_is_reproducible() {
+        let builder = SimulatedNetwork::builder(2)
+            .seed(1234)
+            .drop_probability(0.2)
+            .duplicate_probability(0.2);
+
+        let (first, _) = send_and_collect(builder.clone(), 50).await;
+        let (second, _) = send_and_collect(builder, 50).await;
+        assert!(first.dropped > 0);
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn invalid_probabilities_are_sanitized() {
+        let builder = SimulatedNetwork::builder(2)
+            .drop_probability(f64::NAN)
+            .duplicate_probability(-1.0)
+            .reorder(2.0, Duration::ZERO);
+        let (stats, received) = send_and_collect(builder, 10).await;
+        assert_eq!(stats.dropped, 0);
+        assert_eq!(received.len(), 10);
+
+        let (network, _parties) = SimulatedNetwork::builder(2).build();
+        network.set_conditions(LinkConditions {
+            drop_probability: f64::NAN,
+            duplicate_probability: f64::INFINITY,
+            reorder_probability: f64::NEG_INFINITY,
+            max_latency: Duration::ZERO,
+            min_latency: Duration::from_millis(1),
+            ..LinkConditions::default()
+        });
+        assert_eq!(
+            network.conditions(),
+            LinkConditions {
+                duplicate_probability: 1.0,
+                max_latency: Duration::from_millis(1),
+                min_latency: Duration::from_millis(1),
+                ..LinkConditions::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn partitions_block_messages_until_healed() {
+        let (network, parties) = SimulatedNetwork::builder(3).build();
+        network.partition(&[&[0], &[1, 2]]);
+
+        let msg = parties[1].build_protocol_message(IdentifierInfo::default(), 1, None, &(), None);
+        parties[1].send_message(msg.clone()).await.unwrap();
+        assert!(parties[2].next_message().await.is_some());
+        assert!(parties[0].rx.lock().await.try_recv().is_err());
+        assert_eq!(network.stats().partitioned, 1);
+
+        network.heal();
+        parties[1].send_message(msg).await.unwrap();
+        assert!(parties[0].next_message().await.is_some());
+    }
+}
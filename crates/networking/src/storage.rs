@@ -0,0 +1,46 @@
+//! Helpers for the lists persisted to the network data directory.
+
+use gadget_std::fs;
+use gadget_std::io::{self, Write};
+use gadget_std::path::Path;
+use gadget_std::vec::Vec;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Read the JSON list at `path`, logging and returning an empty list if it cannot be read
+///
+/// `what` names the list in log messages. A missing file is not an error.
+pub(crate) fn load_json_list<T: DeserializeOwned>(path: &Path, what: &str) -> Vec<T> {
+    match fs::read(path) {
+        Ok(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(entries) => entries,
+            Err(e) => {
+                gadget_logging::error!("Failed to parse {what} at {}: {e}", path.display());
+                Vec::new()
+            }
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            gadget_logging::error!("Failed to read {what} at {}: {e}", path.display());
+            Vec::new()
+        }
+    }
+}
+
+/// Write `entries` to `path` as a JSON list, creating its parent directories
+///
+/// The list is written and synced beside the old file first, then renamed over it, so a crash never
+/// leaves a truncated file behind.
+pub(crate) fn store_json_list<T: Serialize>(path: &Path, entries: &[T]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let bytes = serde_json::to_vec_pretty(entries)?;
+
+    let tmp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(tmp, path)
+}
@@ -2,10 +2,13 @@ use crate::config::BlueprintConfig;
 use crate::error::RunnerError as Error;
 use crate::jobs::JobBuilder;
 use core::pin::Pin;
+use std::sync::Arc;
 
 use futures::Future;
 use gadget_config::GadgetConfiguration;
-use gadget_event_listeners::core::InitializableEventHandler;
+use gadget_event_listeners::core::dead_letter::DeadLetterReplayers;
+use gadget_event_listeners::core::middleware::JobLayer;
+use gadget_event_listeners::core::{HandlerSettings, InitializableEventHandler};
 use tokio::sync::oneshot;
 
 #[async_trait::async_trait]
@@ -18,6 +21,12 @@ pub struct BlueprintRunner {
     pub jobs: Vec<Box<dyn InitializableEventHandler + Send + 'static>>,
     pub env: GadgetConfiguration,
     pub background_services: Vec<Box<dyn BackgroundService>>,
+    /// Layers that wrap every job, see [`BlueprintRunner::layer`]
+    pub layers: Vec<Arc<dyn JobLayer>>,
+    /// The dead-letter replayers of the jobs, registered as they start
+    ///
+    /// Clone this before calling [`BlueprintRunner::run`] to replay dead letters while the jobs run.
+    pub dead_letters: DeadLetterReplayers,
 }
 
 impl BlueprintRunner {
@@ -26,6 +35,8 @@ impl BlueprintRunner {
             config: Box::new(config),
             jobs: Vec::new(),
             background_services: Vec::new(),
+            layers: Vec::new(),
+            dead_letters: DeadLetterReplayers::default(),
             env,
         }
     }
@@ -40,6 +51,15 @@ impl BlueprintRunner {
         self
     }
 
+    /// Wrap every job in `layer`
+    ///
+    /// These layers run outside any layers attached to a job through `#[job(layers(...))]`, in the
+    /// order they are added.
+    pub fn layer<L: JobLayer>(&mut self, layer: L) -> &mut Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
     pub fn background_service(&mut self, service: Box<dyn BackgroundService>) -> &mut Self {
         self.background_services.push(service);
         self
@@ -58,10 +78,17 @@ impl BlueprintRunner {
 
         let mut all_futures = Vec::new();
 
+        let settings = HandlerSettings {
+            data_dir: self.env.data_dir.clone(),
+            layers: self.layers.iter().cloned().collect(),
+            dead_letters: self.dead_letters.clone(),
+        };
+
         // Handle job futures
         for job in self.jobs.drain(..) {
+            let settings = settings.clone();
             all_futures.push(Box::pin(async move {
-                match job.init_event_handler().await {
+                match job.init_event_handler(&settings).await {
                     Some(receiver) => receiver
                         .await
                         .map(|_| ())
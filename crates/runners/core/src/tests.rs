@@ -38,6 +38,19 @@ async fn test_runner_creation() {
 
     assert!(runner.jobs.is_empty());
     assert!(runner.background_services.is_empty());
+    assert!(runner.layers.is_empty());
+}
+
+#[tokio::test]
+async fn test_layer_addition() {
+    use gadget_event_listeners::core::middleware::{MetricsLayer, TraceLayer};
+
+    let config = MockBlueprintConfig;
+    let env = GadgetConfiguration::default();
+    let mut runner = BlueprintRunner::new(config, env);
+
+    runner.layer(TraceLayer).layer(MetricsLayer);
+    assert_eq!(runner.layers.len(), 2);
 }
 
 #[tokio::test]
@@ -1,12 +1,16 @@
 pub mod error;
+pub mod qos;
 pub mod tangle;
 
 #[cfg(test)]
 mod tests {
-    use crate::tangle::{get_client, TangleConfig};
+    use crate::tangle::{get_client, PriceTargets, TangleConfig};
+    use gadget_benchmarking::{BenchmarkReport, BenchmarkSummary, DurationStats};
     use gadget_config::protocol::TangleInstanceSettings;
     use gadget_config::{GadgetConfiguration, ProtocolSettings};
     use gadget_runner_core::config::BlueprintConfig;
+    use std::time::Duration;
+    use tangle_subxt::tangle_testnet_runtime::api::runtime_types::tangle_primitives::services::PriceTargets as TanglePriceTargets;
 
     fn create_test_config() -> GadgetConfiguration {
         let mut config = GadgetConfiguration::default();
@@ -32,4 +36,45 @@ mod tests {
         // This will fail without a running node, which is expected
         assert!(result.is_err());
     }
+
+    fn summary(cores: usize, peak_memory: u64, disk_written: u64) -> BenchmarkSummary {
+        BenchmarkSummary {
+            name: "bench".to_string(),
+            job_id: 0,
+            elapsed: Duration::from_secs(1),
+            cores,
+            iterations: 1,
+            durations: DurationStats::from_samples(&[Duration::from_secs(1)]),
+            cpu_time: Duration::from_secs(1),
+            ram_usage: peak_memory,
+            peak_memory,
+            disk_read: 0,
+            disk_written,
+            network_received: 0,
+            network_transmitted: 0,
+        }
+    }
+
+    #[test]
+    fn test_price_targets_from_benchmarks() {
+        const MIB: u64 = 1024 * 1024;
+
+        let report = BenchmarkReport {
+            benchmarks: vec![summary(2, 100 * MIB, 0), summary(4, 10 * MIB, MIB + 1)],
+        };
+        let unit_prices = PriceTargets(TanglePriceTargets {
+            cpu: 10,
+            mem: 2,
+            storage_hdd: 1,
+            storage_ssd: 3,
+            storage_nvme: 5,
+        });
+
+        let targets = PriceTargets::from_benchmarks(&report, &unit_prices).0;
+        assert_eq!(targets.cpu, 40);
+        assert_eq!(targets.mem, 200);
+        assert_eq!(targets.storage_hdd, 2);
+        assert_eq!(targets.storage_ssd, 6);
+        assert_eq!(targets.storage_nvme, 10);
+    }
 }
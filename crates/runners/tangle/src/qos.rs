@@ -0,0 +1,615 @@
+//! The runtime of `QoS` reports
+//!
+//! A `QoS` report is a function annotated with `#[report(report_type = "qos", ...)]`. Every
+//! `interval`, the [`QosReportService`] reads the metrics recorded in the
+//! [`MetricsRegistry`](gadget_metrics::registry::MetricsRegistry) and checks them against the
+//! report's `metric_thresholds`. When any threshold is violated, the report function is called with
+//! the metrics named in its `params`, and its result is verified, if the report has a verifier, then
+//! submitted with a [`ReportSubmitter`]. A metric that was never recorded counts as a violation.
+//!
+//! With the [`TangleReportSubmitter`], the report function decides who is slashed, and by how much, by
+//! returning a [`Slash`].
+//!
+//! ```rust,ignore
+//! use blueprint_sdk::runners::tangle::qos::{QosReportService, TangleReportSubmitter};
+//!
+//! let submitter = TangleReportSubmitter::new(env.clone());
+//! let qos = QosReportService::new(env.clone(), submitter).report(report_service_health_qos(context));
+//! BlueprintRunner::new(config, env).background_service(Box::new(qos)).run().await?;
+//! ```
+
+use crate::error::TangleError;
+use crate::tangle::get_client;
+use gadget_blueprint_proc_macro_core::{
+    MetricThreshold, ReportDefinition, ReportResultVerifier, ReportType,
+};
+use gadget_config::{GadgetConfiguration, ProtocolSettings};
+use gadget_crypto::sp_core::SpSr25519;
+use gadget_event_listeners::cronjob::CronJob;
+use gadget_keystore::backends::Backend;
+use gadget_keystore::Keystore;
+use gadget_metrics::registry::{MetricsRegistry, Snapshot};
+use gadget_runner_core::error::RunnerError;
+use gadget_runner_core::runner::BackgroundService;
+use gadget_std::fmt::Display;
+use gadget_std::future::Future;
+use gadget_std::pin::Pin;
+use gadget_std::string::{String, ToString};
+use gadget_std::sync::Arc;
+use gadget_std::time::Duration;
+use gadget_std::vec::Vec;
+use serde::{Deserialize, Serialize};
+use subxt_core::config::PolkadotConfig;
+use subxt_core::tx::signer::PairSigner;
+use subxt_core::utils::AccountId32;
+use tangle_subxt::subxt_core;
+use tangle_subxt::tangle_testnet_runtime::api;
+use tangle_subxt::tangle_testnet_runtime::api::runtime_types::sp_arithmetic::per_things::Percent;
+use tokio::sync::oneshot;
+
+#[derive(Debug, thiserror::Error)]
+pub enum QosError {
+    #[error("Invalid report definition: {0}")]
+    Definition(String),
+    #[error("Metric `{0}` was not recorded")]
+    MissingMetric(String),
+    #[error("Metric `{name}` has a value out of range: {value}")]
+    InvalidMetric { name: String, value: u64 },
+    #[error("Report failed: {0}")]
+    Report(String),
+    #[error("Report `{report}` must be verified by the `{contract}` contract, set its verifier with `QosReport::verifier`")]
+    MissingVerifier { report: String, contract: String },
+    #[error("Failed to verify the report: {0}")]
+    Verification(String),
+    #[error("Failed to submit the report: {0}")]
+    Submission(String),
+}
+
+impl From<QosError> for RunnerError {
+    fn from(err: QosError) -> Self {
+        TangleError::Other(err.to_string()).into()
+    }
+}
+
+/// The metrics a `QoS` report is evaluated with
+#[derive(Debug, Clone, Default)]
+pub struct Metrics(pub Snapshot);
+
+impl Metrics {
+    /// The value of the metric `name`
+    ///
+    /// # Errors
+    ///
+    /// * The metric was never recorded
+    /// * The value doesn't fit in `T`
+    pub fn get<T: TryFrom<u64>>(&self, name: &str) -> Result<T, QosError> {
+        let value = *self
+            .0
+            .get(name)
+            .ok_or_else(|| QosError::MissingMetric(name.to_string()))?;
+        T::try_from(value).map_err(|_| QosError::InvalidMetric {
+            name: name.to_string(),
+            value,
+        })
+    }
+}
+
+/// The result of a `QoS` report function, encoded for submission
+pub trait IntoReport {
+    /// Encode the result as JSON
+    ///
+    /// # Errors
+    ///
+    /// The report function failed, or its result could not be encoded
+    fn into_report(self) -> Result<Vec<u8>, QosError>;
+}
+
+impl<T: Serialize, E: Display> IntoReport for Result<T, E> {
+    fn into_report(self) -> Result<Vec<u8>, QosError> {
+        let output = self.map_err(|e| QosError::Report(e.to_string()))?;
+        serde_json::to_vec(&output).map_err(|e| QosError::Report(e.to_string()))
+    }
+}
+
+/// A metric that was outside of its threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub threshold: MetricThreshold,
+    /// The metric's value, or `None` if it was never recorded
+    pub value: Option<u64>,
+}
+
+/// A report of the thresholds violated in a service, and the output of the report function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosViolation {
+    /// The name of the report function
+    pub report: String,
+    pub service_id: u64,
+    pub violations: Vec<Violation>,
+    /// The JSON-encoded result of the report function
+    pub output: Vec<u8>,
+}
+
+/// Checks a report before it is submitted
+#[async_trait::async_trait]
+pub trait ReportVerifier: Send + Sync + 'static {
+    /// Whether the report should be submitted
+    async fn verify(&self, report: &QosViolation) -> Result<bool, QosError>;
+}
+
+/// Submits reports, for example to the Tangle services pallet with [`TangleReportSubmitter`]
+#[async_trait::async_trait]
+pub trait ReportSubmitter: Send + Sync + 'static {
+    async fn submit(&self, report: &QosViolation) -> Result<(), QosError>;
+}
+
+type ReportFn = dyn Fn(&Metrics) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, QosError>> + Send>>
+    + Send
+    + Sync;
+
+/// A `QoS` report function and its definition, usually created by the function generated by
+/// `#[report(report_type = "qos", ...)]`
+#[derive(Clone)]
+pub struct QosReport {
+    name: String,
+    interval: Duration,
+    thresholds: Vec<MetricThreshold>,
+    evm_verifier: Option<String>,
+    verifier: Option<Arc<dyn ReportVerifier>>,
+    report: Arc<ReportFn>,
+}
+
+impl QosReport {
+    /// Create a report from its JSON [`ReportDefinition`]
+    ///
+    /// # Errors
+    ///
+    /// * The definition is not valid JSON
+    /// * The definition is not for a `QoS` report, or has no interval
+    pub fn from_definition<F, Fut>(definition: &str, report: F) -> Result<Self, QosError>
+    where
+        F: Fn(&Metrics) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Vec<u8>, QosError>> + Send + 'static,
+    {
+        let definition: ReportDefinition<'_> =
+            serde_json::from_str(definition).map_err(|e| QosError::Definition(e.to_string()))?;
+        let name = definition.metadata.name.to_string();
+        if definition.report_type != ReportType::QoS {
+            return Err(QosError::Definition(format!(
+                "`{name}` is not a QoS report"
+            )));
+        }
+
+        let interval = definition
+            .interval
+            .ok_or_else(|| QosError::Definition(format!("`{name}` has no interval")))?;
+
+        let evm_verifier = match definition.verifier {
+            ReportResultVerifier::Evm(contract) => Some(contract),
+            ReportResultVerifier::None => None,
+        };
+
+        Ok(Self {
+            name,
+            interval: Duration::from_secs(interval),
+            thresholds: definition.metric_thresholds.unwrap_or_default(),
+            evm_verifier,
+            verifier: None,
+            report: Arc::new(move |metrics| Box::pin(report(metrics))),
+        })
+    }
+
+    /// Verify the report before it is submitted
+    ///
+    /// This is required for reports declared with `verifier(evm = "...")`, see
+    /// `EvmReportVerifier` with the `evm` feature.
+    #[must_use]
+    pub fn verifier<V: ReportVerifier>(mut self, verifier: V) -> Self {
+        self.verifier = Some(Arc::new(verifier));
+        self
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// The thresholds violated by `metrics`
+    ///
+    /// A threshold on a metric that was never recorded is violated, as the service can't be shown to
+    /// meet it.
+    #[must_use]
+    pub fn violations(&self, metrics: &Metrics) -> Vec<Violation> {
+        self.thresholds
+            .iter()
+            .filter_map(|threshold| {
+                let Some(&value) = metrics.0.get(&threshold.metric) else {
+                    gadget_logging::warn!(
+                        "Metric `{}` checked by report `{}` was not recorded",
+                        threshold.metric,
+                        self.name
+                    );
+                    return Some(Violation {
+                        threshold: threshold.clone(),
+                        value: None,
+                    });
+                };
+                threshold.is_violated_by(value).then(|| Violation {
+                    threshold: threshold.clone(),
+                    value: Some(value),
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluate the report against `metrics`, returning the report to submit if any threshold was
+    /// violated and the report was verified
+    ///
+    /// # Errors
+    ///
+    /// The report function or its verifier failed
+    pub async fn evaluate(
+        &self,
+        service_id: u64,
+        metrics: &Metrics,
+    ) -> Result<Option<QosViolation>, QosError> {
+        let violations = self.violations(metrics);
+        if violations.is_empty() {
+            return Ok(None);
+        }
+
+        let report = QosViolation {
+            report: self.name.clone(),
+            service_id,
+            violations,
+            output: (self.report)(metrics).await?,
+        };
+
+        if let Some(verifier) = &self.verifier {
+            if !verifier.verify(&report).await? {
+                gadget_logging::info!("Report `{}` was rejected by its verifier", self.name);
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(report))
+    }
+}
+
+/// Runs [`QosReport`]s on their interval, as a [`BackgroundService`]
+pub struct QosReportService {
+    env: GadgetConfiguration,
+    reports: Vec<QosReport>,
+    submitter: Arc<dyn ReportSubmitter>,
+}
+
+impl QosReportService {
+    pub fn new<S: ReportSubmitter>(env: GadgetConfiguration, submitter: S) -> Self {
+        Self {
+            env,
+            reports: Vec::new(),
+            submitter: Arc::new(submitter),
+        }
+    }
+
+    #[must_use]
+    pub fn report(mut self, report: QosReport) -> Self {
+        self.reports.push(report);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl BackgroundService for QosReportService {
+    async fn start(&self) -> Result<oneshot::Receiver<Result<(), RunnerError>>, RunnerError> {
+        let service_id = match self.env.protocol_settings {
+            ProtocolSettings::Tangle(settings) => settings.service_id.ok_or_else(|| {
+                RunnerError::Config("QoS reports require a service ID".to_string())
+            })?,
+            _ => {
+                return Err(RunnerError::InvalidProtocol(
+                    "Expected Tangle protocol".into(),
+                ))
+            }
+        };
+
+        for report in &self.reports {
+            if let (Some(contract), None) = (&report.evm_verifier, &report.verifier) {
+                return Err(QosError::MissingVerifier {
+                    report: report.name.clone(),
+                    contract: contract.clone(),
+                }
+                .into());
+            }
+        }
+
+        // Install the registry before the first interval, so it sees every metric recorded
+        let registry = MetricsRegistry::global();
+
+        let mut tasks = Vec::new();
+        for report in self.reports.clone() {
+            let submitter = self.submitter.clone();
+            let mut schedule = CronJob::<()>::every(report.interval)
+                .await
+                .map_err(|e| RunnerError::Other(e.to_string()))?;
+
+            tasks.push(tokio::spawn(async move {
+                while schedule.next_tick().await.is_some() {
+                    let metrics = Metrics(registry.snapshot());
+                    let result = match report.evaluate(service_id, &metrics).await {
+                        Ok(Some(violation)) => submitter.submit(&violation).await,
+                        Ok(None) => Ok(()),
+                        Err(e) => Err(e),
+                    };
+
+                    if let Err(e) = result {
+                        gadget_logging::error!("QoS report `{}` failed: {e}", report.name);
+                    }
+                }
+            }));
+        }
+
+        let (tx, rx) = oneshot::channel();
+        drop(tokio::spawn(async move {
+            let result = futures::future::try_join_all(tasks)
+                .await
+                .map(|_| ())
+                .map_err(|e| RunnerError::Other(e.to_string()));
+            let _ = tx.send(result);
+        }));
+
+        Ok(rx)
+    }
+}
+
+/// A slash requested by a `QoS` report
+///
+/// Returned by report functions whose reports are submitted with the [`TangleReportSubmitter`], as
+/// `Option<Slash>` when not every report should slash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Slash {
+    /// The operator to slash
+    pub offender: AccountId32,
+    /// The percentage of the offender's stake to slash, at most `100`
+    pub percent: u8,
+}
+
+type SlashFn = dyn Fn(&QosViolation) -> Result<Option<Slash>, QosError> + Send + Sync;
+
+/// Submits reports by slashing the offending operator through the Tangle services pallet
+///
+/// The operator must be allowed to slash in the service, see the blueprint's service manager.
+#[derive(Clone)]
+pub struct TangleReportSubmitter {
+    env: GadgetConfiguration,
+    slash: Arc<SlashFn>,
+}
+
+impl TangleReportSubmitter {
+    /// Slash as decided by the report function, whose output must be a [`Slash`], or `null` to not
+    /// slash anyone
+    #[must_use]
+    pub fn new(env: GadgetConfiguration) -> Self {
+        Self::with_slash(env, |report| {
+            serde_json::from_slice::<Option<Slash>>(&report.output).map_err(|e| {
+                QosError::Submission(format!(
+                    "the output of report `{}` is not a slash: {e}",
+                    report.report
+                ))
+            })
+        })
+    }
+
+    /// Slash as decided by `slash`, given the report's violations and output
+    #[must_use]
+    pub fn with_slash<F>(env: GadgetConfiguration, slash: F) -> Self
+    where
+        F: Fn(&QosViolation) -> Result<Option<Slash>, QosError> + Send + Sync + 'static,
+    {
+        Self {
+            env,
+            slash: Arc::new(slash),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ReportSubmitter for TangleReportSubmitter {
+    async fn submit(&self, report: &QosViolation) -> Result<(), QosError> {
+        let submission = |e: &dyn Display| QosError::Submission(e.to_string());
+
+        let Some(slash) = (self.slash)(report)? else {
+            gadget_logging::info!(
+                "QoS report `{}` for service {} requested no slash",
+                report.report,
+                report.service_id
+            );
+            return Ok(());
+        };
+
+        let client = get_client(
+            self.env.ws_rpc_endpoint.as_str(),
+            self.env.http_rpc_endpoint.as_str(),
+        )
+        .await
+        .map_err(|e| submission(&e))?;
+
+        let keystore_config = self.env.keystore_config().map_err(|e| submission(&e))?;
+        let keystore = Keystore::new(keystore_config).map_err(|e| submission(&e))?;
+        let key = keystore
+            .first_local::<SpSr25519>()
+            .map_err(|e| submission(&e))?;
+        let pair = keystore
+            .get_secret::<SpSr25519>(&key)
+            .map_err(|e| submission(&e))?;
+        let signer: PairSigner<PolkadotConfig, _> = PairSigner::new(pair.0);
+
+        let xt = api::tx().services().slash(
+            slash.offender.clone(),
+            report.service_id,
+            Percent(slash.percent.min(100)),
+        );
+        let result = gadget_utils::tangle::tx::send(&client, &signer, &xt)
+            .await
+            .map_err(|e| submission(&e))?;
+        gadget_logging::info!(
+            "Submitted QoS report `{}` for service {}, slashing {}% of {} for {} violation(s), with hash: {:?}",
+            report.report,
+            report.service_id,
+            slash.percent.min(100),
+            slash.offender,
+            report.violations.len(),
+            result
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "evm")]
+pub use evm::EvmReportVerifier;
+
+#[cfg(feature = "evm")]
+mod evm {
+    use super::{QosError, QosViolation, ReportVerifier};
+    use alloy_primitives::{Address, Bytes};
+    use gadget_std::string::{String, ToString};
+
+    alloy_sol_types::sol! {
+        #[sol(rpc)]
+        interface IReportVerifier {
+            function verifyReport(uint64 serviceId, bytes calldata report) external view returns (bool);
+        }
+    }
+
+    /// Verifies reports with a contract implementing
+    /// `verifyReport(uint64 serviceId, bytes report) returns (bool)`
+    ///
+    /// The contract is called with the JSON-encoded [`QosViolation::output`].
+    #[derive(Debug, Clone)]
+    pub struct EvmReportVerifier {
+        http_rpc_url: String,
+        address: Address,
+    }
+
+    impl EvmReportVerifier {
+        #[must_use]
+        pub fn new(http_rpc_url: impl Into<String>, address: Address) -> Self {
+            Self {
+                http_rpc_url: http_rpc_url.into(),
+                address,
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ReportVerifier for EvmReportVerifier {
+        async fn verify(&self, report: &QosViolation) -> Result<bool, QosError> {
+            let provider = gadget_utils::evm::get_provider_http(&self.http_rpc_url);
+            let verifier = IReportVerifier::new(self.address, provider);
+            let verified = verifier
+                .verifyReport(report.service_id, Bytes::from(report.output.clone()))
+                .call()
+                .await
+                .map_err(|e| QosError::Verification(e.to_string()))?;
+            Ok(verified._0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gadget_blueprint_proc_macro_core::ThresholdBound;
+
+    const DEFINITION: &str = r#"{
+        "metadata": { "name": "report_service_health", "description": null },
+        "params": [],
+        "result": [],
+        "report_type": "qos",
+        "interval": 60,
+        "metric_thresholds": [
+            { "metric": "uptime", "bound": "min", "value": 99 },
+            { "metric": "error_rate", "bound": "max", "value": 5 }
+        ],
+        "verifier": "none"
+    }"#;
+
+    fn report() -> QosReport {
+        QosReport::from_definition(DEFINITION, |metrics: &Metrics| {
+            let uptime = metrics.get::<u8>("uptime");
+            async move { uptime.map_err(|e| e.to_string()).into_report() }
+        })
+        .unwrap()
+    }
+
+    fn metrics(uptime: u64, error_rate: u64) -> Metrics {
+        Metrics(Snapshot::from([
+            ("uptime".to_string(), uptime),
+            ("error_rate".to_string(), error_rate),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn reports_only_violations() {
+        let report = report();
+        assert_eq!(report.interval(), Duration::from_secs(60));
+
+        assert!(report
+            .evaluate(1, &metrics(100, 0))
+            .await
+            .unwrap()
+            .is_none());
+
+        let violation = report.evaluate(1, &metrics(90, 7)).await.unwrap().unwrap();
+        assert_eq!(violation.report, "report_service_health");
+        assert_eq!(violation.output, b"90");
+        assert_eq!(
+            violation
+                .violations
+                .iter()
+                .map(|v| (v.threshold.bound, v.value))
+                .collect::<Vec<_>>(),
+            [
+                (ThresholdBound::Min, Some(90)),
+                (ThresholdBound::Max, Some(7))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_metrics_are_violations() {
+        let metrics = Metrics(Snapshot::from([("uptime".to_string(), 100)]));
+        let violation = report().evaluate(1, &metrics).await.unwrap().unwrap();
+        assert_eq!(violation.violations.len(), 1);
+        assert_eq!(violation.violations[0].threshold.metric, "error_rate");
+        assert_eq!(violation.violations[0].value, None);
+    }
+
+    #[tokio::test]
+    async fn rejected_reports_are_not_submitted() {
+        struct Reject;
+
+        #[async_trait::async_trait]
+        impl ReportVerifier for Reject {
+            async fn verify(&self, _report: &QosViolation) -> Result<bool, QosError> {
+                Ok(false)
+            }
+        }
+
+        let report = report().verifier(Reject);
+        assert!(report.evaluate(1, &metrics(90, 0)).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_job_reports() {
+        let definition = DEFINITION.replace("\"qos\"", "\"job\"");
+        let result =
+            QosReport::from_definition(&definition, |_: &Metrics| async { Ok(Vec::new()) });
+        assert!(matches!(result, Err(QosError::Definition(_))));
+    }
+}
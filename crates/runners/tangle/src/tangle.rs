@@ -1,10 +1,11 @@
 use crate::error::TangleError;
 use futures::future::select_ok;
+use gadget_benchmarking::BenchmarkReport;
 use gadget_clients::tangle;
 use gadget_config::{GadgetConfiguration, ProtocolSettings};
 use gadget_crypto::sp_core::{SpEcdsa, SpSr25519};
 use gadget_keystore::backends::Backend;
-use gadget_keystore::{Keystore, KeystoreConfig};
+use gadget_keystore::Keystore;
 use gadget_runner_core::config::BlueprintConfig;
 use gadget_runner_core::error::{RunnerError as Error, RunnerError};
 use gadget_std::string::ToString;
@@ -31,6 +32,35 @@ impl From<TanglePriceTargets> for PriceTargets {
     }
 }
 
+impl PriceTargets {
+    /// Price targets for the resources used by a blueprint's benchmarks
+    ///
+    /// `unit_prices` holds the price of a single unit of each resource: a core for `cpu`, and a MiB
+    /// for `mem` and the storage targets. Each target is the price of the most any benchmark used
+    /// of that resource, with memory taken from the peak memory and storage from the bytes written
+    /// to disk, rounded up to the next MiB.
+    ///
+    /// The report is written by `cargo tangle blueprint bench`, and can be read with
+    /// [`BenchmarkReport::load`].
+    #[must_use]
+    pub fn from_benchmarks(report: &BenchmarkReport, unit_prices: &PriceTargets) -> Self {
+        const MIB: u64 = 1024 * 1024;
+
+        let cores = u64::try_from(report.max_cores()).unwrap_or(u64::MAX);
+        let memory = report.max_peak_memory().div_ceil(MIB);
+        let storage = report.max_disk_written().div_ceil(MIB);
+
+        let unit_prices = &unit_prices.0;
+        Self(TanglePriceTargets {
+            cpu: unit_prices.cpu.saturating_mul(cores),
+            mem: unit_prices.mem.saturating_mul(memory),
+            storage_hdd: unit_prices.storage_hdd.saturating_mul(storage),
+            storage_ssd: unit_prices.storage_ssd.saturating_mul(storage),
+            storage_nvme: unit_prices.storage_nvme.saturating_mul(storage),
+        })
+    }
+}
+
 impl Default for PriceTargets {
     fn default() -> Self {
         Self(TanglePriceTargets {
@@ -73,11 +103,10 @@ pub async fn requires_registration_impl(env: &GadgetConfiguration) -> Result<boo
     let client = get_client(env.ws_rpc_endpoint.as_str(), env.http_rpc_endpoint.as_str()).await?;
 
     // TODO: Improve key fetching logic
-    let keystore_path = env.clone().keystore_uri;
-    let keystore_config = KeystoreConfig::new()
-        .in_memory(false)
-        .fs_root(keystore_path);
-    let keystore = Keystore::new(keystore_config).unwrap();
+    let keystore_config = env
+        .keystore_config()
+        .map_err(|e| TangleError::Config(e.to_string()))?;
+    let keystore = Keystore::new(keystore_config).map_err(TangleError::from)?;
 
     // TODO: Key IDs
     let sr25519_key = keystore
@@ -115,11 +144,10 @@ pub async fn register_impl(
     let client = get_client(env.ws_rpc_endpoint.as_str(), env.http_rpc_endpoint.as_str()).await?;
 
     // TODO: Improve key fetching logic
-    let keystore_path = env.clone().keystore_uri;
-    let keystore_config = KeystoreConfig::new()
-        .in_memory(false)
-        .fs_root(keystore_path);
-    let keystore = Keystore::new(keystore_config).unwrap();
+    let keystore_config = env
+        .keystore_config()
+        .map_err(|e| TangleError::Config(e.to_string()))?;
+    let keystore = Keystore::new(keystore_config).map_err(TangleError::from)?;
 
     // TODO: Key IDs
     let sr25519_key = keystore
@@ -41,8 +41,11 @@ pub use gadget_event_listeners as event_listeners;
 #[cfg(feature = "macros")]
 mod macros_feat {
     pub use gadget_macros as macros;
+    pub use gadget_macros::benchmark;
     pub use gadget_macros::job;
     pub use gadget_macros::main;
+    pub use gadget_macros::report;
+    pub use gadget_macros::BlueprintField;
 }
 #[cfg(feature = "macros")]
 pub use macros_feat::*;
@@ -53,6 +56,9 @@ pub use gadget_crypto as crypto;
 /// Structured logging facilities
 pub use gadget_logging as logging;
 
+/// Metrics, including the registry `QoS` reports are evaluated against
+pub use gadget_metrics as metrics;
+
 /// Blueprint execution and runtime utilities
 pub use gadget_runners as runners;
 